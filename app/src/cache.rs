@@ -0,0 +1,72 @@
+use edh_tourn::Tournament;
+
+/// A value computed from the tournament, kept until the tournament's snapshot changes
+#[derive(Clone, Debug)]
+pub struct Cached<T>(Option<(usize, T)>);
+
+impl<T> Default for Cached<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Cached<T> {
+    /// The value, if it was computed from the tournament as it is now
+    #[must_use]
+    pub fn get(&self, tournament: &Tournament) -> Option<&T> {
+        self.0
+            .as_ref()
+            .filter(|(snapshot, _)| *snapshot == tournament.snapshot())
+            .map(|(_, value)| value)
+    }
+
+    /// Recomputes the value if the tournament changed since it was last computed
+    pub fn refresh<F>(&mut self, tournament: &Tournament, compute: F)
+    where
+        F: FnOnce(&Tournament) -> T,
+    {
+        if self.get(tournament).is_none() {
+            self.0 = Some((tournament.snapshot(), compute(tournament)));
+        }
+    }
+
+    /// Forgets the value, such as when another tournament with its own snapshots is loaded
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edh_tourn::Tournament;
+
+    use crate::cache::Cached;
+
+    #[test]
+    fn recomputes_only_after_changes() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(5, 3)?;
+        let mut cached = Cached::default();
+        let mut computed = 0;
+        let mut refresh = |cached: &mut Cached<usize>, tourn: &Tournament| {
+            cached.refresh(tourn, |t| {
+                computed += 1;
+                t.games().len()
+            });
+        };
+
+        refresh(&mut cached, &tourn);
+        refresh(&mut cached, &tourn);
+        assert_eq!(Some(&3), cached.get(&tourn));
+
+        tourn.delete_game(0)?;
+        assert_eq!(None, cached.get(&tourn));
+        refresh(&mut cached, &tourn);
+        assert_eq!(Some(&2), cached.get(&tourn));
+
+        cached.clear();
+        assert_eq!(None, cached.get(&tourn));
+        refresh(&mut cached, &tourn);
+        assert_eq!(3, computed);
+        Ok(())
+    }
+}
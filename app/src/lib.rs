@@ -3,9 +3,16 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use edh_tourn::Tournament;
-use iced::Task;
+use edh_tourn::{
+    Tournament,
+    command::{Command, CommandLog},
+};
+use iced::{
+    Subscription, Task,
+    keyboard::{self, Key},
+};
 
+pub mod cache;
 pub mod fonts;
 pub mod logic;
 #[cfg(feature = "dev")]
@@ -26,6 +33,8 @@ pub struct App {
     file: Option<PathBuf>,
     home: HomeState,
     scenes: Vec<Scene>,
+    /// Changes made to the tournament since it was opened, for undo and redo
+    history: CommandLog,
 }
 
 impl App {
@@ -35,30 +44,75 @@ impl App {
             && let Ok(file) = File::open("game.ron")
             && let Ok(tournament) = ron::de::from_reader(file)
         {
-            return Self {
+            let mut app = Self {
                 tournament,
                 file: Some(Path::new("game.ron").to_path_buf()),
 
                 ..Self::default()
             };
+            app.refresh_caches();
+            return app;
         }
 
-        Self::default()
+        let mut app = Self::default();
+        app.refresh_caches();
+        app
     }
 
     pub fn updater(&mut self, message: Message) -> Task<Message> {
-        match self.update(message) {
+        let task = match self.update(message) {
             Ok(task) => task,
             Err(res) => {
                 let msg = res.to_string();
                 self.error = Some(msg);
                 Task::none()
             }
+        };
+        self.refresh_caches();
+        task
+    }
+
+    /// Recomputes the values the screens keep from the tournament, if it changed
+    pub fn refresh_caches(&mut self) {
+        self.home.refresh(&self.tournament);
+        for scene in &mut self.scenes {
+            scene.refresh(&self.tournament);
+        }
+    }
+
+    /// Swaps in a tournament opened from elsewhere. Its changes can't be undone.
+    fn load(&mut self, tournament: Tournament) {
+        self.tournament = tournament;
+        self.history.clear();
+        // The loaded tournament counts its snapshots from 0 again
+        self.home.clear_caches();
+        for scene in &mut self.scenes {
+            scene.clear_caches();
         }
     }
 
+    pub fn subscription(&self) -> Subscription<Message> {
+        keyboard::listen().filter_map(|event| match event {
+            keyboard::Event::KeyPressed { key, modifiers, .. } if modifiers.command() => {
+                match key.as_ref() {
+                    Key::Character("z") if modifiers.shift() => Some(Message::Redo),
+                    Key::Character("z") => Some(Message::Undo),
+                    Key::Character("y") => Some(Message::Redo),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    }
+
     #[must_use]
     pub const fn tournament(&self) -> &Tournament {
         &self.tournament
     }
+
+    /// Changes the tournament through the command log, so that the change can be undone
+    pub fn execute(&mut self, command: Command) -> anyhow::Result<()> {
+        self.history.execute(&mut self.tournament, command)?;
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 pub mod file;
 
-use edh_tourn::Tournament;
+use edh_tourn::{Tournament, command::Command};
 use iced::Task;
 use opener::open_browser;
 
@@ -8,7 +8,12 @@ use crate::{
     App,
     logic::file::FileMessage,
     traits::HandleMessage,
-    view::{confirm::ConfirmPromptMessage, home::HomeMessage, player::ViewPlayerMessage},
+    view::{
+        calibration::CalibrationMessage, confirm::ConfirmPromptMessage,
+        constraints::ConstraintsMessage, game::EditGameMessage, head_to_head::HeadToHeadMessage,
+        history::HistoryMessage, home::HomeMessage, meta::MetaMessage, planner::PlannerMessage,
+        player::ViewPlayerMessage, settings::SettingsMessage, tuning::SuggestConfigMessage,
+    },
 };
 
 #[derive(Clone, Default)]
@@ -17,6 +22,8 @@ pub enum Message {
     None,
     ReloadTournament,
     RefreshTournament,
+    Undo,
+    Redo,
     Batch(Vec<Self>),
     OpenLink(String),
     Error(Option<String>),
@@ -24,6 +31,15 @@ pub enum Message {
     LoadTournament(Box<Tournament>),
     Home(HomeMessage),
     ViewPlayer(ViewPlayerMessage),
+    History(HistoryMessage),
+    EditGame(EditGameMessage),
+    SuggestConfig(SuggestConfigMessage),
+    Calibration(CalibrationMessage),
+    HeadToHead(HeadToHeadMessage),
+    Meta(MetaMessage),
+    Planner(PlannerMessage),
+    Constraints(ConstraintsMessage),
+    Settings(SettingsMessage),
     ConfirmationPrompt(ConfirmPromptMessage),
 }
 
@@ -33,7 +49,7 @@ impl Message {
         Ok(Task::none())
     }
 
-    fn handle_error_fn<T, E: ToString, M: Into<Self>>(
+    pub(crate) fn handle_error_fn<T, E: ToString, M: Into<Self>>(
         on_ok: impl Fn(T) -> M,
     ) -> impl Fn(Result<T, E>) -> Self {
         move |result: Result<T, E>| match result {
@@ -42,7 +58,9 @@ impl Message {
         }
     }
 
-    fn handle_option_fn<T, M: Into<Self>>(on_some: impl Fn(T) -> M) -> impl Fn(Option<T>) -> Self {
+    pub(crate) fn handle_option_fn<T, M: Into<Self>>(
+        on_some: impl Fn(T) -> M,
+    ) -> impl Fn(Option<T>) -> Self {
         move |option: Option<T>| option.map_or(Self::None, |value| on_some(value).into())
     }
 }
@@ -68,19 +86,40 @@ impl HandleMessage<Message> for App {
             }
             Message::File(file_message) => self.update(file_message),
             Message::LoadTournament(tournament) => {
-                self.tournament = *tournament;
+                self.load(*tournament);
                 Message::done()
             }
             Message::Home(msg) => self.update(msg),
             Message::ViewPlayer(msg) => self.update(msg),
+            Message::History(msg) => self.update(msg),
+            Message::EditGame(msg) => self.update(msg),
+            Message::SuggestConfig(msg) => self.update(msg),
+            Message::Calibration(msg) => self.update(msg),
+            Message::HeadToHead(msg) => self.update(msg),
+            Message::Meta(msg) => self.update(msg),
+            Message::Planner(msg) => self.update(msg),
+            Message::Constraints(msg) => self.update(msg),
+            Message::Settings(msg) => self.update(msg),
             Message::ReloadTournament => {
                 self.tournament.reload()?;
                 Ok(Task::none())
             }
             Message::RefreshTournament => {
-                self.tournament = self.tournament.into_fresh()?;
+                let fresh = self.tournament.into_fresh()?;
+                self.execute(Command::Replace(Box::new(fresh)))?;
                 Ok(Task::none())
             }
+            // Open scenes keep game indices and reports from the tournament as it was, so undo
+            // and redo only run from the home screen
+            Message::Undo | Message::Redo if !self.scenes.is_empty() => Message::done(),
+            Message::Undo => {
+                self.history.undo(&mut self.tournament)?;
+                Message::done()
+            }
+            Message::Redo => {
+                self.history.redo(&mut self.tournament)?;
+                Message::done()
+            }
             Message::ConfirmationPrompt(msg) => self.update(msg),
         }
     }
@@ -94,9 +133,14 @@ impl From<()> for Message {
 
 #[cfg(test)]
 mod tests {
-    use edh_tourn::Tournament;
+    use edh_tourn::{Tournament, command::Command};
 
-    use crate::{App, logic::Message, traits::HandleMessage};
+    use crate::{
+        App,
+        logic::{Message, file::FileMessage},
+        traits::HandleMessage,
+        view::history::HistoryMessage,
+    };
 
     #[test]
     fn error_sets_correctly() {
@@ -121,6 +165,45 @@ mod tests {
         assert_eq!(app.tournament, tourn);
     }
 
+    #[test]
+    fn undo_and_redo_new_tournament() {
+        let mut app = App::default();
+        let tourn = Tournament::sample_game();
+        let _ = app
+            .update(Message::LoadTournament(tourn.clone().into()))
+            .unwrap();
+        let _ = app.update(FileMessage::New).unwrap();
+        assert!(app.tournament.players().is_empty());
+
+        let _ = app.update(Message::Undo).unwrap();
+        assert_eq!(tourn.games(), app.tournament.games());
+        assert!(!app.history.can_undo());
+
+        let _ = app.update(Message::Redo).unwrap();
+        assert!(app.tournament.players().is_empty());
+    }
+
+    #[test]
+    fn undo_is_ignored_while_a_scene_is_open() {
+        let mut app = App::default();
+        let _ = app
+            .update(Message::LoadTournament(Tournament::sample_game().into()))
+            .unwrap();
+        app.execute(Command::DeleteGame(0)).unwrap();
+        let games = app.tournament.games().len();
+
+        let _ = app.update(HistoryMessage::Open).unwrap();
+        let _ = app.update(Message::Undo).unwrap();
+        assert_eq!(games, app.tournament.games().len());
+
+        app.scenes.clear();
+        let _ = app.update(Message::Undo).unwrap();
+        assert_eq!(games + 1, app.tournament.games().len());
+        let _ = app.update(HistoryMessage::Open).unwrap();
+        let _ = app.update(Message::Redo).unwrap();
+        assert_eq!(games + 1, app.tournament.games().len());
+    }
+
     mod handle_error_fn {
         use crate::logic::Message;
 
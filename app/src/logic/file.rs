@@ -1,7 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::anyhow;
-use edh_tourn::{Tournament, compat::v1::TournamentCompatV1};
+use edh_tourn::{Tournament, command::Command, compat::v1::TournamentCompatV1};
 use iced::{Task, futures::FutureExt};
 use rfd::AsyncFileDialog;
 use serde::{Deserialize, Serialize};
@@ -77,12 +77,12 @@ impl HandleMessage<FileMessage> for App {
                 Message::done()
             }
             FileMessage::LoadTournamentFromFile(path_buf, tournament) => {
-                self.tournament = *tournament;
+                self.load(*tournament);
                 self.file = Some(path_buf);
                 Message::done()
             }
             FileMessage::New => {
-                self.tournament = Tournament::default();
+                self.execute(Command::Replace(Box::default()))?;
                 self.file = None;
                 Message::done()
             }
@@ -6,6 +6,7 @@ use iced::application;
 
 pub fn main() -> iced::Result {
     application(App::boot, App::updater, App::app_view)
+        .subscription(App::subscription)
         .font(jetbrains_mono_bytes())
         .default_font(default_font())
         .run()
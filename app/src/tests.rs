@@ -1,6 +1,21 @@
+use edh_tourn::Tournament;
+use itertools::Itertools;
+
 use crate::{App, traits::HandleMessage};
 
 impl App {
+    /// App over a generated tournament, along with its deck ids in order
+    #[must_use]
+    pub fn test_with_tournament(players: usize, games: usize) -> (Self, Vec<u32>) {
+        let tournament = Tournament::generate_tournament(players, games).unwrap();
+        let ids = tournament.players().keys().copied().sorted().collect_vec();
+        let app = Self {
+            tournament,
+            ..Self::default()
+        };
+        (app, ids)
+    }
+
     pub fn test_update<T>(&mut self, msg: T) -> anyhow::Result<()>
     where
         Self: HandleMessage<T>,
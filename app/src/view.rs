@@ -1,8 +1,21 @@
+pub mod calibration;
 pub mod config_matchmaker;
 pub mod confirm;
+pub mod constraints;
+pub mod game;
+pub mod head_to_head;
+pub mod history;
 pub mod home;
+pub mod meta;
+pub mod planner;
 pub mod player;
+pub mod seats;
+pub mod settings;
+pub mod tuning;
 
+use core::num::ParseIntError;
+
+use edh_tourn::Tournament;
 use iced::{
     Alignment, Element, Length,
     alignment::Horizontal,
@@ -13,14 +26,43 @@ use crate::{
     App,
     logic::Message,
     traits::View,
-    view::{confirm::ConfirmPrompt, player::ViewPlayerScene},
+    view::{
+        calibration::CalibrationScene, confirm::ConfirmPrompt, constraints::ConstraintsScene,
+        game::EditGameScene, head_to_head::HeadToHeadScene, history::HistoryScene, meta::MetaScene,
+        planner::PlannerScene, player::ViewPlayerScene, settings::SettingsScene,
+        tuning::SuggestConfigScene,
+    },
 };
 
 pub enum Scene {
     Player(ViewPlayerScene),
+    History(HistoryScene),
+    EditGame(Box<EditGameScene>),
+    SuggestConfig(SuggestConfigScene),
+    Calibration(CalibrationScene),
+    HeadToHead(HeadToHeadScene),
+    Meta(MetaScene),
+    Planner(PlannerScene),
+    Constraints(ConstraintsScene),
+    Settings(SettingsScene),
     Confirm(ConfirmPrompt),
 }
 
+impl Scene {
+    /// Recomputes what the scene keeps from the tournament, if it changed
+    pub(crate) fn refresh(&mut self, tournament: &Tournament) {
+        if let Self::Player(scene) = self {
+            scene.refresh(tournament);
+        }
+    }
+
+    pub(crate) fn clear_caches(&mut self) {
+        if let Self::Player(scene) = self {
+            scene.clear_caches();
+        }
+    }
+}
+
 impl App {
     #[must_use]
     pub fn app_view(&self) -> Element<'_, Message> {
@@ -31,6 +73,15 @@ impl App {
             || self.view(&self.home),
             |scene| match scene {
                 Scene::Player(scene) => self.view(scene),
+                Scene::History(scene) => self.view(scene),
+                Scene::EditGame(scene) => self.view(scene.as_ref()),
+                Scene::SuggestConfig(scene) => self.view(scene),
+                Scene::Calibration(scene) => self.view(scene),
+                Scene::HeadToHead(scene) => self.view(scene),
+                Scene::Meta(scene) => self.view(scene),
+                Scene::Planner(scene) => self.view(scene),
+                Scene::Constraints(scene) => self.view(scene),
+                Scene::Settings(scene) => self.view(scene),
                 Scene::Confirm(prompt) => self.view(prompt),
             },
         );
@@ -39,6 +90,25 @@ impl App {
     }
 }
 
+/// Formats a finishing place as "1st", "2nd", and so on
+#[must_use]
+pub fn ordinal(place: usize) -> String {
+    let suffix = match (place % 10, place % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{place}{suffix}")
+}
+
+/// Reads a number typed into a field, where a blank field has no number
+pub(crate) fn parse_number(field: &str) -> Result<Option<u32>, ParseIntError> {
+    let field = field.trim();
+    (!field.is_empty()).then(|| field.parse()).transpose()
+}
+
 fn error_screen(error: &str) -> Element<'_, Message> {
     container(
         column![
@@ -0,0 +1,232 @@
+use core::fmt::Display;
+use std::path::PathBuf;
+
+use edh_tourn::{
+    calibration::{Calibration, CalibrationBucket, CalibrationReport},
+    player::RegisteredPlayer,
+};
+use iced::{
+    Element, Length, Task,
+    futures::FutureExt,
+    widget::{button, column, container, pick_list, row, space, table, text},
+};
+use itertools::Itertools;
+use rfd::AsyncFileDialog;
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::Scene,
+};
+
+/// Ranges of expected result the report is split into
+const BUCKETS: usize = 10;
+
+/// Shows how well the expected results matched the outcomes, overall or for one deck
+#[derive(Debug)]
+pub struct CalibrationScene {
+    deck: Option<u32>,
+    report: CalibrationReport,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CalibrationMessage {
+    Open,
+    SetDeck(Option<u32>),
+    ExportCsv,
+    SaveCsv(PathBuf),
+    Close,
+}
+
+impl From<CalibrationMessage> for Message {
+    fn from(value: CalibrationMessage) -> Self {
+        Self::Calibration(value)
+    }
+}
+
+impl HandleMessage<CalibrationMessage> for App {
+    fn update(&mut self, msg: CalibrationMessage) -> anyhow::Result<Task<Message>> {
+        match msg {
+            CalibrationMessage::Open => {
+                self.scenes.push(Scene::Calibration(CalibrationScene {
+                    deck: None,
+                    report: self.tournament.calibration(BUCKETS),
+                }));
+            }
+            CalibrationMessage::SetDeck(deck) => {
+                if let Some(Scene::Calibration(scene)) = self.scenes.last_mut() {
+                    scene.deck = deck;
+                }
+            }
+            CalibrationMessage::ExportCsv => {
+                return Ok(Task::perform(
+                    AsyncFileDialog::new()
+                        .add_filter("csv", &["csv"])
+                        .set_directory(".")
+                        .set_title("Export Calibration")
+                        .save_file()
+                        .then(async |res| res.map(|handle| handle.path().to_path_buf())),
+                    Message::handle_option_fn(CalibrationMessage::SaveCsv),
+                ));
+            }
+            CalibrationMessage::SaveCsv(path) => {
+                let Some(Scene::Calibration(scene)) = self.scenes.last() else {
+                    return Message::done();
+                };
+                // The report that's on screen, rather than one worked out again
+                let csv = scene.report.to_csv(&self.tournament);
+                return Ok(Task::perform(
+                    async_fs::write(path, csv),
+                    Message::handle_error_fn(Message::from),
+                ));
+            }
+            CalibrationMessage::Close => {
+                if let Some(Scene::Calibration(_)) = self.scenes.last() {
+                    self.scenes.pop();
+                }
+            }
+        }
+        Message::done()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum DeckOption<'a> {
+    All,
+    Deck(RegisteredPlayer<'a>),
+}
+
+impl DeckOption<'_> {
+    const fn id(&self) -> Option<u32> {
+        match self {
+            Self::All => None,
+            Self::Deck(player) => Some(player.id()),
+        }
+    }
+}
+
+impl Display for DeckOption<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::All => write!(f, "All Decks"),
+            Self::Deck(player) => write!(f, "{player}"),
+        }
+    }
+}
+
+impl View<CalibrationScene> for App {
+    fn view<'a>(&'a self, scene: &'a CalibrationScene) -> Element<'a, Message> {
+        let options = core::iter::once(DeckOption::All)
+            .chain(
+                scene
+                    .report
+                    .decks
+                    .keys()
+                    .filter_map(|id| self.tournament.get_registered_player(*id).ok())
+                    .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
+                    .map(DeckOption::Deck),
+            )
+            .collect_vec();
+        let picked = options
+            .iter()
+            .find(|option| option.id() == scene.deck)
+            .cloned();
+
+        let menu_bar = row![
+            pick_list(options, picked, |option| {
+                CalibrationMessage::SetDeck(option.id()).into()
+            }),
+            space().width(Length::Fill),
+            button(text("Export CSV")).on_press(CalibrationMessage::ExportCsv.into()),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(CalibrationMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Prediction Calibration")
+            .width(Length::Fill)
+            .center()
+            .size(50);
+
+        let calibration: Option<&Calibration> =
+            scene.deck.map_or(Some(&scene.report.overall), |id| {
+                scene.report.decks.get(&id)
+            });
+        let Some(calibration) = calibration else {
+            return container(column![menu_bar, title].spacing(20)).into();
+        };
+
+        let rows = calibration
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.predictions > 0)
+            .copied();
+        let percent = |value: f64| format!("{:.1}%", value * 100.0);
+        let buckets = table(
+            [
+                table::column("Expected Range", |b: CalibrationBucket| {
+                    text(format!("{:.0}–{:.0}%", b.low * 100.0, b.high * 100.0))
+                }),
+                table::column("Predictions", |b: CalibrationBucket| text(b.predictions)),
+                table::column("Mean Expected", move |b: CalibrationBucket| {
+                    text(percent(b.expected))
+                }),
+                table::column("Observed", move |b: CalibrationBucket| {
+                    text(percent(b.observed))
+                }),
+                table::column("Brier", |b: CalibrationBucket| {
+                    text(format!("{:.4}", b.brier))
+                }),
+                table::column("Log-loss", |b: CalibrationBucket| {
+                    text(format!("{:.4}", b.log_loss))
+                }),
+            ],
+            rows,
+        );
+
+        let total = &calibration.total;
+        let summary = text(format!(
+            "{} predictions, Brier {:.4}, log-loss {:.4}. Expected {} on average, won {}.",
+            total.predictions,
+            total.brier,
+            total.log_loss,
+            percent(total.expected),
+            percent(total.observed),
+        ))
+        .size(12);
+
+        container(
+            column![menu_bar, title, buckets, summary]
+                .spacing(20)
+                .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use crate::{App, view::Scene, view::calibration::CalibrationMessage};
+
+    #[test]
+    fn open_and_pick_deck() {
+        let (mut app, ids) = App::test_with_tournament(6, 20);
+        let id = ids[0];
+
+        app.test_update(CalibrationMessage::Open).unwrap();
+        app.test_update(CalibrationMessage::SetDeck(Some(id)))
+            .unwrap();
+        let Some(Scene::Calibration(scene)) = app.scenes.last() else {
+            panic!("Calibration should be open");
+        };
+        assert_eq!(Some(id), scene.deck);
+        assert_eq!(app.tournament.calibration(10), scene.report);
+
+        app.test_update(CalibrationMessage::Close).unwrap();
+        assert!(app.scenes.is_empty());
+    }
+}
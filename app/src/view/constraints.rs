@@ -0,0 +1,229 @@
+use edh_tourn::{
+    command::Command, matches::constraints::MatchConstraints, player::RegisteredPlayer,
+};
+use iced::{
+    Element, Length, Task,
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, space, text},
+};
+use itertools::Itertools;
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::Scene,
+};
+
+/// Edits the hard rules the matchmaker and pod planner follow
+#[derive(Debug)]
+pub struct ConstraintsScene {
+    constraints: MatchConstraints,
+    /// Decks picked for a new exclusion
+    exclusion: [Option<u32>; 2],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstraintsMessage {
+    Open,
+    SetOneDeckPerOwner(bool),
+    SetRequired(u32, bool),
+    SetExclusionDeck(usize, u32),
+    AddExclusion,
+    RemoveExclusion(u32, u32),
+    Save,
+    Close,
+}
+
+impl From<ConstraintsMessage> for Message {
+    fn from(value: ConstraintsMessage) -> Self {
+        Self::Constraints(value)
+    }
+}
+
+impl HandleMessage<ConstraintsMessage> for App {
+    fn update(&mut self, msg: ConstraintsMessage) -> anyhow::Result<Task<Message>> {
+        if msg == ConstraintsMessage::Open {
+            self.scenes.push(Scene::Constraints(ConstraintsScene {
+                constraints: self.tournament.constraints().clone(),
+                exclusion: [None; 2],
+            }));
+            return Message::done();
+        }
+        let Some(Scene::Constraints(scene)) = self.scenes.last_mut() else {
+            return Message::done();
+        };
+
+        match msg {
+            ConstraintsMessage::Open => {}
+            ConstraintsMessage::SetOneDeckPerOwner(value) => {
+                scene.constraints.set_one_deck_per_owner(value);
+            }
+            ConstraintsMessage::SetRequired(id, required) => {
+                scene.constraints.set_required(id, required);
+            }
+            ConstraintsMessage::SetExclusionDeck(index, id) => {
+                if let Some(deck) = scene.exclusion.get_mut(index) {
+                    *deck = Some(id);
+                }
+            }
+            ConstraintsMessage::AddExclusion => {
+                if let [Some(a), Some(b)] = scene.exclusion {
+                    scene.constraints.add_exclusion(a, b);
+                    scene.exclusion = [None; 2];
+                }
+            }
+            ConstraintsMessage::RemoveExclusion(a, b) => {
+                scene.constraints.remove_exclusion(a, b);
+            }
+            ConstraintsMessage::Save => {
+                let constraints = scene.constraints.clone();
+                self.execute(Command::SetConstraints(constraints))?;
+                self.scenes.pop();
+            }
+            ConstraintsMessage::Close => {
+                self.scenes.pop();
+            }
+        }
+        Message::done()
+    }
+}
+
+impl View<ConstraintsScene> for App {
+    fn view<'a>(&'a self, scene: &'a ConstraintsScene) -> Element<'a, Message> {
+        let name = |id: u32| {
+            self.tournament
+                .get_player_name(&id)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let decks = || {
+            self.tournament
+                .get_active_players()
+                .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
+        };
+        let picked =
+            |id: Option<u32>| id.and_then(|id| self.tournament.get_registered_player(id).ok());
+
+        let menu_bar = row![
+            space().width(Length::Fill),
+            button(text("Save")).on_press(ConstraintsMessage::Save.into()),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(ConstraintsMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Match Constraints")
+            .width(Length::Fill)
+            .center()
+            .size(50);
+
+        let owners = column![
+            checkbox(scene.constraints.one_deck_per_owner())
+                .label("One deck per owner")
+                .on_toggle(|value| ConstraintsMessage::SetOneDeckPerOwner(value).into()),
+            text("Decks with the same recorded owner are never put in the same pod").size(12),
+        ]
+        .spacing(6);
+
+        let required = column![
+            text("Must Play Next").size(24),
+            column(scene.constraints.required().iter().map(|id| {
+                row![
+                    text(name(*id)).width(250),
+                    button(text("Remove"))
+                        .style(button::secondary)
+                        .on_press(ConstraintsMessage::SetRequired(*id, false).into()),
+                ]
+                .spacing(10)
+                .into()
+            }))
+            .spacing(6),
+            pick_list(
+                decks()
+                    .filter(|player| !scene.constraints.is_required(player.id()))
+                    .collect_vec(),
+                None::<RegisteredPlayer<'_>>,
+                |player| ConstraintsMessage::SetRequired(player.id(), true).into(),
+            )
+            .placeholder("Add a deck..."),
+        ]
+        .spacing(10);
+
+        let [first, second] = scene.exclusion;
+        let exclusion_pick = |index: usize, id: Option<u32>| {
+            pick_list(decks().collect_vec(), picked(id), move |player| {
+                ConstraintsMessage::SetExclusionDeck(index, player.id()).into()
+            })
+            .placeholder("Deck...")
+        };
+        let can_add = first.zip(second).is_some_and(|(a, b)| a != b);
+        let exclusions = column![
+            text("Never in the Same Pod").size(24),
+            column(scene.constraints.exclusions().iter().map(|(a, b)| {
+                row![
+                    text(format!("{} and {}", name(*a), name(*b))).width(250),
+                    button(text("Remove"))
+                        .style(button::secondary)
+                        .on_press(ConstraintsMessage::RemoveExclusion(*a, *b).into()),
+                ]
+                .spacing(10)
+                .into()
+            }))
+            .spacing(6),
+            row![
+                exclusion_pick(0, first),
+                exclusion_pick(1, second),
+                button(text("Add"))
+                    .on_press_maybe(can_add.then_some(ConstraintsMessage::AddExclusion.into())),
+            ]
+            .spacing(10),
+        ]
+        .spacing(10);
+
+        container(
+            column![
+                menu_bar,
+                title,
+                scrollable(column![owners, required, exclusions].spacing(30)).height(Length::Fill),
+            ]
+            .spacing(20)
+            .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use crate::{App, view::constraints::ConstraintsMessage};
+
+    #[test]
+    fn edit_and_save_constraints() {
+        let (mut app, ids) = App::test_with_tournament(6, 10);
+
+        app.test_updates([
+            ConstraintsMessage::Open,
+            ConstraintsMessage::SetOneDeckPerOwner(true),
+            ConstraintsMessage::SetRequired(ids[5], true),
+            ConstraintsMessage::SetExclusionDeck(0, ids[1]),
+            ConstraintsMessage::SetExclusionDeck(1, ids[0]),
+            ConstraintsMessage::AddExclusion,
+        ])
+        .unwrap();
+        assert!(app.tournament.constraints().is_empty());
+
+        app.test_update(ConstraintsMessage::Save).unwrap();
+        assert!(app.scenes.is_empty());
+        let constraints = app.tournament.constraints();
+        assert!(constraints.one_deck_per_owner());
+        assert_eq!(&[ids[5]], constraints.required());
+        assert_eq!(&[(ids[0], ids[1])], constraints.exclusions());
+        assert_eq!(
+            Some(ids[5]),
+            app.tournament.rank_least_played(ids[0]).unwrap().next()
+        );
+    }
+}
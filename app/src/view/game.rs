@@ -0,0 +1,466 @@
+use std::borrow::ToOwned;
+
+use anyhow::Context;
+
+use edh_tourn::{
+    Tournament,
+    command::Command,
+    error::TournamentError,
+    game::{
+        MAX_POD_SIZE, MIN_POD_SIZE, details::WinCondition, entry::GameEntry, outcome::GameOutcome,
+    },
+};
+use iced::{
+    Element, Length,
+    alignment::Vertical,
+    widget::{button, column, container, pick_list, row, space, text, text_input},
+};
+use itertools::Itertools;
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::{
+        Scene, ordinal, parse_number,
+        seats::{OutcomeChoice, OutcomeOption, SeatEditor},
+    },
+};
+
+/// Edits a game of the history, or a new game to insert into it, without moving it
+#[derive(Debug)]
+pub struct EditGameScene {
+    /// Game being edited, or `None` when inserting a new game
+    gid: Option<usize>,
+    /// Position of the game in the history
+    index: usize,
+    pod: SeatEditor,
+    session: String,
+    turns: String,
+    /// In minutes
+    duration: String,
+    win_condition: Option<WinCondition>,
+    notes: String,
+    /// The game as it was opened, for the parts that can't be edited here
+    original: GameEntry,
+}
+
+impl EditGameScene {
+    fn new(gid: Option<usize>, index: usize, original: GameEntry) -> Self {
+        let mut pod = if original.players().is_empty() {
+            SeatEditor::default()
+        } else {
+            SeatEditor::new(original.players().iter().copied().map(Some).collect())
+        };
+        let (outcome, draw_excluded) = match original.outcome() {
+            GameOutcome::Win(winner) => (OutcomeChoice::Winner(*winner), Vec::new()),
+            GameOutcome::Draw(credited) => (
+                OutcomeChoice::Draw,
+                original
+                    .players()
+                    .iter()
+                    .positions(|id| !credited.contains(id))
+                    .collect(),
+            ),
+            GameOutcome::NoResult => (OutcomeChoice::NoResult, Vec::new()),
+        };
+        pod.first = original.first();
+        pod.outcome = (!original.players().is_empty()).then_some(outcome);
+        pod.draw_excluded = draw_excluded;
+        let details = original.details();
+
+        Self {
+            gid,
+            index,
+            pod,
+            session: details.session().unwrap_or_default().to_owned(),
+            turns: details.turns().map(|t| t.to_string()).unwrap_or_default(),
+            duration: details
+                .duration()
+                .map(|d| d.to_string())
+                .unwrap_or_default(),
+            win_condition: details.win_condition(),
+            notes: details.notes().to_owned(),
+            original,
+        }
+    }
+
+    fn open(tournament: &Tournament, gid: usize) -> Result<Self, TournamentError> {
+        let game = tournament
+            .games()
+            .get(gid)
+            .ok_or(TournamentError::GameNotFound(gid))?;
+        Ok(Self::new(Some(gid), gid, game.to_entry()))
+    }
+
+    fn insert(tournament: &Tournament, index: usize) -> Result<Self, TournamentError> {
+        if index > tournament.games().len() {
+            return Err(TournamentError::GameNotFound(index));
+        }
+        Ok(Self::new(None, index, GameEntry::default()))
+    }
+
+    /// Whether the game can be saved: every seat and the outcome are picked, and the turns and
+    /// duration are either blank or whole numbers
+    fn can_save(&self) -> bool {
+        self.pod.outcome.is_some()
+            && self.pod.seats.iter().all(Option::is_some)
+            && parse_number(&self.turns).is_ok()
+            && parse_number(&self.duration).is_ok()
+    }
+
+    /// The edited game. Placements, pilots and eliminations are kept from the original game where
+    /// they still fit it.
+    fn entry(&self) -> anyhow::Result<GameEntry> {
+        let players = self
+            .pod
+            .players()
+            .ok_or(TournamentError::NotEnoughPlayers)?;
+        let outcome = self.pod.outcome().unwrap_or(GameOutcome::NoResult);
+
+        let mut details = self.original.details().clone();
+        let session = self.session.trim();
+        details.session = (!session.is_empty()).then(|| session.to_owned());
+        details.turns = parse_number(&self.turns).context("Turns must be a whole number")?;
+        details.duration =
+            parse_number(&self.duration).context("Minutes must be a whole number")?;
+        details.win_condition = self.win_condition;
+        self.notes.trim().clone_into(&mut details.notes);
+        details.pilots.retain(|deck, _| players.contains(deck));
+        details.eliminations.retain(|elimination| {
+            players.contains(&elimination.eliminated)
+                && !outcome.is_credited(elimination.eliminated)
+                && elimination
+                    .killer()
+                    .is_none_or(|killer| players.contains(&killer))
+        });
+
+        let placements = self.original.placements().to_vec();
+        let placements = if outcome.validate_placements(&players, &placements).is_ok() {
+            placements
+        } else {
+            Vec::new()
+        };
+
+        let entry = GameEntry::new(players, outcome)?;
+        let entry = match self.pod.first {
+            Some(first) => entry.with_first(first)?,
+            None => entry,
+        };
+        Ok(entry.with_placements(placements)?.with_details(details))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditGameMessage {
+    Open(usize),
+    Insert(usize),
+    SetPlayer(usize, Option<u32>),
+    SetFirst(Option<usize>),
+    SetOutcome(Option<OutcomeChoice>),
+    ToggleDrawSeat(usize),
+    SetSession(String),
+    SetTurns(String),
+    SetDuration(String),
+    SetWinCondition(Option<WinCondition>),
+    SetNotes(String),
+    AddSeat,
+    RemoveSeat,
+    SaveAndClose,
+    Close,
+}
+
+impl From<EditGameMessage> for Message {
+    fn from(value: EditGameMessage) -> Self {
+        Self::EditGame(value)
+    }
+}
+
+impl HandleMessage<EditGameMessage> for App {
+    fn update(&mut self, msg: EditGameMessage) -> anyhow::Result<iced::Task<Message>> {
+        match msg {
+            EditGameMessage::Open(gid) => {
+                let scene = EditGameScene::open(&self.tournament, gid)?;
+                self.scenes.push(Scene::EditGame(Box::new(scene)));
+                return Message::done();
+            }
+            EditGameMessage::Insert(index) => {
+                let scene = EditGameScene::insert(&self.tournament, index)?;
+                self.scenes.push(Scene::EditGame(Box::new(scene)));
+                return Message::done();
+            }
+            _ => {}
+        }
+
+        let Some(Scene::EditGame(scene)) = self.scenes.last_mut() else {
+            return Message::done();
+        };
+
+        match msg {
+            EditGameMessage::Open(_) | EditGameMessage::Insert(_) => {}
+            EditGameMessage::SetPlayer(seat, value) => scene.pod.set_player(seat, value),
+            EditGameMessage::SetFirst(first) => scene.pod.first = first,
+            EditGameMessage::SetOutcome(outcome) => scene.pod.outcome = outcome,
+            EditGameMessage::ToggleDrawSeat(seat) => scene.pod.toggle_draw_seat(seat),
+            EditGameMessage::SetSession(session) => scene.session = session,
+            EditGameMessage::SetTurns(turns) => scene.turns = turns,
+            EditGameMessage::SetDuration(duration) => scene.duration = duration,
+            EditGameMessage::SetWinCondition(condition) => scene.win_condition = condition,
+            EditGameMessage::SetNotes(notes) => scene.notes = notes,
+            EditGameMessage::AddSeat => {
+                scene.pod.add_seat();
+            }
+            EditGameMessage::RemoveSeat => {
+                scene.pod.remove_seat();
+            }
+            EditGameMessage::SaveAndClose => {
+                let command = match scene.gid {
+                    Some(gid) => Command::UpdateGame(gid, scene.entry()?),
+                    None => Command::InsertGame(scene.index, scene.entry()?),
+                };
+                self.execute(command)?;
+                self.scenes.pop();
+            }
+            EditGameMessage::Close => {
+                self.scenes.pop();
+            }
+        }
+        Message::done()
+    }
+}
+
+impl View<EditGameScene> for App {
+    fn view<'a>(&'a self, scene: &'a EditGameScene) -> Element<'a, Message> {
+        let menu_bar = row![
+            space().width(Length::Fill),
+            button(text("Cancel")).on_press(EditGameMessage::Close.into()),
+            button(text("Save")).on_press_maybe(
+                scene
+                    .can_save()
+                    .then_some(EditGameMessage::SaveAndClose.into())
+            ),
+        ]
+        .spacing(20);
+
+        let title = text(scene.gid.map_or_else(
+            || format!("Insert Game {}", scene.index + 1),
+            |gid| format!("Edit Game {}", gid + 1),
+        ))
+        .width(Length::Fill)
+        .center()
+        .size(50);
+
+        let players = self
+            .tournament
+            .get_registered_players()
+            .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
+            .collect_vec();
+
+        let seats = column((0..scene.pod.seats.len()).map(|seat| {
+            let entry = scene
+                .pod
+                .seats
+                .get(seat)
+                .copied()
+                .flatten()
+                .and_then(|id| self.tournament.get_registered_player(id).ok());
+            let style = if scene.pod.first == Some(seat) {
+                button::primary
+            } else {
+                button::secondary
+            };
+            row![
+                text(ordinal(seat + 1)).width(35),
+                pick_list(players.clone(), entry, move |option| {
+                    EditGameMessage::SetPlayer(seat, Some(option.id())).into()
+                })
+                .width(Length::Fill),
+                button(text("First")).style(style).on_press(
+                    EditGameMessage::SetFirst((scene.pod.first != Some(seat)).then_some(seat))
+                        .into()
+                ),
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+            .into()
+        }))
+        .spacing(10);
+
+        let seat_buttons = row![
+            button("-").on_press_maybe(
+                (scene.pod.seats.len() > MIN_POD_SIZE)
+                    .then_some(EditGameMessage::RemoveSeat.into())
+            ),
+            button("+").on_press_maybe(
+                (scene.pod.seats.len() < MAX_POD_SIZE).then_some(EditGameMessage::AddSeat.into())
+            ),
+        ]
+        .spacing(5);
+
+        let outcome_options = scene
+            .pod
+            .seats
+            .iter()
+            .filter_map(|player| self.tournament.get_registered_player((*player)?).ok())
+            .map(OutcomeOption::Winner)
+            .chain([OutcomeOption::Draw, OutcomeOption::NoResult])
+            .collect_vec();
+        let outcome = scene.pod.outcome.and_then(|choice| {
+            outcome_options
+                .iter()
+                .find(|option| option.choice() == choice)
+                .cloned()
+        });
+
+        let draw_seats = matches!(scene.pod.outcome, Some(OutcomeChoice::Draw)).then(|| {
+            row(scene
+                .pod
+                .seats
+                .iter()
+                .enumerate()
+                .filter_map(|(seat, id)| Some((seat, self.tournament.get_player_name(&(*id)?)?)))
+                .map(|(seat, name)| {
+                    let style = if scene.pod.draw_excluded.contains(&seat) {
+                        button::secondary
+                    } else {
+                        button::primary
+                    };
+                    button(text(name).size(12))
+                        .style(style)
+                        .on_press(EditGameMessage::ToggleDrawSeat(seat).into())
+                        .into()
+                }))
+            .spacing(5)
+            .wrap()
+        });
+
+        let winner = row![
+            text("Winner: ").size(17),
+            pick_list(outcome_options, outcome, |picked| {
+                EditGameMessage::SetOutcome(Some(picked.choice())).into()
+            })
+            .width(Length::Fill),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let metadata = row![
+            text_input("Turn", &scene.turns)
+                .on_input(|turns| EditGameMessage::SetTurns(turns).into())
+                .width(60),
+            text_input("Minutes", &scene.duration)
+                .on_input(|duration| EditGameMessage::SetDuration(duration).into())
+                .width(80),
+            pick_list(WinCondition::VALUES, scene.win_condition, |condition| {
+                EditGameMessage::SetWinCondition(Some(condition)).into()
+            })
+            .placeholder("Win Condition"),
+            text_input("Notes", &scene.notes)
+                .on_input(|notes| EditGameMessage::SetNotes(notes).into()),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let session = row![
+            text("Session: ").size(17),
+            text_input("Game night", &scene.session)
+                .on_input(|session| EditGameMessage::SetSession(session).into()),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        container(
+            column![
+                menu_bar,
+                title,
+                column![seats, seat_buttons, winner, draw_seats, metadata, session]
+                    .spacing(10)
+                    .max_width(700)
+            ]
+            .spacing(20)
+            .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use edh_tourn::{
+        Tournament,
+        game::{entry::GameEntry, outcome::GameOutcome},
+    };
+
+    use crate::view::{game::EditGameScene, seats::OutcomeChoice};
+
+    #[test]
+    fn unchanged_game_keeps_entry() {
+        let tourn = Tournament::sample_game();
+        for gid in 0..tourn.games().len() {
+            let scene = EditGameScene::open(&tourn, gid).unwrap();
+            assert_eq!(tourn.games()[gid].to_entry(), scene.entry().unwrap());
+        }
+    }
+
+    #[test]
+    fn edited_outcome_drops_stale_placements() {
+        let entry = GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Win(0))
+            .unwrap()
+            .with_placements(vec![1, 2, 3])
+            .unwrap();
+        let mut scene = EditGameScene::new(Some(0), 0, entry);
+        assert_eq!(&[1, 2, 3], scene.entry().unwrap().placements());
+
+        scene.pod.outcome = Some(OutcomeChoice::Winner(2));
+        let edited = scene.entry().unwrap();
+        assert_eq!(Some(2), edited.winner());
+        assert!(edited.placements().is_empty());
+    }
+
+    #[test]
+    fn draw_leaves_out_excluded_seats() {
+        let entry = GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Draw(vec![0, 2])).unwrap();
+        let mut scene = EditGameScene::new(Some(0), 0, entry);
+        assert_eq!(vec![1, 3], scene.pod.draw_excluded);
+
+        scene.pod.toggle_draw_seat(1);
+        assert_eq!(
+            &GameOutcome::Draw(vec![0, 1, 2]),
+            scene.entry().unwrap().outcome()
+        );
+    }
+
+    #[test]
+    fn typos_in_numbers_block_saving() {
+        let tourn = Tournament::sample_game();
+        let mut scene = EditGameScene::open(&tourn, 0).unwrap();
+        scene.turns = " 12 ".to_owned();
+        scene.duration = String::new();
+        assert!(scene.can_save());
+        let edited = scene.entry().unwrap();
+        assert_eq!(Some(12), edited.details().turns());
+        assert_eq!(None, edited.details().duration());
+
+        scene.duration = "45m".to_owned();
+        assert!(!scene.can_save());
+        scene.entry().unwrap_err();
+        scene.duration = "45".to_owned();
+        scene.turns = "twelve".to_owned();
+        assert!(!scene.can_save());
+        scene.entry().unwrap_err();
+    }
+
+    #[test]
+    fn insert_needs_every_seat() {
+        let tourn = Tournament::sample_game();
+        let mut scene = EditGameScene::insert(&tourn, tourn.games().len()).unwrap();
+        scene.entry().unwrap_err();
+        for id in 0..4 {
+            scene.pod.set_player(id as usize, Some(id));
+        }
+        scene.entry().unwrap();
+        EditGameScene::insert(&tourn, tourn.games().len() + 1).unwrap_err();
+    }
+}
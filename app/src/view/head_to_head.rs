@@ -0,0 +1,263 @@
+use edh_tourn::{
+    game::outcome::GameOutcome,
+    head_to_head::{HeadToHeadMatrix, PairRecord},
+    player::RegisteredPlayer,
+};
+use iced::{
+    Background, Color, Element, Length, Theme,
+    widget::{button, column, container, row, scrollable, space, text},
+};
+use itertools::Itertools;
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::Scene,
+};
+
+/// Width of each cell of the matrix
+const CELL_WIDTH: f32 = 70.0;
+/// Difference between actual and expected results that gets the strongest color
+const FULL_COLOR: f64 = 0.25;
+
+/// Every deck against every other deck, colored by how much better than expected the row deck
+/// did in their shared games
+#[derive(Debug)]
+pub struct HeadToHeadScene {
+    matrix: HeadToHeadMatrix,
+    /// Row and column deck of the cell whose games are listed
+    selected: Option<(u32, u32)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeadToHeadMessage {
+    Open,
+    Select(u32, u32),
+    Close,
+}
+
+impl From<HeadToHeadMessage> for Message {
+    fn from(value: HeadToHeadMessage) -> Self {
+        Self::HeadToHead(value)
+    }
+}
+
+impl HandleMessage<HeadToHeadMessage> for App {
+    fn update(&mut self, msg: HeadToHeadMessage) -> anyhow::Result<iced::Task<Message>> {
+        match msg {
+            HeadToHeadMessage::Open => {
+                self.scenes.push(Scene::HeadToHead(HeadToHeadScene {
+                    matrix: self.tournament.head_to_head(),
+                    selected: None,
+                }));
+            }
+            HeadToHeadMessage::Select(deck, opponent) => {
+                if let Some(Scene::HeadToHead(scene)) = self.scenes.last_mut() {
+                    scene.selected = Some((deck, opponent));
+                }
+            }
+            HeadToHeadMessage::Close => {
+                if let Some(Scene::HeadToHead(_)) = self.scenes.last() {
+                    self.scenes.pop();
+                }
+            }
+        }
+        Message::done()
+    }
+}
+
+/// Green when the deck did better than expected, red when it did worse
+#[allow(clippy::cast_possible_truncation)]
+fn heat_color(record: &PairRecord) -> Color {
+    let Some(difference) = record.over_performance() else {
+        return Color::from_rgba(0.5, 0.5, 0.5, 0.2);
+    };
+    let strength = (difference / FULL_COLOR).clamp(-1.0, 1.0) as f32;
+    if strength >= 0.0 {
+        Color::from_rgba(0.2, 0.7, 0.3, 0.75f32.mul_add(strength, 0.15))
+    } else {
+        Color::from_rgba(0.85, 0.25, 0.25, 0.75f32.mul_add(-strength, 0.15))
+    }
+}
+
+/// Cell of the matrix for the row deck against the column deck
+fn pair_cell(scene: &HeadToHeadScene, deck: u32, opponent: u32) -> Element<'_, Message> {
+    let cell = |label: String| text(label).size(12).width(CELL_WIDTH).center();
+    let Some(record) = scene
+        .matrix
+        .get(deck, opponent)
+        .filter(|_| deck != opponent)
+    else {
+        return cell(String::new()).into();
+    };
+
+    let color = heat_color(record);
+    let selected = scene.selected == Some((deck, opponent));
+    button(cell(format!("{}-{}", record.wins, record.opponent_wins)))
+        .padding(4)
+        .style(move |theme: &Theme, status| button::Style {
+            background: Some(Background::Color(color)),
+            text_color: theme.palette().text,
+            border: if selected {
+                iced::Border::default()
+                    .width(2)
+                    .color(theme.palette().primary)
+            } else {
+                iced::Border::default()
+            },
+            ..button::text(theme, status)
+        })
+        .on_press(HeadToHeadMessage::Select(deck, opponent).into())
+        .into()
+}
+
+impl App {
+    fn view_shared_games(
+        &self,
+        record: &PairRecord,
+        deck: u32,
+        opponent: u32,
+    ) -> Element<'_, Message> {
+        let name = |id: u32| {
+            self.tournament
+                .get_player_name(&id)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let percent = |value: Option<f64>| {
+            value.map_or_else(
+                || "--%".to_owned(),
+                |value| format!("{:.0}%", value * 100.0),
+            )
+        };
+        let summary = text(format!(
+            "{} vs {}: {} games, {} won, {} lost. Expected {}, scored {}.",
+            name(deck),
+            name(opponent),
+            record.games,
+            record.wins,
+            record.opponent_wins,
+            percent(record.mean_expected()),
+            percent(record.mean_actual()),
+        ));
+
+        let games = record.game_ids.iter().rev().filter_map(|gid| {
+            let game = self.tournament.games().get(*gid)?;
+            let outcome = match game.outcome() {
+                GameOutcome::Win(winner) => format!("{} won", name(*winner)),
+                GameOutcome::Draw(drawn) => {
+                    format!(
+                        "Draw between {}",
+                        drawn.iter().map(|id| name(*id)).join(", ")
+                    )
+                }
+                GameOutcome::NoResult => "No result".to_owned(),
+            };
+            let pod = game.ids().into_iter().map(name).join(", ");
+            Some(
+                text(format!("Game {}: {outcome} ({pod})", gid + 1))
+                    .size(12)
+                    .into(),
+            )
+        });
+
+        column![summary, column(games).spacing(2)]
+            .spacing(10)
+            .into()
+    }
+}
+
+impl View<HeadToHeadScene> for App {
+    fn view<'a>(&'a self, scene: &'a HeadToHeadScene) -> Element<'a, Message> {
+        let menu_bar = row![
+            space().width(Length::Fill),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(HeadToHeadMessage::Close.into()),
+        ];
+
+        let title = text("Head to Head").width(Length::Fill).center().size(50);
+
+        let decks = self
+            .tournament
+            .get_active_players()
+            .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
+            .collect_vec();
+
+        let header = row(
+            core::iter::once(space().width(150).into()).chain(decks.iter().map(
+                |deck: &RegisteredPlayer<'_>| {
+                    text(deck.info().name().to_owned())
+                        .size(11)
+                        .width(CELL_WIDTH)
+                        .center()
+                        .into()
+                },
+            )),
+        );
+        let rows = decks.iter().map(|deck| {
+            row(core::iter::once(
+                text(deck.info().name().to_owned())
+                    .size(12)
+                    .width(150)
+                    .into(),
+            )
+            .chain(
+                decks
+                    .iter()
+                    .map(|opponent| pair_cell(scene, deck.id(), opponent.id())),
+            ))
+            .spacing(2)
+            .into()
+        });
+        let matrix = scrollable(column![header.spacing(2), column(rows).spacing(2)])
+            .direction(scrollable::Direction::Both {
+                vertical: scrollable::Scrollbar::default(),
+                horizontal: scrollable::Scrollbar::default(),
+            })
+            .height(Length::FillPortion(2));
+
+        let shared_games = scene.selected.and_then(|(deck, opponent)| {
+            let record = scene.matrix.get(deck, opponent)?;
+            Some(
+                scrollable(self.view_shared_games(record, deck, opponent))
+                    .height(Length::FillPortion(1)),
+            )
+        });
+
+        container(
+            column![menu_bar, title, matrix, shared_games]
+                .spacing(20)
+                .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{App, view::Scene, view::head_to_head::HeadToHeadMessage};
+
+    #[test]
+    fn select_a_pair() {
+        let (mut app, ids) = App::test_with_tournament(5, 10);
+        let (Some(deck), Some(opponent)) = (ids.first(), ids.get(1)) else {
+            panic!("Tournament should have decks");
+        };
+
+        app.test_updates([
+            HeadToHeadMessage::Open,
+            HeadToHeadMessage::Select(*deck, *opponent),
+        ])
+        .unwrap();
+        let Some(Scene::HeadToHead(scene)) = app.scenes.last() else {
+            panic!("Head to head should be open");
+        };
+        assert_eq!(Some((*deck, *opponent)), scene.selected);
+        assert_eq!(app.tournament.head_to_head(), scene.matrix);
+
+        app.test_update(HeadToHeadMessage::Close).unwrap();
+        assert!(app.scenes.is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use edh_tourn::{
+    command::Command,
+    game::{outcome::GameOutcome, record::GameRecord},
+};
+use iced::{
+    Element, Length, font,
+    widget::{button, column, container, row, scrollable, space, table, text},
+};
+use itertools::Itertools;
+
+use crate::{
+    App,
+    fonts::default_font,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::{Scene, confirm::ConfirmPrompt, game::EditGameMessage},
+};
+
+/// Every game of the tournament, newest first
+#[derive(Debug, Default)]
+pub struct HistoryScene;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HistoryMessage {
+    Open,
+    Close,
+    Delete(usize),
+    ConfirmedDelete(usize),
+}
+
+impl From<HistoryMessage> for Message {
+    fn from(value: HistoryMessage) -> Self {
+        Self::History(value)
+    }
+}
+
+impl HandleMessage<HistoryMessage> for App {
+    fn update(&mut self, msg: HistoryMessage) -> anyhow::Result<iced::Task<Message>> {
+        match msg {
+            HistoryMessage::Open => {
+                self.scenes.push(Scene::History(HistoryScene));
+            }
+            HistoryMessage::Close => {
+                if let Some(Scene::History(_)) = self.scenes.last() {
+                    self.scenes.pop();
+                }
+            }
+            HistoryMessage::Delete(gid) => {
+                self.scenes.push(Scene::Confirm(ConfirmPrompt::new(
+                    format!("Are you sure you want to delete game {}?", gid + 1),
+                    HistoryMessage::ConfirmedDelete(gid).into(),
+                )));
+            }
+            HistoryMessage::ConfirmedDelete(gid) => {
+                self.execute(Command::DeleteGame(gid))?;
+            }
+        }
+        Message::done()
+    }
+}
+
+impl View<HistoryScene> for App {
+    fn view<'a>(&'a self, _: &'a HistoryScene) -> Element<'a, Message> {
+        let menu_bar = row![
+            button(text("Add Game"))
+                .on_press(EditGameMessage::Insert(self.tournament.games().len()).into()),
+            space().width(Length::Fill),
+            button(text("Close")).on_press(HistoryMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Game History").width(Length::Fill).center().size(50);
+
+        let games = self.tournament.games().iter().enumerate().rev();
+
+        let view_games = scrollable(
+            table(
+                [
+                    table::column("#", |(gid, _): (usize, &GameRecord)| {
+                        text(format!("{}", gid + 1)).size(20)
+                    }),
+                    table::column("Competitors", |(_, game): (usize, &GameRecord)| {
+                        let outcome = match game.outcome() {
+                            GameOutcome::Win(_) => None,
+                            GameOutcome::Draw(_) => Some(text("Draw").size(12)),
+                            GameOutcome::NoResult => Some(text("No Result").size(12)),
+                        };
+                        column(game.players().iter().map(|player| {
+                            text(
+                                self.tournament
+                                    .get_player_name(&player.id())
+                                    .map_or_else(|| player.id().to_string(), ToOwned::to_owned),
+                            )
+                            .font_maybe(game.outcome().is_credited(player.id()).then_some(
+                                font::Font {
+                                    weight: font::Weight::Bold,
+                                    ..default_font()
+                                },
+                            ))
+                            .into()
+                        }))
+                        .push(outcome)
+                    }),
+                    table::column("Details", |(_, game): (usize, &GameRecord)| {
+                        let details = game.details();
+                        let summary = [
+                            details.session().map(ToOwned::to_owned),
+                            details.turns().map(|turns| format!("Turn {turns}")),
+                            details.duration().map(|minutes| format!("{minutes} min")),
+                            details
+                                .win_condition()
+                                .map(|condition| condition.to_string()),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .join(", ");
+                        let notes =
+                            (!details.notes().is_empty()).then(|| text(details.notes()).size(12));
+                        column![text(summary).size(12)].push(notes).max_width(250)
+                    }),
+                    table::column("", |(gid, _): (usize, &GameRecord)| {
+                        row![
+                            button(text("Edit")).on_press(EditGameMessage::Open(gid).into()),
+                            button(text("Insert Before"))
+                                .style(button::secondary)
+                                .on_press(EditGameMessage::Insert(gid).into()),
+                            button(text("Delete"))
+                                .style(button::danger)
+                                .on_press(HistoryMessage::Delete(gid).into()),
+                        ]
+                        .spacing(5)
+                    }),
+                ],
+                games,
+            )
+            .width(Length::Fill),
+        )
+        .width(Length::Fill);
+
+        container(
+            column![menu_bar, title, view_games]
+                .spacing(20)
+                .width(Length::Fill),
+        )
+        .into()
+    }
+}
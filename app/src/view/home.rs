@@ -1,43 +1,74 @@
+use edh_tourn::{Tournament, rating::batch::BatchRatings};
 use iced::widget::{button, column, container, row, rule, space};
 
 use crate::{
     App,
+    cache::Cached,
     logic::{Message, file::FileMessage},
     traits::{HandleMessage, View},
     view::{
+        calibration::CalibrationMessage,
+        head_to_head::HeadToHeadMessage,
+        history::HistoryMessage,
         home::{
-            leaderboard::LeaderboardColumn,
+            leaderboard::{LeaderboardColumn, LeaderboardTab},
             matchmaker::{MatchMakerMessage, MatchMakerView},
             matchup::{MatchupMessage, MatchupView},
         },
+        meta::MetaMessage,
+        planner::PlannerMessage,
         player::ViewPlayerMessage,
+        settings::SettingsMessage,
+        tuning::SuggestConfigMessage,
     },
 };
 
 mod leaderboard;
 mod matchmaker;
-mod matchup;
+pub(crate) mod matchup;
 
 pub struct HomeState {
+    leaderboard_tab: LeaderboardTab,
+    leaderboard_show_archived: bool,
     leaderboard_sort_column: LeaderboardColumn,
     leaderboard_sort_asc: bool,
     matchup_view: MatchupView,
     matchmaker: MatchMakerView,
+    /// Fitting these replays every game, so they're only refit once the tournament changes
+    batch_ratings: Cached<BatchRatings>,
+}
+
+impl HomeState {
+    pub(crate) fn refresh(&mut self, tournament: &Tournament) {
+        self.batch_ratings
+            .refresh(tournament, Tournament::batch_ratings);
+        self.matchup_view.refresh(tournament);
+    }
+
+    pub(crate) fn clear_caches(&mut self) {
+        self.batch_ratings.clear();
+        self.matchup_view.clear_caches();
+    }
 }
 
 impl Default for HomeState {
     fn default() -> Self {
         Self {
+            leaderboard_tab: LeaderboardTab::Decks,
+            leaderboard_show_archived: false,
             leaderboard_sort_column: LeaderboardColumn::Elo,
             leaderboard_sort_asc: false,
             matchup_view: MatchupView::default(),
             matchmaker: MatchMakerView::default(),
+            batch_ratings: Cached::default(),
         }
     }
 }
 
 #[derive(Clone)]
 pub enum HomeMessage {
+    SetLeaderboardTab(LeaderboardTab),
+    ToggleShowArchived,
     SortLeaderboardBy(LeaderboardColumn),
     MatchupMessage(MatchupMessage),
     MatchmakerMessage(MatchMakerMessage),
@@ -52,6 +83,14 @@ impl From<HomeMessage> for Message {
 impl HandleMessage<HomeMessage> for App {
     fn update(&mut self, msg: HomeMessage) -> anyhow::Result<iced::Task<Message>> {
         match msg {
+            HomeMessage::SetLeaderboardTab(tab) => {
+                self.home.leaderboard_tab = tab;
+                Message::done()
+            }
+            HomeMessage::ToggleShowArchived => {
+                self.home.leaderboard_show_archived = !self.home.leaderboard_show_archived;
+                Message::done()
+            }
             HomeMessage::SortLeaderboardBy(sort_column) => {
                 if self.home.leaderboard_sort_column.eq(&sort_column) {
                     self.home.leaderboard_sort_asc = !self.home.leaderboard_sort_asc;
@@ -73,12 +112,22 @@ impl View<HomeState> for App {
         column![
             row![
                 button("New Player").on_press(ViewPlayerMessage::Open(None).into()),
+                button("History").on_press(HistoryMessage::Open.into()),
+                button("Suggest Config").on_press(SuggestConfigMessage::Open.into()),
+                button("Calibration").on_press(CalibrationMessage::Open.into()),
+                button("Head to Head").on_press(HeadToHeadMessage::Open.into()),
+                button("Meta").on_press(MetaMessage::Open.into()),
+                button("Pod Planner").on_press(PlannerMessage::Open.into()),
+                button("Settings").on_press(SettingsMessage::Open.into()),
                 space().width(15.0),
                 button("Open").on_press(FileMessage::OpenFile.into()),
                 button("Save").on_press(FileMessage::Save.into()),
                 button("Save As")
                     .on_press_maybe(self.file.is_some().then_some(FileMessage::SaveAs.into())),
                 button("New").on_press(FileMessage::New.into()),
+                space().width(15.0),
+                button("Undo").on_press_maybe(self.history.can_undo().then_some(Message::Undo)),
+                button("Redo").on_press_maybe(self.history.can_redo().then_some(Message::Redo)),
             ],
             row![
                 container(self.view_home_leaderboard()).padding(10),
@@ -1,9 +1,13 @@
 use core::cmp::Ordering;
+use std::borrow::Cow;
 
-use edh_tourn::{player::info::PlayerInfo, player::stats::PlayerStats};
+use edh_tourn::{
+    pilot::{PilotInfo, PilotStats},
+    player::{info::PlayerInfo, stats::PlayerStats},
+};
 use iced::{
     Element, Padding,
-    widget::{button, container, row, scrollable, space, table, text},
+    widget::{button, column, container, row, scrollable, space, table, text},
 };
 use itertools::Itertools;
 
@@ -26,10 +30,26 @@ impl Player<'_> {
     }
 }
 
+#[derive(Clone)]
+struct Pilot<'a> {
+    info: &'a PilotInfo,
+    stats: PilotStats,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LeaderboardTab {
+    Decks,
+    Pilots,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum LeaderboardColumn {
     Name,
     Elo,
+    /// Rating minus a margin for its uncertainty, see [`PlayerStats::conservative_rating`]
+    Conservative,
+    /// Rating fitted to every game at once, see [`edh_tourn::Tournament::batch_ratings`]
+    Batch,
     WR,
     Games,
     Wins,
@@ -38,12 +58,147 @@ pub enum LeaderboardColumn {
 impl App {
     #[must_use]
     pub fn view_home_leaderboard(&self) -> Element<'_, Message> {
+        let tab_button = |label, tab| {
+            let style = if self.home.leaderboard_tab == tab {
+                button::primary
+            } else {
+                button::secondary
+            };
+            button(label)
+                .style(style)
+                .on_press(HomeMessage::SetLeaderboardTab(tab).into())
+        };
+
+        let tabs = row![
+            tab_button("Decks", LeaderboardTab::Decks),
+            tab_button("Pilots", LeaderboardTab::Pilots),
+            space().width(15),
+            button(if self.home.leaderboard_show_archived {
+                "Hide Retired"
+            } else {
+                "Show Retired"
+            })
+            .style(button::secondary)
+            .on_press_maybe(
+                (self.home.leaderboard_tab == LeaderboardTab::Decks)
+                    .then_some(HomeMessage::ToggleShowArchived.into())
+            ),
+        ]
+        .spacing(5);
+
+        let board = match self.home.leaderboard_tab {
+            LeaderboardTab::Decks => self.view_deck_leaderboard(),
+            LeaderboardTab::Pilots => self.view_pilot_leaderboard(),
+        };
+
+        column![tabs, board].into()
+    }
+
+    fn col_header(&self, label: &str, col: LeaderboardColumn) -> button::Button<'_, Message> {
+        let ord_char = if self.home.leaderboard_sort_asc {
+            "󰁅"
+        } else {
+            "󰁝"
+        };
+
+        button(text(if self.home.leaderboard_sort_column == col {
+            format!("{label} {ord_char}")
+        } else {
+            format!("{label}  ")
+        }))
+        .style(button::text)
+        .on_press(HomeMessage::SortLeaderboardBy(col).into())
+    }
+
+    fn view_pilot_leaderboard(&self) -> Element<'_, Message> {
+        let mut all_stats = self.tournament.get_all_pilot_stats();
+
+        let rows = self
+            .tournament
+            .pilots()
+            .iter()
+            .filter_map(|(id, info)| {
+                Some(Pilot {
+                    info,
+                    stats: all_stats.remove(id)?,
+                })
+            })
+            .sorted_by(|a, b| {
+                let sort = match self.home.leaderboard_sort_column {
+                    LeaderboardColumn::Name => a.info.name().cmp(b.info.name()),
+                    LeaderboardColumn::Elo
+                    | LeaderboardColumn::Conservative
+                    | LeaderboardColumn::Batch => a.stats.rating().total_cmp(&b.stats.rating()),
+                    LeaderboardColumn::WR => a
+                        .stats
+                        .wr()
+                        .partial_cmp(&b.stats.wr())
+                        .unwrap_or(Ordering::Equal),
+                    LeaderboardColumn::Games => a.stats.games().cmp(&b.stats.games()),
+                    LeaderboardColumn::Wins => a.stats.wins().cmp(&b.stats.wins()),
+                };
+                if self.home.leaderboard_sort_asc {
+                    sort
+                } else {
+                    sort.reverse()
+                }
+            })
+            .collect_vec();
+
+        let tbl = table(
+            [
+                table::column(
+                    self.col_header("Name", LeaderboardColumn::Name),
+                    |p: Pilot<'_>| text(p.info.name().to_owned()).size(12),
+                ),
+                table::column(
+                    self.col_header("Rating", LeaderboardColumn::Elo),
+                    |p: Pilot<'_>| text(format!("{:.0}", p.stats.rating())).size(12),
+                ),
+                table::column(
+                    self.col_header("Games", LeaderboardColumn::Games),
+                    |p: Pilot<'_>| text(p.stats.games()).size(12),
+                ),
+                table::column(
+                    self.col_header("Wins", LeaderboardColumn::Wins),
+                    |p: Pilot<'_>| text(p.stats.wins()).size(12),
+                ),
+                table::column(
+                    self.col_header("WR", LeaderboardColumn::WR),
+                    |p: Pilot<'_>| {
+                        text(
+                            p.stats
+                                .wr()
+                                .map(|wr| format!("{:.1}%", wr * 100.0))
+                                .unwrap_or_default(),
+                        )
+                        .size(12)
+                    },
+                ),
+            ],
+            rows,
+        );
+
+        container(scrollable(row![tbl, space().width(15)]))
+            .padding(Padding::new(10f32))
+            .into()
+    }
+
+    fn view_deck_leaderboard(&self) -> Element<'_, Message> {
         let default_stats = self.tournament.default_stats();
+        // Only out of date when the tournament was changed without going through the updater
+        let batch = self.home.batch_ratings.get(&self.tournament).map_or_else(
+            || Cow::Owned(self.tournament.batch_ratings()),
+            Cow::Borrowed,
+        );
+        let starting_elo = self.tournament.config().starting_elo;
+        let batch_rating = |id: u32| batch.rating(id).unwrap_or(starting_elo);
 
         let players = self
             .tournament
             .players()
             .iter()
+            .filter(|(_, info)| self.home.leaderboard_show_archived || !info.is_archived())
             .map(move |(id, info)| Player {
                 id: *id,
                 info,
@@ -57,6 +212,11 @@ impl App {
                     .get_stats(default_stats)
                     .elo()
                     .total_cmp(&b.get_stats(default_stats).elo()),
+                LeaderboardColumn::Conservative => a
+                    .get_stats(default_stats)
+                    .conservative_rating()
+                    .total_cmp(&b.get_stats(default_stats).conservative_rating()),
+                LeaderboardColumn::Batch => batch_rating(a.id).total_cmp(&batch_rating(b.id)),
                 LeaderboardColumn::WR => a
                     .get_stats(default_stats)
                     .wr()
@@ -78,63 +238,76 @@ impl App {
             }
         });
 
-        let ord_char = if self.home.leaderboard_sort_asc {
-            "󰁅"
-        } else {
-            "󰁝"
-        };
+        let col_header = |label: &str, col: LeaderboardColumn| self.col_header(label, col);
 
-        let col_header = |label: &str, col: LeaderboardColumn| {
-            button(text(if self.home.leaderboard_sort_column == col {
-                format!("{label} {ord_char}")
-            } else {
-                format!("{label}  ")
-            }))
-            .style(button::text)
-            .on_press(HomeMessage::SortLeaderboardBy(col).into())
-        };
+        // Only rating systems that track uncertainty have a separate conservative rating
+        let conservative = default_stats.deviation().is_some().then(|| {
+            table::column(
+                col_header("Conservative", LeaderboardColumn::Conservative),
+                |p: Player<'_>| {
+                    let stats = p.stats.unwrap_or(default_stats);
+                    text(format!("{:.0}", stats.conservative_rating())).size(12)
+                },
+            )
+        });
 
         let tbl = table(
             [
-                table::column(
+                Some(table::column(
                     col_header("Name", LeaderboardColumn::Name),
                     |p: Player<'_>| {
                         button(text(p.info.name()).size(12))
                             .style(button::text)
                             .on_press(ViewPlayerMessage::Open(Some(p.id)).into())
                     },
-                ),
-                table::column(
+                )),
+                Some(table::column(
                     col_header("Elo", LeaderboardColumn::Elo),
                     |p: Player<'_>| {
-                        text(format!("{:.0}", p.stats.unwrap_or(default_stats).elo())).size(12)
+                        let stats = p.stats.unwrap_or(default_stats);
+                        let elo = format!("{:.0}", stats.elo());
+                        text(stats.deviation().map_or_else(
+                            || elo.clone(),
+                            |deviation| format!("{elo} ± {deviation:.0}"),
+                        ))
+                        .size(12)
                     },
-                ),
-                table::column(
+                )),
+                conservative,
+                Some(table::column(
+                    col_header("Batch", LeaderboardColumn::Batch),
+                    |p: Player<'_>| text(format!("{:.0}", batch_rating(p.id))).size(12),
+                )),
+                Some(table::column(
                     col_header("Games", LeaderboardColumn::Games),
                     |p: Player<'_>| text(p.stats.unwrap_or(default_stats).games()).size(12),
-                ),
-                table::column(
+                )),
+                Some(table::column(
                     col_header("Wins", LeaderboardColumn::Wins),
                     |p: Player<'_>| text(p.stats.unwrap_or(default_stats).wins()).size(12),
-                ),
-                table::column(col_header("WR", LeaderboardColumn::WR), |p: Player<'_>| {
-                    text(
-                        p.stats
-                            .unwrap_or(default_stats)
-                            .wr()
-                            .map(|wr| format!("{:.1}%", wr * 100.0))
-                            .unwrap_or_default(),
-                    )
-                    .size(12)
-                }),
-                table::column(
+                )),
+                Some(table::column(
+                    col_header("WR", LeaderboardColumn::WR),
+                    |p: Player<'_>| {
+                        text(
+                            p.stats
+                                .unwrap_or(default_stats)
+                                .wr()
+                                .map(|wr| format!("{:.1}%", wr * 100.0))
+                                .unwrap_or_default(),
+                        )
+                        .size(12)
+                    },
+                )),
+                Some(table::column(
                     button("+").on_press(ViewPlayerMessage::Open(None).into()),
                     |p: Player<'_>| {
                         button("").on_press_maybe(p.info.moxfield_link().map(Message::OpenLink))
                     },
-                ),
-            ],
+                )),
+            ]
+            .into_iter()
+            .flatten(),
             sorted_players,
         );
 
@@ -143,3 +316,36 @@ impl App {
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use edh_tourn::Tournament;
+
+    use crate::{App, logic::Message};
+
+    #[test]
+    fn batch_ratings_follow_the_tournament() -> anyhow::Result<()> {
+        let mut app = App::default();
+        let tourn = Tournament::generate_tournament(6, 10)?;
+        let _ = app.updater(Message::LoadTournament(tourn.clone().into()));
+        assert_eq!(
+            Some(&tourn.batch_ratings()),
+            app.home.batch_ratings.get(&app.tournament)
+        );
+
+        // Another tournament with the same snapshot mustn't reuse the ratings
+        let other = Tournament::generate_tournament(6, 10)?;
+        let _ = app.updater(Message::LoadTournament(other.clone().into()));
+        assert_eq!(
+            Some(&other.batch_ratings()),
+            app.home.batch_ratings.get(&app.tournament)
+        );
+
+        let _ = app.updater(Message::RefreshTournament);
+        assert_eq!(
+            Some(&app.tournament.batch_ratings()),
+            app.home.batch_ratings.get(&app.tournament)
+        );
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use core::fmt::Display;
 
-use edh_tourn::Tournament;
+use edh_tourn::{
+    Tournament,
+    matches::criteria::{CRITERIA, criterion},
+};
 use iced::{
     Length, Task,
     alignment::Horizontal,
@@ -12,7 +15,10 @@ use crate::{
     App,
     logic::Message,
     traits::{HandleMessage, View},
-    view::home::{HomeMessage, matchup::MatchupMessage},
+    view::{
+        constraints::ConstraintsMessage,
+        home::{HomeMessage, matchup::MatchupMessage},
+    },
 };
 
 pub struct MatchMakerView {
@@ -33,66 +39,32 @@ impl Default for MatchMakerView {
 }
 
 impl MatchMakerView {
-    fn get_leaderboard<'a>(&'a self, tournament: &'a Tournament) -> Option<Vec<u32>> {
-        self.player.and_then(|id| {
-            Some(match &self.method {
-                MatchMethod::LeastPlayed => tournament
-                    .rank_least_played(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-                MatchMethod::ExpectedNeighbors => tournament
-                    .rank_expected_neighbors(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-                MatchMethod::LossWith => tournament
-                    .rank_loss_with(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-                MatchMethod::Nemesis => tournament
-                    .rank_nemesis(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-                MatchMethod::EloNeighbors => tournament
-                    .rank_elo_neighbors(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-                MatchMethod::WRNeighbors => tournament
-                    .rank_wr_neighbors(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-                MatchMethod::Combined => tournament
-                    .rank_combined(id)
-                    .ok()?
-                    .take(self.show_count)
-                    .collect_vec(),
-            })
-        })
+    fn get_leaderboard(&self, tournament: &Tournament) -> Option<Vec<u32>> {
+        self.player?;
+        let ranking = self.ranking(tournament).ok()?;
+        Some(ranking.into_iter().take(self.show_count).collect_vec())
     }
 
-    fn update(&mut self, tournament: &Tournament) -> anyhow::Result<()> {
+    /// Every opponent of the selected deck, best pick first
+    fn ranking(&self, tournament: &Tournament) -> anyhow::Result<Vec<u32>> {
         let Some(id) = self.player else {
-            self.leaderboard = Vec::new();
-            return Ok(());
+            return Ok(Vec::new());
         };
 
-        self.leaderboard = match &self.method {
-            MatchMethod::LeastPlayed => tournament.rank_least_played(id)?.collect_vec(),
-            MatchMethod::ExpectedNeighbors => tournament.rank_expected_neighbors(id)?.collect_vec(),
-            MatchMethod::LossWith => tournament.rank_loss_with(id)?.collect_vec(),
-            MatchMethod::Nemesis => tournament.rank_nemesis(id)?.collect_vec(),
-            MatchMethod::EloNeighbors => tournament.rank_elo_neighbors(id)?.collect_vec(),
-            MatchMethod::WRNeighbors => tournament.rank_wr_neighbors(id)?.collect_vec(),
+        Ok(match self.method {
             MatchMethod::Combined => tournament.rank_combined(id)?.collect_vec(),
-        }
-        .into_iter()
-        .take(self.show_count)
-        .collect_vec();
+            MatchMethod::Criterion(criterion_id) => criterion(criterion_id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown match criterion {criterion_id}"))?
+                .rank(tournament, &tournament.head_to_head(), id)?,
+        })
+    }
+
+    fn update(&mut self, tournament: &Tournament) -> anyhow::Result<()> {
+        self.leaderboard = self
+            .ranking(tournament)?
+            .into_iter()
+            .take(self.show_count)
+            .collect_vec();
 
         Ok(())
     }
@@ -100,38 +72,31 @@ impl MatchMakerView {
 
 #[derive(Clone, Default, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum MatchMethod {
-    LeastPlayed,
-    ExpectedNeighbors,
-    LossWith,
-    Nemesis,
-    EloNeighbors,
-    WRNeighbors,
     #[default]
     Combined,
+    /// A single criterion from the registry, by id
+    Criterion(&'static str),
 }
 
 impl MatchMethod {
-    pub const VALUES: [Self; 7] = [
-        Self::Combined,
-        Self::LeastPlayed,
-        Self::Nemesis,
-        Self::ExpectedNeighbors,
-        Self::EloNeighbors,
-        Self::WRNeighbors,
-        Self::LossWith,
-    ];
+    /// Combined first, then every registered criterion
+    #[must_use]
+    pub fn values() -> Vec<Self> {
+        chain!(
+            [Self::Combined],
+            CRITERIA
+                .iter()
+                .map(|criterion| Self::Criterion(criterion.id()))
+        )
+        .collect()
+    }
 }
 
 impl Display for MatchMethod {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            Self::LeastPlayed => write!(f, "Least Played"),
-            Self::ExpectedNeighbors => write!(f, "Expected Neighbors"),
-            Self::LossWith => write!(f, "Loss With"),
-            Self::Nemesis => write!(f, "Nemesis"),
-            Self::EloNeighbors => write!(f, "Elo Neighbors"),
-            Self::WRNeighbors => write!(f, "WR Neighbors"),
             Self::Combined => write!(f, "Combined"),
+            Self::Criterion(id) => write!(f, "{}", criterion(id).map_or(*id, |c| c.name())),
         }
     }
 }
@@ -177,13 +142,13 @@ impl HandleMessage<MatchMakerMessage> for App {
                     return Message::done();
                 };
 
+                // Picks down the ranking so the opponents are allowed together as well
+                let pod = self
+                    .tournament
+                    .fill_pod(id, view.ranking(&self.tournament)?, 4);
                 let matchup_updates = chain!(
-                    [MatchupMessage::Clear, MatchupMessage::AddPlayer(id)],
-                    view.leaderboard
-                        .iter()
-                        .take(3)
-                        .copied()
-                        .map(MatchupMessage::AddPlayer)
+                    [MatchupMessage::Clear],
+                    pod.into_iter().map(MatchupMessage::AddPlayer)
                 )
                 .collect_vec();
 
@@ -209,7 +174,7 @@ impl View<MatchMakerView> for App {
                     .align_x(Horizontal::Center),
                 pick_list(
                     self.tournament()
-                        .get_registered_players()
+                        .get_active_players()
                         .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
                         .collect_vec(),
                     scene
@@ -219,7 +184,7 @@ impl View<MatchMakerView> for App {
                 )
                 .width(Length::Fill),
                 row![
-                    pick_list(MatchMethod::VALUES, Some(scene.method), |method| {
+                    pick_list(MatchMethod::values(), Some(scene.method), |method| {
                         MatchMakerMessage::Method(method).into()
                     }),
                     button("Load Top 3").on_press_maybe(
@@ -228,7 +193,8 @@ impl View<MatchMakerView> for App {
                             .is_some()
                             .then_some(MatchMakerMessage::LoadTopThree.into())
                     ),
-                    button("⚙")
+                    button("⚙"),
+                    button("Constraints").on_press(ConstraintsMessage::Open.into()),
                 ]
                 .spacing(10),
                 table(
@@ -249,3 +215,31 @@ impl View<MatchMakerView> for App {
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use edh_tourn::matches::criteria::CRITERIA;
+
+    use crate::{
+        App,
+        view::home::matchmaker::{MatchMakerMessage, MatchMethod},
+    };
+
+    #[test]
+    fn every_criterion_can_be_picked() {
+        let (mut app, ids) = App::test_with_tournament(8, 20);
+        let id = ids[0];
+        let methods = MatchMethod::values();
+        assert_eq!(CRITERIA.len() + 1, methods.len());
+
+        app.test_update(MatchMakerMessage::Player(Some(id)))
+            .unwrap();
+        for method in methods {
+            app.test_update(MatchMakerMessage::Method(method)).unwrap();
+            assert_eq!(7, app.home.matchmaker.leaderboard.len());
+            assert!(!method.to_string().is_empty());
+        }
+    }
+}
@@ -1,115 +1,324 @@
+use core::{fmt::Display, iter::once};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
 use edh_tourn::{
     Tournament,
+    command::Command,
     error::TournamentError,
-    game::{match_player::MatchPlayer, matchup::Matchup},
+    game::{
+        DEFAULT_POD_SIZE, MAX_POD_SIZE, MIN_POD_SIZE,
+        details::{GameDetails, WinCondition},
+        elimination::{Elimination, Eliminator},
+        match_player::MatchPlayer,
+        matchup::Matchup,
+        outcome::GameOutcome,
+        preview::{OutcomePreview, Standing},
+    },
+    player::RegisteredPlayer,
+    simulation::{DeckSimulation, Simulation, SimulationSettings},
 };
 use iced::{
-    Alignment, Length, Task,
+    Alignment, Element, Length, Task,
     alignment::Vertical,
-    widget::{button, column, container, pick_list, row, space, text},
+    widget::{button, column, container, pick_list, row, space, table, text, text_input},
 };
-use itertools::Itertools;
+use itertools::{Itertools, izip};
+use rand::{Rng, seq::SliceRandom};
 
 use crate::{
     App,
     logic::Message,
     traits::{HandleMessage, View},
-    view::home::HomeMessage,
+    view::{
+        home::HomeMessage,
+        ordinal, parse_number,
+        seats::{OutcomeChoice, OutcomeOption, SeatEditor},
+    },
 };
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct MatchupView {
-    player_a: Option<u32>,
-    player_b: Option<u32>,
-    player_c: Option<u32>,
-    player_d: Option<u32>,
+    pod: SeatEditor,
     matchup: Option<Matchup>,
-    winner: Option<u32>,
+    /// Finishing place of each seat, starting at 1
+    places: Vec<Option<usize>>,
+    /// What knocked each seat out, if it was knocked out
+    eliminated_by: Vec<Option<Eliminator>>,
+    /// Session that submitted games are added to. Kept between games.
+    session: String,
+    turns: String,
+    /// In minutes
+    duration: String,
+    win_condition: Option<WinCondition>,
+    notes: String,
+    /// Simulated series of the seated pod, cleared when the seats change
+    simulation: Option<Simulation>,
+    /// Seed of the simulation running in the background. Results of any other run are dropped.
+    simulating: Option<u64>,
+    /// Leaderboard after each possible winner, shown while expanded
+    preview: Option<Vec<OutcomePreview>>,
+    /// Snapshot of the tournament the matchup, preview and simulation were worked out from
+    snapshot: Option<usize>,
 }
 
-impl MatchupView {
-    const fn set_player(&mut self, position: MatchViewPlayer, value: Option<u32>) {
-        match position {
-            MatchViewPlayer::PlayerA => self.player_a = value,
-            MatchViewPlayer::PlayerB => self.player_b = value,
-            MatchViewPlayer::PlayerC => self.player_c = value,
-            MatchViewPlayer::PlayerD => self.player_d = value,
+impl Default for MatchupView {
+    fn default() -> Self {
+        Self {
+            pod: SeatEditor::default(),
+            matchup: None,
+            places: vec![None; DEFAULT_POD_SIZE],
+            eliminated_by: vec![None; DEFAULT_POD_SIZE],
+            session: String::new(),
+            turns: String::new(),
+            duration: String::new(),
+            win_condition: None,
+            notes: String::new(),
+            simulation: None,
+            simulating: None,
+            preview: None,
+            snapshot: None,
         }
     }
+}
 
+impl MatchupView {
     #[must_use]
-    const fn get_player(&self, position: MatchViewPlayer) -> Option<&u32> {
-        match position {
-            MatchViewPlayer::PlayerA => self.player_a.as_ref(),
-            MatchViewPlayer::PlayerB => self.player_b.as_ref(),
-            MatchViewPlayer::PlayerC => self.player_c.as_ref(),
-            MatchViewPlayer::PlayerD => self.player_d.as_ref(),
-        }
+    fn get_player(&self, seat: usize) -> Option<&u32> {
+        self.pod.seats.get(seat)?.as_ref()
     }
 
     pub fn add_player(&mut self, id: u32) {
-        for player in MatchViewPlayer::PLAYERS {
-            if self.get_player(player).is_none() {
-                self.set_player(player, Some(id));
-                return;
-            }
+        if let Some(seat) = self.pod.seats.iter_mut().find(|seat| seat.is_none()) {
+            *seat = Some(id);
+        }
+    }
+
+    fn add_seat(&mut self) {
+        if self.pod.add_seat() {
+            self.places.push(None);
+            self.eliminated_by.push(None);
+        }
+    }
+
+    fn remove_seat(&mut self) {
+        if self.pod.remove_seat() {
+            self.places.pop();
+            self.eliminated_by.pop();
+        }
+    }
+
+    /// Shuffles the seats, which are listed in turn order. Places and eliminations move with
+    /// their seats.
+    fn randomize_seating<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let mut seating = izip!(
+            self.pod.seats.iter().copied(),
+            self.places.iter().copied(),
+            self.eliminated_by.iter().copied()
+        )
+        .collect_vec();
+        seating.shuffle(rng);
+        (self.pod.seats, self.places, self.eliminated_by) = seating.into_iter().multiunzip();
+        self.pod.draw_excluded.clear();
+    }
+
+    fn set_place(&mut self, seat: usize, value: Option<usize>) {
+        if let Some(place) = self.places.get_mut(seat) {
+            *place = value;
         }
     }
 
-    fn get_matchup_player(&self, position: MatchViewPlayer) -> Option<&MatchPlayer> {
-        let [player_a, player_b, player_c, player_d] = self.matchup.as_ref()?.players();
-        Some(match position {
-            MatchViewPlayer::PlayerA => player_a,
-            MatchViewPlayer::PlayerB => player_b,
-            MatchViewPlayer::PlayerC => player_c,
-            MatchViewPlayer::PlayerD => player_d,
+    fn set_eliminated_by(&mut self, seat: usize, value: Option<Eliminator>) {
+        if let Some(eliminated_by) = self.eliminated_by.get_mut(seat) {
+            *eliminated_by = value;
+        }
+    }
+
+    /// Eliminations of the seats that aren't credited with the outcome, in order of their place.
+    /// Eliminations by decks that are no longer seated are left out.
+    fn eliminations(&self, outcome: &GameOutcome) -> Vec<Elimination> {
+        izip!(&self.pod.seats, &self.places, &self.eliminated_by)
+            .filter_map(|(id, place, by)| Some(((*id)?, *place, (*by)?)))
+            .filter(|(id, _, _)| !outcome.is_credited(*id))
+            .filter(|(_, _, by)| match by {
+                Eliminator::Deck(killer) => self.pod.seats.contains(&Some(*killer)),
+                Eliminator::SelfInflicted | Eliminator::Table => true,
+            })
+            .sorted_by_key(|(_, place, _)| core::cmp::Reverse(*place))
+            .map(|(id, _, by)| Elimination::new(id, by))
+            .collect()
+    }
+
+    /// Finishing order of the seats that were given a place, leaving out those credited with the
+    /// win as they always come first
+    fn placements(&self, outcome: &GameOutcome) -> Vec<u32> {
+        self.pod
+            .seats
+            .iter()
+            .zip(&self.places)
+            .filter_map(|(id, place)| Some(((*id)?, (*place)?)))
+            .filter(|(id, _)| !outcome.is_credited(*id))
+            .sorted_by_key(|(_, place)| *place)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Resets the panel for the next game, keeping the session
+    fn reset(&mut self) {
+        let session = core::mem::take(&mut self.session);
+        *self = Self {
+            session,
+            ..Self::default()
+        };
+    }
+
+    /// Whether the game can be submitted: the pod and outcome are picked, and the turns and
+    /// duration are either blank or whole numbers
+    fn can_submit(&self) -> bool {
+        self.matchup.is_some()
+            && self.pod.outcome.is_some()
+            && parse_number(&self.turns).is_ok()
+            && parse_number(&self.duration).is_ok()
+    }
+
+    /// Details for a game with the given outcome, submitted now
+    fn details(&self, outcome: &GameOutcome) -> anyhow::Result<GameDetails> {
+        let session = self.session.trim();
+        Ok(GameDetails {
+            played_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|time| time.as_secs()),
+            session: (!session.is_empty()).then(|| session.to_owned()),
+            turns: parse_number(&self.turns).context("Turns must be a whole number")?,
+            duration: parse_number(&self.duration).context("Minutes must be a whole number")?,
+            win_condition: self.win_condition,
+            notes: self.notes.trim().to_owned(),
+            eliminations: self.eliminations(outcome),
+            ..GameDetails::default()
         })
     }
 
-    fn players(&self) -> Option<[u32; 4]> {
-        Some([
-            self.player_a?,
-            self.player_b?,
-            self.player_c?,
-            self.player_d?,
-        ])
+    fn get_matchup_player(&self, seat: usize) -> Option<&MatchPlayer> {
+        self.matchup.as_ref()?.players().get(seat)
     }
 
     fn update_matchup(&mut self, tournament: &Tournament) -> Result<(), TournamentError> {
-        self.matchup = match self.players() {
-            Some(players) => Some(tournament.create_match(players)?),
-            None => None,
+        self.simulation = None;
+        self.simulating = None;
+        self.preview = None;
+        self.matchup = None;
+        self.snapshot = Some(tournament.snapshot());
+        self.matchup = match (self.pod.players(), self.pod.first) {
+            (Some(players), Some(first)) => {
+                Some(tournament.create_seated_match(&players, Some(first))?)
+            }
+            (Some(players), None) => Some(tournament.create_match(&players)?),
+            (None, _) => None,
         };
         Ok(())
     }
+
+    /// Works the matchup out again if the tournament changed under it, such as by an undo
+    pub(crate) fn refresh(&mut self, tournament: &Tournament) {
+        if self.snapshot == Some(tournament.snapshot()) {
+            return;
+        }
+        for seat in &mut self.pod.seats {
+            if seat.is_some_and(|id| !tournament.players().contains_key(&id)) {
+                *seat = None;
+            }
+        }
+        // Leaves the matchup cleared if the seated decks can no longer play together
+        let _ = self.update_matchup(tournament);
+    }
+
+    pub(crate) const fn clear_caches(&mut self) {
+        self.snapshot = None;
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-pub enum MatchViewPlayer {
-    PlayerA,
-    PlayerB,
-    PlayerC,
-    PlayerD,
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Place(usize);
+
+impl Display for Place {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", ordinal(self.0))
+    }
 }
 
-impl MatchViewPlayer {
-    const PLAYERS: [Self; 4] = [Self::PlayerA, Self::PlayerB, Self::PlayerC, Self::PlayerD];
+#[derive(Clone, PartialEq)]
+enum KnockoutOption<'a> {
+    Deck(RegisteredPlayer<'a>),
+    SelfInflicted,
+    Table,
+}
+
+impl KnockoutOption<'_> {
+    const fn eliminator(&self) -> Eliminator {
+        match self {
+            Self::Deck(player) => Eliminator::Deck(player.id()),
+            Self::SelfInflicted => Eliminator::SelfInflicted,
+            Self::Table => Eliminator::Table,
+        }
+    }
+}
+
+impl Display for KnockoutOption<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Deck(player) => write!(f, "{player}"),
+            Self::SelfInflicted => write!(f, "Self"),
+            Self::Table => write!(f, "Table"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum FirstOption<'a> {
+    Unknown,
+    Seat(usize, RegisteredPlayer<'a>),
+}
 
-    const fn number(self) -> usize {
+impl FirstOption<'_> {
+    const fn seat(&self) -> Option<usize> {
         match self {
-            Self::PlayerA => 0,
-            Self::PlayerB => 1,
-            Self::PlayerC => 2,
-            Self::PlayerD => 3,
+            Self::Unknown => None,
+            Self::Seat(seat, _) => Some(*seat),
         }
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+impl Display for FirstOption<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "Unknown"),
+            Self::Seat(_, player) => write!(f, "{player}"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
 pub enum MatchupMessage {
-    SetPlayer(MatchViewPlayer, Option<u32>),
-    SetWinner(Option<u32>),
+    SetPlayer(usize, Option<u32>),
+    SetOutcome(Option<OutcomeChoice>),
+    SetPlace(usize, Option<usize>),
+    SetEliminatedBy(usize, Option<Eliminator>),
+    ToggleDrawSeat(usize),
+    SetFirst(Option<usize>),
     AddPlayer(u32),
+    SetSession(String),
+    SetTurns(String),
+    SetDuration(String),
+    SetWinCondition(Option<WinCondition>),
+    SetNotes(String),
+    AddSeat,
+    RemoveSeat,
+    RandomizeSeating,
+    Simulate,
+    /// Result of the simulation run with the seed
+    Simulated(u64, Result<Simulation, String>),
+    TogglePreview,
     SubmitGame,
     Clear,
 }
@@ -126,12 +335,29 @@ impl HandleMessage<MatchupMessage> for App {
 
         match msg {
             MatchupMessage::SetPlayer(position, value) => {
-                view.set_player(position, value);
+                view.pod.set_player(position, value);
                 view.update_matchup(&self.tournament)?;
                 Ok(Task::none())
             }
-            MatchupMessage::SetWinner(value) => {
-                view.winner = value;
+            MatchupMessage::SetOutcome(value) => {
+                view.pod.outcome = value;
+                Ok(Task::none())
+            }
+            MatchupMessage::SetPlace(seat, place) => {
+                view.set_place(seat, place);
+                Ok(Task::none())
+            }
+            MatchupMessage::SetEliminatedBy(seat, eliminator) => {
+                view.set_eliminated_by(seat, eliminator);
+                Ok(Task::none())
+            }
+            MatchupMessage::ToggleDrawSeat(seat) => {
+                view.pod.toggle_draw_seat(seat);
+                Ok(Task::none())
+            }
+            MatchupMessage::SetFirst(first) => {
+                view.pod.first = first;
+                view.update_matchup(&self.tournament)?;
                 Ok(Task::none())
             }
             MatchupMessage::AddPlayer(player) => {
@@ -139,19 +365,90 @@ impl HandleMessage<MatchupMessage> for App {
                 view.update_matchup(&self.tournament)?;
                 Ok(Task::none())
             }
+            MatchupMessage::SetSession(session) => {
+                view.session = session;
+                Ok(Task::none())
+            }
+            MatchupMessage::SetTurns(turns) => {
+                view.turns = turns;
+                Ok(Task::none())
+            }
+            MatchupMessage::SetDuration(duration) => {
+                view.duration = duration;
+                Ok(Task::none())
+            }
+            MatchupMessage::SetWinCondition(condition) => {
+                view.win_condition = condition;
+                Ok(Task::none())
+            }
+            MatchupMessage::SetNotes(notes) => {
+                view.notes = notes;
+                Ok(Task::none())
+            }
+            MatchupMessage::RandomizeSeating => {
+                view.randomize_seating(&mut rand::rng());
+                view.update_matchup(&self.tournament)?;
+                Ok(Task::none())
+            }
+            MatchupMessage::Simulate => {
+                let Some(players) = view.pod.players() else {
+                    return Ok(Task::none());
+                };
+                let settings = SimulationSettings {
+                    seed: rand::rng().random(),
+                    ..SimulationSettings::default()
+                };
+                let simulator = self.tournament.pod_simulator(&players, view.pod.first)?;
+                view.simulation = None;
+                view.simulating = Some(settings.seed);
+                Ok(Task::perform(
+                    async move { simulator.run(&settings).map_err(|err| err.to_string()) },
+                    move |result| MatchupMessage::Simulated(settings.seed, result).into(),
+                ))
+            }
+            MatchupMessage::Simulated(seed, result) => {
+                if view.simulating != Some(seed) {
+                    return Ok(Task::none());
+                }
+                view.simulating = None;
+                view.simulation = Some(result.map_err(anyhow::Error::msg)?);
+                Ok(Task::none())
+            }
+            MatchupMessage::TogglePreview => {
+                view.preview = match (&view.preview, &view.matchup) {
+                    (None, Some(matchup)) => Some(self.tournament.preview_outcomes(matchup)?),
+                    _ => None,
+                };
+                Ok(Task::none())
+            }
+            MatchupMessage::AddSeat => {
+                view.add_seat();
+                view.update_matchup(&self.tournament)?;
+                Ok(Task::none())
+            }
+            MatchupMessage::RemoveSeat => {
+                view.remove_seat();
+                view.update_matchup(&self.tournament)?;
+                Ok(Task::none())
+            }
             MatchupMessage::SubmitGame => {
-                let (Some(matchup), Some(winner)) = (&view.matchup, view.winner) else {
+                let (Some(matchup), Some(outcome)) = (&view.matchup, view.pod.outcome()) else {
                     return Ok(Task::none());
                 };
 
-                self.tournament
-                    .register_record(matchup.clone().record(winner)?)?;
-                *view = MatchupView::default();
+                let placements = view.placements(&outcome);
+                let details = view.details(&outcome)?;
+                let record = matchup
+                    .clone()
+                    .record_placements(outcome, placements)?
+                    .with_details(details);
+                self.execute(Command::RegisterGame(record.into()))?;
+                self.home.matchup_view.reset();
 
                 Ok(Task::none())
             }
             MatchupMessage::Clear => {
-                *view = MatchupView::default();
+                view.reset();
 
                 Ok(Task::none())
             }
@@ -159,15 +456,97 @@ impl HandleMessage<MatchupMessage> for App {
     }
 }
 
+impl App {
+    /// Table of how each deck fared over the simulated series
+    fn view_simulation(&self, simulation: &Simulation) -> Element<'_, Message> {
+        let name = |deck: &DeckSimulation| {
+            self.tournament
+                .get_player_name(&deck.id)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let tbl = table(
+            [
+                table::column("Deck", move |deck: DeckSimulation| {
+                    text(name(&deck)).size(12)
+                }),
+                table::column("Elo Change", |deck: DeckSimulation| {
+                    let change = deck.rating_change;
+                    text(format!(
+                        "{:+.0} ({:+.0} to {:+.0})",
+                        change.mean, change.low, change.high
+                    ))
+                    .size(12)
+                }),
+                table::column("Wins", |deck: DeckSimulation| {
+                    text(format!("{:.2}", deck.wins)).size(12)
+                }),
+                table::column("Series", |deck: DeckSimulation| {
+                    text(format!("{:.0}%", deck.series_wins * 100.0)).size(12)
+                }),
+            ],
+            simulation.decks.iter().copied(),
+        );
+        let settings = simulation.settings;
+        column![
+            text(format!(
+                "Over {} games, from {} runs (seed {}):",
+                settings.games, settings.runs, settings.seed
+            ))
+            .size(12),
+            tbl
+        ]
+        .spacing(5)
+        .into()
+    }
+
+    /// Side by side leaderboard changes for each possible winner
+    fn view_outcome_previews(&self, previews: &[OutcomePreview]) -> Element<'_, Message> {
+        let name = |id: u32| {
+            self.tournament
+                .get_player_name(&id)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let standing_line = |standing: &Standing| {
+            let rank = match standing.rank_change {
+                0 => format!("#{}", standing.rank),
+                change => format!("#{} ({change:+})", standing.rank),
+            };
+            let peak = if standing.new_peak { ", new peak" } else { "" };
+            text(format!(
+                "{rank} {} {:.0} ({:+.0}){peak}",
+                name(standing.id),
+                standing.elo,
+                standing.elo_change
+            ))
+            .size(12)
+            .into()
+        };
+
+        row(previews.iter().map(|preview| {
+            column![
+                text(format!("If {} wins", name(preview.winner))).size(14),
+                column(preview.changes().map(standing_line)).spacing(2)
+            ]
+            .spacing(5)
+            .width(Length::Fill)
+            .into()
+        }))
+        .spacing(10)
+        .into()
+    }
+}
+
 impl View<MatchupView> for App {
     fn view<'a>(&'a self, scene: &'a MatchupView) -> iced::Element<'a, Message> {
         let players = self
             .tournament
-            .get_registered_players()
+            .get_active_players()
             .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
             .collect_vec();
 
-        let match_players = MatchViewPlayer::PLAYERS.map(|position| {
+        let match_players = (0..scene.pod.seats.len()).map(|position| {
             let id = scene.get_player(position).copied();
             let entry = id.and_then(|id| self.tournament.get_registered_player(id).ok());
 
@@ -180,15 +559,13 @@ impl View<MatchupView> for App {
                 text(format!("{} Elo, {str_wr}", stats.elo().round()))
             });
 
-            let text_expected = scene.matchup.as_ref().and_then(|matchup| {
-                let player = matchup.players().get(position.number())?;
-
-                Some(text(format!(
+            let text_expected = scene.get_matchup_player(position).map(|player| {
+                text(format!(
                     "Expected: {}% (+{}/-{})",
                     (player.expected() * 100f64).round(),
                     player.elo_win().round(),
                     player.elo_loss().round()
-                )))
+                ))
             });
 
             let player_info = row![
@@ -203,9 +580,45 @@ impl View<MatchupView> for App {
             })
             .width(Length::Fill);
 
-            container(column![
+            let credited = scene.pod.outcome().is_some_and(|outcome| {
+                outcome.credited().is_empty() || id.is_some_and(|id| outcome.is_credited(id))
+            });
+            let place = (!credited && id.is_some()).then(|| {
+                let places = (1..=scene.pod.seats.len()).map(Place).collect_vec();
+                let picked = scene.places.get(position).copied().flatten().map(Place);
+                pick_list(places, picked, move |place| {
+                    MatchupMessage::SetPlace(position, Some(place.0)).into()
+                })
+                .placeholder("Place")
+            });
+            let knocked_out_by = (!credited && id.is_some()).then(|| {
+                let options = scene
+                    .pod
+                    .seats
+                    .iter()
+                    .filter(|seat| **seat != id)
+                    .filter_map(|seat| self.tournament.get_registered_player((*seat)?).ok())
+                    .map(KnockoutOption::Deck)
+                    .chain([KnockoutOption::SelfInflicted, KnockoutOption::Table])
+                    .collect_vec();
+                let picked = scene
+                    .eliminated_by
+                    .get(position)
+                    .copied()
+                    .flatten()
+                    .and_then(|by| options.iter().find(|o| o.eliminator() == by).cloned());
+                pick_list(options, picked, move |option| {
+                    MatchupMessage::SetEliminatedBy(position, Some(option.eliminator())).into()
+                })
+                .placeholder("Knocked out by")
+            });
+
+            Element::from(container(column![
                 row![
+                    text(ordinal(position + 1)).width(35),
                     selector,
+                    place,
+                    knocked_out_by,
                     button("").on_press_maybe(
                         entry
                             .and_then(|entry| entry.info().moxfield_goldfish_link())
@@ -213,39 +626,118 @@ impl View<MatchupView> for App {
                     )
                 ],
                 player_info
-            ])
-            .into()
+            ]))
         });
 
         let players = column(match_players).spacing(15);
 
-        let title = text("Record Game")
-            .size(20)
-            .align_x(Alignment::Center)
-            .width(Length::Fill);
+        let title = row![
+            text("Record Game")
+                .size(20)
+                .align_x(Alignment::Center)
+                .width(Length::Fill),
+            button("Randomize Seating").on_press(MatchupMessage::RandomizeSeating.into()),
+            button(if scene.preview.is_some() {
+                "Hide What If"
+            } else {
+                "What If"
+            })
+            .on_press_maybe(
+                scene
+                    .matchup
+                    .is_some()
+                    .then_some(MatchupMessage::TogglePreview.into())
+            ),
+            button(if scene.simulating.is_some() {
+                "Simulating..."
+            } else {
+                "Simulate"
+            })
+            .on_press_maybe(
+                (scene.matchup.is_some() && scene.simulating.is_none())
+                    .then_some(MatchupMessage::Simulate.into())
+            ),
+            button("-").on_press_maybe(
+                (scene.pod.seats.len() > MIN_POD_SIZE).then_some(MatchupMessage::RemoveSeat.into())
+            ),
+            button("+").on_press_maybe(
+                (scene.pod.seats.len() < MAX_POD_SIZE).then_some(MatchupMessage::AddSeat.into())
+            ),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center);
 
-        let current_players = MatchViewPlayer::PLAYERS
+        let outcome_options = scene
+            .pod
+            .seats
             .iter()
-            .filter_map(|player| scene.get_player(*player).copied())
+            .filter_map(|player| *player)
             .filter_map(|id| self.tournament().get_registered_player(id).ok())
+            .map(OutcomeOption::Winner)
+            .chain([OutcomeOption::Draw, OutcomeOption::NoResult])
             .collect_vec();
-        let winner = scene
-            .winner
-            .and_then(|id| self.tournament().get_registered_player(id).ok());
+        let outcome = scene.pod.outcome.and_then(|choice| {
+            outcome_options
+                .iter()
+                .find(|option| option.choice() == choice)
+                .cloned()
+        });
+
+        let draw_seats = matches!(scene.pod.outcome, Some(OutcomeChoice::Draw)).then(|| {
+            row(scene
+                .pod
+                .seats
+                .iter()
+                .enumerate()
+                .filter_map(|(seat, id)| Some((seat, self.tournament().get_player_name(&(*id)?)?)))
+                .map(|(seat, name)| {
+                    let style = if scene.pod.draw_excluded.contains(&seat) {
+                        button::secondary
+                    } else {
+                        button::primary
+                    };
+                    button(text(name).size(12))
+                        .style(style)
+                        .on_press(MatchupMessage::ToggleDrawSeat(seat).into())
+                        .into()
+                }))
+            .spacing(5)
+            .wrap()
+        });
+
+        let first_options = once(FirstOption::Unknown)
+            .chain(scene.pod.seats.iter().enumerate().filter_map(|(seat, id)| {
+                let player = self.tournament().get_registered_player((*id)?).ok()?;
+                Some(FirstOption::Seat(seat, player))
+            }))
+            .collect_vec();
+        let first = first_options
+            .iter()
+            .find(|option| option.seat() == scene.pod.first)
+            .cloned();
+        let went_first = row![
+            text("Went first: ").size(17),
+            pick_list(first_options, first, |picked| {
+                MatchupMessage::SetFirst(picked.seat()).into()
+            })
+            .width(Length::Fill),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
 
         let winner = row![
             text("Winner: ").size(17),
-            pick_list(current_players, winner, |picked| {
-                MatchupMessage::SetWinner(Some(picked.id())).into()
+            pick_list(outcome_options, outcome, |picked| {
+                MatchupMessage::SetOutcome(Some(picked.choice())).into()
             })
             .width(Length::Fill),
             button("Submit").on_press_maybe(
-                (scene.matchup.is_some() && scene.winner.is_some())
+                scene
+                    .can_submit()
                     .then_some(MatchupMessage::SubmitGame.into())
             ),
             button("󱄀").on_press_maybe({
-                let links = MatchViewPlayer::PLAYERS
-                    .into_iter()
+                let links = (0..scene.pod.seats.len())
                     .filter_map(|position| {
                         let id = scene.get_player(position)?;
                         let info = self.tournament().get_player_info(id)?;
@@ -263,8 +755,340 @@ impl View<MatchupView> for App {
         .spacing(10)
         .align_y(Vertical::Center);
 
-        container(column![title, players, winner].spacing(10))
-            .padding(10)
-            .into()
+        let session = row![
+            text("Session: ").size(17),
+            text_input("Game night", &scene.session)
+                .on_input(|session| MatchupMessage::SetSession(session).into()),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let metadata = row![
+            text_input("Turn", &scene.turns)
+                .on_input(|turns| MatchupMessage::SetTurns(turns).into())
+                .width(60),
+            text_input("Minutes", &scene.duration)
+                .on_input(|duration| MatchupMessage::SetDuration(duration).into())
+                .width(80),
+            pick_list(WinCondition::VALUES, scene.win_condition, |condition| {
+                MatchupMessage::SetWinCondition(Some(condition)).into()
+            })
+            .placeholder("Win Condition"),
+            text_input("Notes", &scene.notes)
+                .on_input(|notes| MatchupMessage::SetNotes(notes).into()),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let preview = scene
+            .preview
+            .as_ref()
+            .map(|previews| self.view_outcome_previews(previews));
+        let simulation = scene
+            .simulation
+            .as_ref()
+            .map(|simulation| self.view_simulation(simulation));
+
+        container(
+            column![
+                title, players, went_first, preview, simulation, winner, draw_seats, metadata,
+                session
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use edh_tourn::game::{MAX_POD_SIZE, MIN_POD_SIZE};
+
+    use edh_tourn::{
+        command::Command,
+        game::{
+            elimination::{Elimination, Eliminator},
+            outcome::GameOutcome,
+        },
+        player::info::PlayerInfo,
+        simulation::SimulationSettings,
+    };
+    use itertools::Itertools;
+
+    use crate::{
+        App,
+        logic::Message,
+        view::{
+            home::matchup::{MatchupMessage, MatchupView},
+            seats::OutcomeChoice,
+        },
+    };
+
+    #[test]
+    fn seats_stay_within_pod_sizes() {
+        let mut view = MatchupView::default();
+        for _ in 0..10 {
+            view.add_seat();
+        }
+        assert_eq!(MAX_POD_SIZE, view.pod.seats.len());
+        for _ in 0..10 {
+            view.remove_seat();
+        }
+        assert_eq!(MIN_POD_SIZE, view.pod.seats.len());
+    }
+
+    #[test]
+    fn removing_seat_clears_missing_winner() {
+        let mut view = MatchupView::default();
+        for id in 0..4 {
+            view.add_player(id);
+        }
+        view.pod.outcome = Some(OutcomeChoice::Winner(3));
+        view.remove_seat();
+        assert_eq!(None, view.pod.outcome);
+    }
+
+    #[test]
+    fn draw_leaves_out_excluded_seats() {
+        let mut view = MatchupView::default();
+        for id in 0..4 {
+            view.add_player(id);
+        }
+        view.pod.outcome = Some(OutcomeChoice::Draw);
+        assert_eq!(
+            Some(GameOutcome::Draw(vec![0, 1, 2, 3])),
+            view.pod.outcome()
+        );
+
+        view.pod.toggle_draw_seat(1);
+        assert_eq!(Some(GameOutcome::Draw(vec![0, 2, 3])), view.pod.outcome());
+
+        view.pod.toggle_draw_seat(1);
+        assert_eq!(
+            Some(GameOutcome::Draw(vec![0, 1, 2, 3])),
+            view.pod.outcome()
+        );
+    }
+
+    #[test]
+    fn reset_keeps_session() {
+        let mut view = MatchupView::default();
+        view.add_player(0);
+        view.session = " Friday ".to_owned();
+        let details = view.details(&GameOutcome::NoResult).unwrap();
+        assert_eq!(Some("Friday"), details.session());
+        assert!(details.played_at().is_some());
+
+        view.turns = String::new();
+        view.duration = " 45 ".to_owned();
+        let details = view.details(&GameOutcome::NoResult).unwrap();
+        assert_eq!(None, details.turns());
+        assert_eq!(Some(45), details.duration());
+
+        view.reset();
+        assert_eq!(None, view.get_player(0));
+        assert!(view.duration.is_empty());
+        assert_eq!(" Friday ", view.session);
+    }
+
+    #[test]
+    fn typos_in_numbers_block_submitting() {
+        let (mut app, ids) = App::test_with_tournament(6, 12);
+        for id in &ids[..4] {
+            app.test_update(MatchupMessage::AddPlayer(*id)).unwrap();
+        }
+        app.test_updates([
+            MatchupMessage::SetOutcome(Some(OutcomeChoice::Winner(ids[0]))),
+            MatchupMessage::SetDuration("45m".to_owned()),
+        ])
+        .unwrap();
+        assert!(!app.home.matchup_view.can_submit());
+        app.test_update(MatchupMessage::SubmitGame).unwrap_err();
+        assert_eq!(12, app.tournament.games().len());
+
+        app.test_update(MatchupMessage::SetDuration("45".to_owned()))
+            .unwrap();
+        assert!(app.home.matchup_view.can_submit());
+        app.test_update(MatchupMessage::SubmitGame).unwrap();
+        let game = app.tournament.games().last().unwrap();
+        assert_eq!(Some(45), game.details().duration());
+    }
+
+    #[test]
+    fn eliminations_follow_places() {
+        let mut view = MatchupView::default();
+        for id in 0..4 {
+            view.add_player(id);
+        }
+        view.set_place(1, Some(2));
+        view.set_place(2, Some(4));
+        view.set_place(3, Some(3));
+        view.set_eliminated_by(1, Some(Eliminator::Deck(0)));
+        view.set_eliminated_by(2, Some(Eliminator::Table));
+        view.set_eliminated_by(3, Some(Eliminator::Deck(0)));
+        view.set_eliminated_by(0, Some(Eliminator::SelfInflicted));
+
+        assert_eq!(
+            vec![
+                Elimination::new(2, Eliminator::Table),
+                Elimination::new(3, Eliminator::Deck(0)),
+                Elimination::new(1, Eliminator::Deck(0)),
+            ],
+            view.eliminations(&GameOutcome::Win(0))
+        );
+
+        view.pod.set_player(0, Some(5));
+        assert_eq!(
+            vec![Elimination::new(2, Eliminator::Table)],
+            view.eliminations(&GameOutcome::Win(5))
+        );
+    }
+
+    #[test]
+    fn randomize_seating_keeps_places_with_seats() {
+        let mut view = MatchupView::default();
+        for id in 0..4 {
+            view.add_player(id);
+            view.set_place(id as usize, Some(4 - id as usize));
+        }
+        view.pod.toggle_draw_seat(0);
+        view.randomize_seating(&mut rand::rng());
+
+        assert_eq!(4, view.pod.seats.len());
+        for (seat, place) in view.pod.seats.iter().zip(&view.places) {
+            assert_eq!(Some(4 - seat.unwrap() as usize), *place);
+        }
+        assert!(view.pod.draw_excluded.is_empty());
+    }
+
+    #[test]
+    fn placements_follow_places_without_winner() {
+        let mut view = MatchupView::default();
+        for id in 0..4 {
+            view.add_player(id);
+        }
+        view.set_place(0, Some(4));
+        view.set_place(1, Some(1));
+        view.set_place(3, Some(2));
+        assert_eq!(vec![3, 0], view.placements(&GameOutcome::Win(1)));
+        assert_eq!(vec![1, 3, 0], view.placements(&GameOutcome::Win(2)));
+    }
+
+    #[test]
+    fn simulation_clears_when_seats_change() {
+        let (mut app, ids) = App::test_with_tournament(6, 12);
+        for id in &ids[..4] {
+            app.test_update(MatchupMessage::AddPlayer(*id)).unwrap();
+        }
+
+        app.test_update(MatchupMessage::Simulate).unwrap();
+        let seed = app.home.matchup_view.simulating.unwrap();
+        let settings = SimulationSettings {
+            seed,
+            ..SimulationSettings::default()
+        };
+        let result = app.tournament.simulate(&ids[..4], None, &settings);
+        let result = result.map_err(|err| err.to_string());
+
+        // Results of an older run are dropped
+        app.test_update(MatchupMessage::Simulated(seed + 1, result.clone()))
+            .unwrap();
+        assert!(app.home.matchup_view.simulation.is_none());
+        app.test_update(MatchupMessage::Simulated(seed, result))
+            .unwrap();
+        assert!(app.home.matchup_view.simulating.is_none());
+        let simulation = app.home.matchup_view.simulation.as_ref().unwrap();
+        assert_eq!(
+            &ids[..4],
+            simulation.decks.iter().map(|d| d.id).collect_vec()
+        );
+        assert_eq!(12, app.tournament.games().len());
+
+        app.test_update(MatchupMessage::SetPlayer(0, Some(ids[4])))
+            .unwrap();
+        assert!(app.home.matchup_view.simulation.is_none());
+    }
+
+    #[test]
+    fn preview_toggles_without_changing_tournament() {
+        let (mut app, ids) = App::test_with_tournament(6, 12);
+        let tourn = app.tournament.clone();
+        for id in &ids[..4] {
+            app.test_update(MatchupMessage::AddPlayer(*id)).unwrap();
+        }
+
+        app.test_update(MatchupMessage::TogglePreview).unwrap();
+        let previews = app.home.matchup_view.preview.as_ref().unwrap();
+        assert_eq!(&ids[..4], previews.iter().map(|p| p.winner).collect_vec());
+        assert_eq!(tourn, app.tournament);
+
+        app.test_update(MatchupMessage::TogglePreview).unwrap();
+        assert!(app.home.matchup_view.preview.is_none());
+    }
+
+    #[test]
+    fn matchup_is_worked_out_again_after_undo() {
+        let (mut app, ids) = App::test_with_tournament(6, 12);
+        app.execute(Command::UnregisterPlayer(ids[5])).unwrap();
+        app.refresh_caches();
+        for id in &ids[..4] {
+            app.test_update(MatchupMessage::AddPlayer(*id)).unwrap();
+        }
+        app.test_update(MatchupMessage::TogglePreview).unwrap();
+        let before = app.home.matchup_view.matchup.clone();
+
+        let _ = app.updater(Message::Undo);
+        let view = &app.home.matchup_view;
+        assert!(view.preview.is_none());
+        assert!(view.matchup.is_some());
+        assert_ne!(before, view.matchup);
+        assert_eq!(Some(app.tournament.snapshot()), view.snapshot);
+    }
+
+    #[test]
+    fn seats_of_removed_decks_are_emptied_after_undo() {
+        let (mut app, _) = App::test_with_tournament(6, 12);
+        let name = "New Deck".to_owned();
+        let mut info = PlayerInfo::default();
+        info.set_name(name.clone());
+        app.execute(Command::RegisterPlayer(info)).unwrap();
+        let id = app.tournament.get_player_id(&name).unwrap();
+        app.refresh_caches();
+        app.test_update(MatchupMessage::AddPlayer(id)).unwrap();
+
+        let _ = app.updater(Message::Undo);
+        assert_eq!(None, app.home.matchup_view.get_player(0));
+        assert!(app.home.matchup_view.matchup.is_none());
+    }
+
+    #[test]
+    fn first_seat_is_only_recorded_when_picked() {
+        let (mut app, ids) = App::test_with_tournament(6, 12);
+        let submit = |app: &mut App| {
+            for id in &ids[..4] {
+                app.test_update(MatchupMessage::AddPlayer(*id)).unwrap();
+            }
+            app.test_updates([
+                MatchupMessage::SetOutcome(Some(OutcomeChoice::Winner(ids[0]))),
+                MatchupMessage::SubmitGame,
+            ])
+            .unwrap();
+            app.tournament.games().last().unwrap().matchup().first()
+        };
+        assert_eq!(None, submit(&mut app));
+
+        app.test_update(MatchupMessage::SetFirst(Some(2))).unwrap();
+        assert_eq!(Some(2), submit(&mut app));
+
+        // Removing the seat that went first forgets it
+        app.test_updates([
+            MatchupMessage::SetFirst(Some(3)),
+            MatchupMessage::RemoveSeat,
+        ])
+        .unwrap();
+        assert_eq!(None, app.home.matchup_view.pod.first);
     }
 }
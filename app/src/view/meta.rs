@@ -0,0 +1,229 @@
+use core::fmt::Display;
+
+use edh_tourn::{
+    meta::{ColorMatchup, GroupRecord, MetaReport},
+    player::color::MtgColor,
+};
+use iced::{
+    Element, Length, Task,
+    widget::{button, column, container, pick_list, row, scrollable, space, table, text},
+};
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::Scene,
+};
+
+/// Width of each cell of the matchup grid
+const CELL_WIDTH: f32 = 70.0;
+
+/// How the decks are grouped in the table
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Grouping {
+    #[default]
+    Color,
+    ColorCount,
+    Identity,
+}
+
+impl Grouping {
+    const ALL: [Self; 3] = [Self::Color, Self::ColorCount, Self::Identity];
+}
+
+impl Display for Grouping {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Color => write!(f, "By Color"),
+            Self::ColorCount => write!(f, "By Color Count"),
+            Self::Identity => write!(f, "By Identity"),
+        }
+    }
+}
+
+/// Shows how each color, color count and identity has done across the history
+#[derive(Debug)]
+pub struct MetaScene {
+    grouping: Grouping,
+    report: MetaReport,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MetaMessage {
+    Open,
+    SetGrouping(Grouping),
+    Close,
+}
+
+impl From<MetaMessage> for Message {
+    fn from(value: MetaMessage) -> Self {
+        Self::Meta(value)
+    }
+}
+
+impl HandleMessage<MetaMessage> for App {
+    fn update(&mut self, msg: MetaMessage) -> anyhow::Result<Task<Message>> {
+        match msg {
+            MetaMessage::Open => {
+                self.scenes.push(Scene::Meta(MetaScene {
+                    grouping: Grouping::default(),
+                    report: self.tournament.meta(),
+                }));
+            }
+            MetaMessage::SetGrouping(grouping) => {
+                if let Some(Scene::Meta(scene)) = self.scenes.last_mut() {
+                    scene.grouping = grouping;
+                }
+            }
+            MetaMessage::Close => {
+                if let Some(Scene::Meta(_)) = self.scenes.last() {
+                    self.scenes.pop();
+                }
+            }
+        }
+        Message::done()
+    }
+}
+
+/// Name of a group of decks playing `count` colors
+fn color_count_name(count: usize) -> String {
+    match count {
+        0 => "Colorless".to_owned(),
+        1 => "Mono".to_owned(),
+        5 => "WUBRG".to_owned(),
+        count => format!("{count} Colors"),
+    }
+}
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(
+        || "--%".to_owned(),
+        |value| format!("{:.1}%", value * 100.0),
+    )
+}
+
+/// Win rate of each color against every other, row color first
+fn matchup_grid(report: &MetaReport) -> Element<'_, Message> {
+    let cell = |label: String| text(label).size(12).width(CELL_WIDTH).center();
+    let header = row(core::iter::once(cell(String::new()).into()).chain(
+        MtgColor::COLORS
+            .iter()
+            .map(|color| cell(color.letter().to_owned()).into()),
+    ));
+    let rows = MtgColor::COLORS.iter().map(|color| {
+        row(
+            core::iter::once(cell(color.letter().to_owned()).into()).chain(
+                MtgColor::COLORS.iter().map(|opponent| {
+                    let rate = report
+                        .matchup(*color, *opponent)
+                        .and_then(ColorMatchup::win_rate);
+                    cell(percent(rate)).into()
+                }),
+            ),
+        )
+        .into()
+    });
+    column![header, column(rows).spacing(4)].spacing(4).into()
+}
+
+impl View<MetaScene> for App {
+    fn view<'a>(&'a self, scene: &'a MetaScene) -> Element<'a, Message> {
+        let menu_bar = row![
+            pick_list(Grouping::ALL, Some(scene.grouping), |grouping| {
+                MetaMessage::SetGrouping(grouping).into()
+            }),
+            space().width(Length::Fill),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(MetaMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Meta").width(Length::Fill).center().size(50);
+
+        let rows: Vec<(String, GroupRecord)> = match scene.grouping {
+            Grouping::Color => scene
+                .report
+                .by_color
+                .iter()
+                .map(|(color, record)| (format!("{color:?}"), *record))
+                .collect(),
+            Grouping::ColorCount => scene
+                .report
+                .by_count
+                .iter()
+                .map(|(count, record)| (color_count_name(*count), *record))
+                .collect(),
+            Grouping::Identity => scene
+                .report
+                .by_identity
+                .iter()
+                .map(|(identity, record)| (identity.to_string(), *record))
+                .collect(),
+        };
+        let groups = table(
+            [
+                table::column("Group", |(name, _): (String, GroupRecord)| text(name)),
+                table::column("Decks", |(_, r): (String, GroupRecord)| text(r.decks)),
+                table::column("Games", |(_, r): (String, GroupRecord)| text(r.games)),
+                table::column("Wins", |(_, r): (String, GroupRecord)| text(r.wins)),
+                table::column("Win Rate", |(_, r): (String, GroupRecord)| {
+                    text(percent(r.win_rate()))
+                }),
+                table::column("Avg Elo", |(_, r): (String, GroupRecord)| {
+                    text(
+                        r.average_rating()
+                            .map_or_else(|| "--".to_owned(), |elo| format!("{elo:.0}")),
+                    )
+                }),
+            ],
+            rows,
+        );
+
+        let matchups = column![
+            text("Color vs Color").size(24),
+            text("Share of the games decided between the two decks won by the row color").size(12),
+            matchup_grid(&scene.report),
+        ]
+        .spacing(10);
+
+        container(
+            scrollable(
+                column![menu_bar, title, groups, matchups]
+                    .spacing(20)
+                    .width(Length::Fill),
+            )
+            .height(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        App,
+        view::Scene,
+        view::meta::{Grouping, MetaMessage},
+    };
+
+    #[test]
+    fn open_and_regroup() {
+        let (mut app, _) = App::test_with_tournament(5, 10);
+
+        app.test_updates([
+            MetaMessage::Open,
+            MetaMessage::SetGrouping(Grouping::Identity),
+        ])
+        .unwrap();
+        let Some(Scene::Meta(scene)) = app.scenes.last() else {
+            panic!("Meta should be open");
+        };
+        assert_eq!(Grouping::Identity, scene.grouping);
+        assert_eq!(app.tournament.meta(), scene.report);
+
+        app.test_update(MetaMessage::Close).unwrap();
+        assert!(app.scenes.is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+use core::iter::repeat_n;
+
+use edh_tourn::{
+    Tournament,
+    game::DEFAULT_POD_SIZE,
+    planner::{PlannedPod, PodPlan},
+};
+use iced::{
+    Element, Length, Task,
+    widget::{button, checkbox, column, container, row, scrollable, space, text},
+};
+use itertools::{Itertools, chain};
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::{Scene, home::matchup::MatchupMessage},
+};
+
+/// Splits the decks brought to game night into pods
+#[derive(Debug, Default)]
+pub struct PlannerScene {
+    attending: Vec<u32>,
+    plan: Option<PodPlan>,
+    /// Why the attending decks couldn't be planned
+    problem: Option<String>,
+}
+
+impl PlannerScene {
+    fn replan(&mut self, tournament: &Tournament) {
+        if self.attending.is_empty() {
+            self.plan = None;
+            self.problem = None;
+            return;
+        }
+        match tournament.plan_pods(&self.attending) {
+            Ok(plan) => {
+                self.plan = Some(plan);
+                self.problem = None;
+            }
+            Err(err) => {
+                self.plan = None;
+                self.problem = Some(err.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlannerMessage {
+    Open,
+    SetAttending(u32, bool),
+    AttendAll,
+    AttendNone,
+    LoadPod(usize),
+    Close,
+}
+
+impl From<PlannerMessage> for Message {
+    fn from(value: PlannerMessage) -> Self {
+        Self::Planner(value)
+    }
+}
+
+impl HandleMessage<PlannerMessage> for App {
+    fn update(&mut self, msg: PlannerMessage) -> anyhow::Result<Task<Message>> {
+        match msg {
+            PlannerMessage::Open => {
+                self.scenes.push(Scene::Planner(PlannerScene::default()));
+            }
+            PlannerMessage::SetAttending(id, attending) => {
+                if let Some(Scene::Planner(scene)) = self.scenes.last_mut() {
+                    scene.attending.retain(|other| *other != id);
+                    if attending {
+                        scene.attending.push(id);
+                    }
+                    scene.replan(&self.tournament);
+                }
+            }
+            PlannerMessage::AttendAll => {
+                if let Some(Scene::Planner(scene)) = self.scenes.last_mut() {
+                    scene.attending = self
+                        .tournament
+                        .get_active_players()
+                        .map(|player| player.id())
+                        .sorted()
+                        .collect();
+                    scene.replan(&self.tournament);
+                }
+            }
+            PlannerMessage::AttendNone => {
+                if let Some(Scene::Planner(scene)) = self.scenes.last_mut() {
+                    scene.attending.clear();
+                    scene.replan(&self.tournament);
+                }
+            }
+            PlannerMessage::LoadPod(index) => {
+                let Some(Scene::Planner(scene)) = self.scenes.last() else {
+                    return Message::done();
+                };
+                let Some(pod) = scene.plan.as_ref().and_then(|plan| plan.pods.get(index)) else {
+                    return Message::done();
+                };
+                let size = pod.ids.len();
+                let matchup_updates = chain!(
+                    [MatchupMessage::Clear],
+                    repeat_n(
+                        MatchupMessage::AddSeat,
+                        size.saturating_sub(DEFAULT_POD_SIZE)
+                    ),
+                    repeat_n(
+                        MatchupMessage::RemoveSeat,
+                        DEFAULT_POD_SIZE.saturating_sub(size)
+                    ),
+                    pod.ids.iter().copied().map(MatchupMessage::AddPlayer),
+                )
+                .collect_vec();
+                self.scenes.pop();
+
+                let mut tasks = Vec::new();
+                for msg in matchup_updates {
+                    tasks.push(self.update(msg)?);
+                }
+                return Ok(Task::batch(tasks));
+            }
+            PlannerMessage::Close => {
+                if let Some(Scene::Planner(_)) = self.scenes.last() {
+                    self.scenes.pop();
+                }
+            }
+        }
+        Message::done()
+    }
+}
+
+impl App {
+    fn view_planned_pod(&self, index: usize, pod: &PlannedPod) -> Element<'_, Message> {
+        let names = pod.ids.iter().map(|id| {
+            text(
+                self.tournament
+                    .get_player_name(id)
+                    .cloned()
+                    .unwrap_or_default(),
+            )
+            .into()
+        });
+        container(
+            column![
+                row![
+                    text(format!("Pod {}", index + 1)).size(18),
+                    space().width(Length::Fill),
+                    text(format!("Cost {:.1}", pod.cost)).size(12),
+                ]
+                .spacing(10),
+                column(names).spacing(4),
+                button(text("Load")).on_press(PlannerMessage::LoadPod(index).into()),
+            ]
+            .spacing(8),
+        )
+        .padding(10)
+        .width(220)
+        .style(container::bordered_box)
+        .into()
+    }
+}
+
+impl View<PlannerScene> for App {
+    fn view<'a>(&'a self, scene: &'a PlannerScene) -> Element<'a, Message> {
+        let menu_bar = row![
+            button(text("All")).on_press(PlannerMessage::AttendAll.into()),
+            button(text("None")).on_press(PlannerMessage::AttendNone.into()),
+            space().width(Length::Fill),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(PlannerMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Pod Planner").width(Length::Fill).center().size(50);
+
+        let decks = self
+            .tournament
+            .get_active_players()
+            .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
+            .map(|player| {
+                let id = player.id();
+                let label = if self.tournament.constraints().is_required(id) {
+                    format!("{} (must play next)", player.info().name())
+                } else {
+                    player.info().name().to_owned()
+                };
+                checkbox(scene.attending.contains(&id))
+                    .label(label)
+                    .on_toggle(move |attending| PlannerMessage::SetAttending(id, attending).into())
+                    .into()
+            });
+        let attending = column![
+            text(format!("{} attending", scene.attending.len())).size(18),
+            scrollable(column(decks).spacing(6)).height(Length::Fill),
+        ]
+        .spacing(10)
+        .width(250);
+
+        let plan: Element<'_, Message> = match (&scene.plan, &scene.problem) {
+            (Some(plan), _) => column![
+                text(format!(
+                    "{} pods, total cost {:.1}",
+                    plan.pods.len(),
+                    plan.cost()
+                ))
+                .size(12),
+                scrollable(
+                    row(plan
+                        .pods
+                        .iter()
+                        .enumerate()
+                        .map(|(index, pod)| self.view_planned_pod(index, pod)))
+                    .spacing(10)
+                    .wrap()
+                ),
+            ]
+            .spacing(10)
+            .into(),
+            (None, Some(problem)) => text(problem).into(),
+            (None, None) => text("Pick the decks at the table").into(),
+        };
+
+        container(
+            column![
+                menu_bar,
+                title,
+                row![attending, container(plan).width(Length::Fill)].spacing(20)
+            ]
+            .spacing(20)
+            .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use crate::{
+        App,
+        view::{
+            Scene, home::matchup::MatchupMessage, planner::PlannerMessage, seats::OutcomeChoice,
+        },
+    };
+
+    #[test]
+    fn plan_and_load_a_pod() {
+        let (mut app, ids) = App::test_with_tournament(7, 20);
+
+        app.test_updates([
+            PlannerMessage::Open,
+            PlannerMessage::AttendAll,
+            PlannerMessage::AttendNone,
+        ])
+        .unwrap();
+        for id in ids.iter().take(2) {
+            app.test_update(PlannerMessage::SetAttending(*id, true))
+                .unwrap();
+        }
+        let Some(Scene::Planner(scene)) = app.scenes.last() else {
+            panic!("Planner should be open");
+        };
+        assert!(scene.plan.is_none());
+        assert!(scene.problem.is_some());
+
+        for id in &ids[2..5] {
+            app.test_update(PlannerMessage::SetAttending(*id, true))
+                .unwrap();
+        }
+        let Some(Scene::Planner(scene)) = app.scenes.last() else {
+            panic!("Planner should be open");
+        };
+        let plan = scene.plan.clone().unwrap();
+        assert_eq!(1, plan.pods.len());
+
+        app.test_update(PlannerMessage::LoadPod(0)).unwrap();
+        assert!(app.scenes.is_empty());
+
+        // The loaded pod can be submitted as it is, with a seat added for the fifth deck
+        let pod = &plan.pods[0].ids;
+        assert_eq!(5, pod.len());
+        app.test_updates([
+            MatchupMessage::SetOutcome(Some(OutcomeChoice::Winner(pod[0]))),
+            MatchupMessage::SubmitGame,
+        ])
+        .unwrap();
+        assert_eq!(21, app.tournament.games().len());
+        assert_eq!(*pod, app.tournament.games()[20].ids());
+    }
+}
@@ -1,36 +1,58 @@
-use std::borrow::ToOwned;
+use std::{
+    borrow::{Cow, ToOwned},
+    collections::HashMap,
+};
 
 use edh_tourn::{
     Tournament,
+    command::Command,
     error::TournamentError,
-    game::record::GameRecord,
-    player::{color::MtgColor, info::PlayerInfo},
+    game::{outcome::GameOutcome, record::GameRecord, seat::SeatStats},
+    pilot::PilotInfo,
+    player::{RegisteredPlayer, color::MtgColor, history::RatingPoint, info::PlayerInfo},
 };
 use iced::{
     Element, Length,
     alignment::{Horizontal, Vertical},
     font,
     widget::{
-        button, column, container, row, scrollable, space, table, text, text_editor, text_input,
+        button, canvas, column, container, pick_list, row, scrollable, space, table, text,
+        text_editor, text_input,
     },
 };
 use itertools::Itertools;
 
 use crate::{
     App,
+    cache::Cached,
     fonts::default_font,
     logic::Message,
     traits::{HandleMessage, View},
-    view::{Scene, confirm::ConfirmPrompt},
+    view::{
+        Scene,
+        confirm::ConfirmPrompt,
+        ordinal,
+        player::chart::{RatingChart, Series, series_color},
+    },
 };
 
+mod chart;
+
+/// A game of the deck, with its rating around it if the game had a result
+type GameRow<'a> = (&'a GameRecord, Option<RatingPoint>);
+
 #[derive(Clone, Debug)]
 pub struct ViewPlayerScene {
     player: Option<u32>,
     name: Option<String>,
     edit_description: text_editor::Content,
     moxfield: String,
+    owner: String,
     info: PlayerInfo,
+    /// Other decks overlaid on the rating chart
+    compare: Vec<u32>,
+    /// Rating history of every deck, for the games table and chart
+    histories: Cached<HashMap<u32, Vec<RatingPoint>>>,
 }
 
 impl From<ViewPlayerScene> for Scene {
@@ -51,9 +73,16 @@ impl ViewPlayerScene {
                 Self {
                     player: Some(id),
                     moxfield: info.moxfield_id().cloned().unwrap_or_default(),
+                    owner: info
+                        .owner()
+                        .and_then(|owner| tournament.get_pilot_info(owner))
+                        .map(|pilot| pilot.name().to_owned())
+                        .unwrap_or_default(),
                     name: Some(info.name().to_owned()),
                     edit_description: text_editor::Content::with_text(info.description()),
                     info,
+                    compare: Vec::new(),
+                    histories: Cached::default(),
                 }
             }
             None => Self {
@@ -61,10 +90,32 @@ impl ViewPlayerScene {
                 name: None,
                 edit_description: text_editor::Content::new(),
                 moxfield: String::new(),
+                owner: String::new(),
                 info: PlayerInfo::default(),
+                compare: Vec::new(),
+                histories: Cached::default(),
             },
         })
     }
+
+    pub(crate) fn refresh(&mut self, tournament: &Tournament) {
+        if self.player.is_some() {
+            self.histories
+                .refresh(tournament, Tournament::rating_histories);
+        }
+    }
+
+    pub(crate) fn clear_caches(&mut self) {
+        self.histories.clear();
+    }
+
+    /// Rating histories of the tournament as it is now. Only worked out here when the tournament
+    /// changed without going through the updater.
+    fn histories<'a>(&'a self, tournament: &Tournament) -> Cow<'a, HashMap<u32, Vec<RatingPoint>>> {
+        self.histories
+            .get(tournament)
+            .map_or_else(|| Cow::Owned(tournament.rating_histories()), Cow::Borrowed)
+    }
 }
 #[derive(Clone)]
 pub enum ViewPlayerMessage {
@@ -74,9 +125,13 @@ pub enum ViewPlayerMessage {
     SetName(String),
     EditDescription(text_editor::Action),
     SetMoxfieldId(String),
+    SetOwner(String),
     ToggleColor(MtgColor),
     ConfirmedDelete,
     Delete,
+    ToggleRetired,
+    AddComparison(u32),
+    RemoveComparison(u32),
 }
 
 impl From<ViewPlayerMessage> for Message {
@@ -113,12 +168,27 @@ impl HandleMessage<ViewPlayerMessage> for App {
                 if !scene.moxfield.is_empty() {
                     scene.info.set_moxfield_id(scene.moxfield.clone());
                 }
-                if let Some(id) = scene.player {
-                    self.tournament.set_player_info(id, scene.info.clone())?;
+                // A new owner is registered along with the deck, so both are undone together
+                let owner = scene.owner.trim();
+                let mut commands = Vec::new();
+                if owner.is_empty() {
+                    scene.info.set_owner(None);
+                } else if let Some(id) = self.tournament.get_pilot_id(owner) {
+                    scene.info.set_owner(Some(id));
                 } else {
-                    self.tournament
-                        .register_player_with_info(scene.info.clone())?;
+                    commands.push(Command::RegisterPilot(PilotInfo::new(owner.to_owned())));
+                    scene.info.set_owner(Some(self.tournament.next_pilot_id()));
                 }
+                commands.push(match scene.player {
+                    Some(id) => Command::SetPlayerInfo(id, scene.info.clone()),
+                    None => Command::RegisterPlayer(scene.info.clone()),
+                });
+                let command = if commands.len() == 1 {
+                    commands.remove(0)
+                } else {
+                    Command::Batch(commands)
+                };
+                self.execute(command)?;
 
                 self.scenes.pop();
 
@@ -140,6 +210,10 @@ impl HandleMessage<ViewPlayerMessage> for App {
                 scene.moxfield = text;
                 Message::done()
             }
+            ViewPlayerMessage::SetOwner(text) => {
+                scene.owner = text;
+                Message::done()
+            }
             ViewPlayerMessage::ToggleColor(color) => {
                 scene.info.toggle_color(color);
                 Message::done()
@@ -152,9 +226,33 @@ impl HandleMessage<ViewPlayerMessage> for App {
                     ViewPlayerMessage::ConfirmedDelete.into())));
                 Message::done()
             }
+            ViewPlayerMessage::AddComparison(id) => {
+                if scene.player != Some(id) && !scene.compare.contains(&id) {
+                    scene.compare.push(id);
+                }
+                Message::done()
+            }
+            ViewPlayerMessage::RemoveComparison(id) => {
+                scene.compare.retain(|compared| *compared != id);
+                Message::done()
+            }
+            ViewPlayerMessage::ToggleRetired => {
+                let archived = !scene.info.is_archived();
+                if let Some(id) = scene.player
+                    && let Some(info) = self.tournament.get_player_info(&id)
+                {
+                    let mut info = info.clone();
+                    info.set_archived(archived);
+                    self.execute(Command::SetPlayerInfo(id, info))?;
+                }
+                if let Some(Scene::Player(scene)) = self.scenes.last_mut() {
+                    scene.info.set_archived(archived);
+                }
+                Message::done()
+            }
             ViewPlayerMessage::ConfirmedDelete => {
-                if let Some(id) = &scene.player {
-                    self.tournament.unregister_player(*id)?;
+                if let Some(id) = scene.player {
+                    self.execute(Command::UnregisterPlayer(id))?;
                 }
                 self.scenes.pop();
                 Message::done()
@@ -166,6 +264,24 @@ impl HandleMessage<ViewPlayerMessage> for App {
 impl View<ViewPlayerScene> for App {
     fn view<'a>(&'a self, scene: &'a ViewPlayerScene) -> Element<'a, Message> {
         let menu_bar = row![
+            button(text(if scene.info.is_archived() {
+                "Restore"
+            } else {
+                "Retire"
+            }))
+            .style(button::secondary)
+            .on_press_maybe(
+                scene
+                    .player
+                    .is_some()
+                    .then_some(ViewPlayerMessage::ToggleRetired.into())
+            ),
+            button(text("Delete")).style(button::danger).on_press_maybe(
+                scene
+                    .player
+                    .is_some()
+                    .then_some(ViewPlayerMessage::Delete.into())
+            ),
             space().width(Length::Fill),
             button(text("Cancel")).on_press(ViewPlayerMessage::Close.into()),
             button(text("Save")).on_press(ViewPlayerMessage::SaveAndClose.into()),
@@ -190,6 +306,9 @@ impl View<ViewPlayerScene> for App {
             let edit_moxfieldid = text_input("Moxfield ID", &scene.moxfield)
                 .on_input(|text| ViewPlayerMessage::SetMoxfieldId(text).into());
 
+            let edit_owner = text_input("Owner", &scene.owner)
+                .on_input(|text| ViewPlayerMessage::SetOwner(text).into());
+
             let deck_colors = row(MtgColor::COLORS.map(|color| {
                 let style = if scene.info.color_identity().has_color(color) {
                     button::primary
@@ -206,6 +325,7 @@ impl View<ViewPlayerScene> for App {
 
             column![
                 edit_name,
+                edit_owner,
                 row![edit_moxfieldid, deck_colors].spacing(20),
                 edit_description
             ]
@@ -226,11 +346,45 @@ impl View<ViewPlayerScene> for App {
                 column![
                     text(format!("Games Played: {}", stats.games())),
                     text(format!("Games Won: {}", stats.wins())),
+                    text(format!("Games Drawn: {}", stats.draws())),
                     {
                         stats.wr().map_or_else(
                             || text("--% WR"),
                             |wr| text(format!("{}% WR", (wr * 100.0).round())),
                         )
+                    },
+                    {
+                        let length = self
+                            .tournament
+                            .get_average_game_length(id)
+                            .unwrap_or_default();
+                        let turns = length.turns.map_or_else(
+                            || "-- turns".to_owned(),
+                            |turns| format!("{turns:.1} turns"),
+                        );
+                        let duration = length.duration.map_or_else(
+                            || "-- min".to_owned(),
+                            |minutes| format!("{} min", minutes.round()),
+                        );
+                        text(format!("Avg. Length: {turns}, {duration}"))
+                    },
+                    {
+                        let first_seat = self
+                            .tournament
+                            .get_player_seat_stats(id, None)
+                            .ok()
+                            .and_then(|seats| seats.first().and_then(SeatStats::wr));
+                        first_seat.map_or_else(
+                            || text("--% WR on the play"),
+                            |wr| text(format!("{}% WR on the play", (wr * 100.0).round())),
+                        )
+                    },
+                    {
+                        let kills = self.tournament.get_kill_stats(id).unwrap_or_default();
+                        text(format!(
+                            "Knockouts: {} dealt, {} taken",
+                            kills.kills, kills.deaths
+                        ))
                     }
                 ]
                 .align_x(Horizontal::Right)
@@ -238,50 +392,88 @@ impl View<ViewPlayerScene> for App {
             .align_y(Vertical::Center)
             .spacing(20);
 
-            let games = self
-                .tournament
-                .get_player_games(id)
+            // Games without a result have no rating point
+            let histories = scene.histories(&self.tournament);
+            let points = histories
+                .get(&id)
                 .into_iter()
                 .flatten()
-                .collect_vec()
-                .into_iter()
+                .map(|point| (point.game, *point))
+                .collect::<HashMap<_, _>>();
+            let games = self
+                .tournament
+                .games()
+                .iter()
+                .enumerate()
+                .filter(|(_, game)| game.has_player(id))
+                .map(|(index, game)| (game, points.get(&index).copied()))
                 .rev();
 
             let view_games = scrollable(
                 table(
                     [
-                        table::column("Competitors", |game: &GameRecord| {
+                        table::column("Competitors", |(game, _): GameRow<'_>| {
+                            let outcome = match game.outcome() {
+                                GameOutcome::Win(_) => None,
+                                GameOutcome::Draw(_) => Some(text("Draw").size(12)),
+                                GameOutcome::NoResult => Some(text("No Result").size(12)),
+                            };
                             column(game.players().iter().map(|player| {
                                 let elo = player.stats().elo().round();
                                 text(self.tournament.get_player_name(&player.id()).map_or_else(
                                     || format!("({elo}) {}", player.id()),
                                     |name| format!("({elo}) {name}"),
                                 ))
-                                .font_maybe((player.id() == game.winner()).then_some(font::Font {
-                                    weight: font::Weight::Bold,
-                                    ..default_font()
-                                }))
+                                .font_maybe(game.outcome().is_credited(player.id()).then_some(
+                                    font::Font {
+                                        weight: font::Weight::Bold,
+                                        ..default_font()
+                                    },
+                                ))
                                 .into()
                             }))
+                            .push(outcome)
                         }),
-                        table::column("Elo", |game: &GameRecord| {
-                            let elo_change = game.get_player_elo_change(id).unwrap_or_default();
+                        table::column("Place", |(game, _): GameRow<'_>| {
+                            text(game.placement(id).map_or_else(|| "--".to_owned(), ordinal))
+                                .size(20)
+                        }),
+                        table::column("Details", |(game, _): GameRow<'_>| {
+                            let details = game.details();
+                            let summary = [
+                                details.turns().map(|turns| format!("Turn {turns}")),
+                                details.duration().map(|minutes| format!("{minutes} min")),
+                                details
+                                    .win_condition()
+                                    .map(|condition| condition.to_string()),
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .join(", ");
+                            let notes = (!details.notes().is_empty())
+                                .then(|| text(details.notes()).size(12));
+                            column![text(summary).size(12)].push(notes).max_width(200)
+                        }),
+                        table::column("Elo", |(_, point): GameRow<'_>| {
+                            let Some(point) = point else {
+                                return column![text("--").size(20)].padding(5);
+                            };
+                            let elo_change = point.change();
                             let elo_change_str = if elo_change >= 0f64 {
                                 format!("+{}", elo_change.round())
                             } else {
                                 format!("{}", elo_change.round())
                             };
-
-                            let old_elo = game.get_player(id).map_or_else(
-                                |_| self.tournament().default_stats().elo(),
-                                |player| player.stats().elo(),
-                            );
-
-                            let new_elo = (old_elo + elo_change).round();
+                            let rank = if point.rank_before == point.rank_after {
+                                format!("#{}", point.rank_after)
+                            } else {
+                                format!("#{} → #{}", point.rank_before, point.rank_after)
+                            };
 
                             column![
-                                text(format!("{new_elo}")).size(20),
-                                text(elo_change_str).size(15)
+                                text(format!("{}", point.after.round())).size(20),
+                                text(elo_change_str).size(15),
+                                text(rank).size(12)
                             ]
                             .spacing(5)
                             .padding(5)
@@ -293,7 +485,7 @@ impl View<ViewPlayerScene> for App {
             )
             .width(Length::Fill);
 
-            column![view_stats, view_games].spacing(30)
+            column![view_stats, self.view_rating_chart(scene, id), view_games].spacing(30)
         });
 
         let content = match deck_progress {
@@ -307,12 +499,83 @@ impl View<ViewPlayerScene> for App {
     }
 }
 
+impl App {
+    /// Rating over time of the deck and any decks it's compared with, with a picker to add more
+    fn view_rating_chart<'a>(
+        &'a self,
+        scene: &'a ViewPlayerScene,
+        id: u32,
+    ) -> Element<'a, Message> {
+        let histories = scene.histories(&self.tournament);
+        let decks = core::iter::once(id).chain(scene.compare.iter().copied());
+        let series = decks
+            .map(|deck| Series {
+                name: self
+                    .tournament
+                    .get_player_name(&deck)
+                    .cloned()
+                    .unwrap_or_default(),
+                points: histories.get(&deck).cloned().unwrap_or_default(),
+            })
+            .collect_vec();
+
+        let legend = row(series.iter().enumerate().map(|(index, series)| {
+            let label = text(series.name.clone())
+                .size(12)
+                .color(series_color(index));
+            if index == 0 {
+                label.into()
+            } else {
+                let deck = scene.compare.get(index - 1).copied().unwrap_or_default();
+                button(label)
+                    .style(button::text)
+                    .on_press(ViewPlayerMessage::RemoveComparison(deck).into())
+                    .into()
+            }
+        }))
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let options = self
+            .tournament
+            .get_active_players()
+            .filter(|player| player.id() != id && !scene.compare.contains(&player.id()))
+            .sorted_by(|a, b| a.info().name().cmp(b.info().name()))
+            .collect_vec();
+        let compare = pick_list(options, None::<RegisteredPlayer<'_>>, |player| {
+            ViewPlayerMessage::AddComparison(player.id()).into()
+        })
+        .placeholder("Compare with...")
+        .text_size(12);
+
+        let chart = canvas(RatingChart {
+            series,
+            games: self.tournament.games().len(),
+        })
+        .width(Length::Fill)
+        .height(200);
+
+        column![row![legend, space().width(Length::Fill), compare], chart]
+            .spacing(10)
+            .into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use edh_tourn::Tournament;
+    #![allow(clippy::indexing_slicing)]
+
+    use edh_tourn::{Tournament, command::Command, pilot::PilotInfo};
     use itertools::Itertools;
 
-    use crate::view::player::ViewPlayerScene;
+    use crate::{
+        App,
+        logic::Message,
+        view::{
+            Scene,
+            player::{ViewPlayerMessage, ViewPlayerScene},
+        },
+    };
 
     #[test]
     fn new_creates_default_values() {
@@ -340,4 +603,88 @@ mod tests {
             assert_eq!(info, scene.info);
         }
     }
+
+    #[test]
+    fn comparisons_skip_the_deck_itself() {
+        let (mut app, ids) = App::test_with_tournament(5, 10);
+        app.test_updates([
+            ViewPlayerMessage::Open(Some(ids[0])),
+            ViewPlayerMessage::AddComparison(ids[0]),
+            ViewPlayerMessage::AddComparison(ids[1]),
+            ViewPlayerMessage::AddComparison(ids[2]),
+            ViewPlayerMessage::AddComparison(ids[1]),
+            ViewPlayerMessage::RemoveComparison(ids[2]),
+        ])
+        .unwrap();
+        let Some(Scene::Player(scene)) = app.scenes.last() else {
+            panic!("Player page should be open");
+        };
+        assert_eq!(vec![ids[1]], scene.compare);
+    }
+
+    #[test]
+    fn new_owner_is_undone_with_the_deck() {
+        let (mut app, ids) = App::test_with_tournament(5, 10);
+        let before = app.tournament.clone();
+        let id = ids[0];
+        app.test_updates([
+            ViewPlayerMessage::Open(Some(id)),
+            ViewPlayerMessage::SetOwner(" Alice ".to_owned()),
+            ViewPlayerMessage::SaveAndClose,
+        ])
+        .unwrap();
+        let alice = app.tournament.get_pilot_id("Alice").unwrap();
+        assert_eq!(
+            Some(alice),
+            app.tournament.get_player_info(&id).unwrap().owner()
+        );
+
+        app.test_update(Message::Undo).unwrap();
+        assert!(app.tournament.pilots().is_empty());
+        assert_eq!(before.players(), app.tournament.players());
+        app.test_update(Message::Redo).unwrap();
+        assert_eq!(
+            Some("Alice"),
+            app.tournament.get_pilot_info(alice).map(PilotInfo::name)
+        );
+    }
+
+    #[test]
+    fn failed_save_leaves_no_new_owner() {
+        let (mut app, ids) = App::test_with_tournament(5, 10);
+        let taken = app.tournament.get_player_name(&ids[1]).unwrap().clone();
+        app.test_updates([
+            ViewPlayerMessage::Open(Some(ids[0])),
+            ViewPlayerMessage::SetName(taken),
+            ViewPlayerMessage::SetOwner("Alice".to_owned()),
+        ])
+        .unwrap();
+        app.test_update(ViewPlayerMessage::SaveAndClose)
+            .unwrap_err();
+        assert!(app.tournament.pilots().is_empty());
+    }
+
+    #[test]
+    fn histories_follow_the_tournament() {
+        let (mut app, ids) = App::test_with_tournament(5, 10);
+        let id = ids[0];
+        let _ = app.updater(ViewPlayerMessage::Open(Some(id)).into());
+        let histories = |app: &App| {
+            let Some(Scene::Player(scene)) = app.scenes.last() else {
+                panic!("Player page should be open");
+            };
+            scene.histories.get(&app.tournament).cloned()
+        };
+        assert_eq!(Some(app.tournament.rating_histories()), histories(&app));
+
+        let game = app
+            .tournament
+            .games()
+            .iter()
+            .position(|game| game.has_player(id));
+        app.execute(Command::DeleteGame(game.unwrap())).unwrap();
+        assert_eq!(None, histories(&app));
+        let _ = app.updater(Message::None);
+        assert_eq!(Some(app.tournament.rating_histories()), histories(&app));
+    }
 }
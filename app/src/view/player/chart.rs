@@ -0,0 +1,176 @@
+use edh_tourn::player::history::RatingPoint;
+use iced::{
+    Color, Pixels, Point, Rectangle, Renderer, Size, Theme, mouse,
+    widget::canvas::{self, Frame, Geometry, Path, Stroke, Text},
+};
+
+use crate::logic::Message;
+
+/// Line colors of the overlaid decks, in order
+const SERIES_COLORS: [Color; 6] = [
+    Color::from_rgb(0.26, 0.52, 0.96),
+    Color::from_rgb(0.93, 0.33, 0.31),
+    Color::from_rgb(0.35, 0.73, 0.36),
+    Color::from_rgb(0.98, 0.66, 0.15),
+    Color::from_rgb(0.67, 0.40, 0.85),
+    Color::from_rgb(0.20, 0.75, 0.78),
+];
+
+/// Room left for the axis labels
+const MARGIN: f32 = 40.0;
+
+/// Color of the line for the deck at `index` in the chart
+#[must_use]
+pub fn series_color(index: usize) -> Color {
+    SERIES_COLORS
+        .get(index % SERIES_COLORS.len())
+        .copied()
+        .unwrap_or(Color::WHITE)
+}
+
+/// One deck's rating over the history
+pub struct Series {
+    pub name: String,
+    pub points: Vec<RatingPoint>,
+}
+
+/// Ratings of several decks plotted against the game index, so overlaid decks line up by when
+/// their games were played
+pub struct RatingChart {
+    pub series: Vec<Series>,
+    /// Games in the whole history, the width of the x axis
+    pub games: usize,
+}
+
+impl RatingChart {
+    /// Lowest and highest rating any deck reached, padded so the lines don't touch the edges
+    fn rating_range(&self) -> Option<(f64, f64)> {
+        let ratings = self
+            .series
+            .iter()
+            .flat_map(|series| &series.points)
+            .flat_map(|point| [point.before, point.after]);
+        let (low, high) = ratings.fold(None, |range: Option<(f64, f64)>, rating| {
+            Some(range.map_or((rating, rating), |(low, high)| {
+                (low.min(rating), high.max(rating))
+            }))
+        })?;
+        let padding = ((high - low) * 0.05).max(10.0);
+        Some((low - padding, high + padding))
+    }
+}
+
+impl canvas::Program<Message> for RatingChart {
+    type State = ();
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let text_color = theme.palette().text;
+        let plot = Rectangle::new(
+            Point::new(MARGIN, MARGIN / 4.0),
+            Size::new(
+                MARGIN.mul_add(-1.25, bounds.width).max(1.0),
+                (bounds.height - MARGIN).max(1.0),
+            ),
+        );
+
+        frame.stroke(
+            &Path::new(|path| {
+                path.move_to(Point::new(plot.x, plot.y));
+                path.line_to(Point::new(plot.x, plot.y + plot.height));
+                path.line_to(Point::new(plot.x + plot.width, plot.y + plot.height));
+            }),
+            Stroke::default()
+                .with_color(text_color.scale_alpha(0.5))
+                .with_width(1.0),
+        );
+
+        let Some((low, high)) = self.rating_range() else {
+            return vec![frame.into_geometry()];
+        };
+        let games = self.games.max(1) as f64;
+        let to_point = |game: f64, rating: f64| {
+            Point::new(
+                ((game / games) as f32).mul_add(plot.width, plot.x),
+                (((high - rating) / (high - low)) as f32).mul_add(plot.height, plot.y),
+            )
+        };
+
+        for (rating, y) in [(high, plot.y), (low, plot.y + plot.height)] {
+            frame.fill_text(Text {
+                content: format!("{rating:.0}"),
+                position: Point::new(2.0, y - 6.0),
+                color: text_color,
+                size: Pixels(11.0),
+                ..Text::default()
+            });
+        }
+        frame.fill_text(Text {
+            content: format!("{} games", self.games),
+            position: Point::new(plot.x + plot.width - 50.0, plot.y + plot.height + 6.0),
+            color: text_color,
+            size: Pixels(11.0),
+            ..Text::default()
+        });
+
+        for (index, series) in self.series.iter().enumerate() {
+            let Some(first) = series.points.first() else {
+                continue;
+            };
+            let line = Path::new(|path| {
+                path.move_to(to_point(first.game as f64, first.before));
+                for point in &series.points {
+                    path.line_to(to_point(point.game as f64 + 1.0, point.after));
+                }
+            });
+            frame.stroke(
+                &line,
+                Stroke::default()
+                    .with_color(series_color(index))
+                    .with_width(2.0),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edh_tourn::Tournament;
+
+    use crate::view::player::chart::{RatingChart, Series};
+
+    #[test]
+    fn range_covers_every_rating() {
+        let tourn = Tournament::generate_tournament(5, 20).unwrap();
+        let series = tourn
+            .rating_histories()
+            .into_iter()
+            .map(|(id, points)| Series {
+                name: id.to_string(),
+                points,
+            })
+            .collect();
+        let chart = RatingChart { series, games: 20 };
+        let (low, high) = chart.rating_range().unwrap();
+        for point in chart.series.iter().flat_map(|series| &series.points) {
+            assert!(low < point.before.min(point.after));
+            assert!(high > point.before.max(point.after));
+        }
+
+        let empty = RatingChart {
+            series: Vec::new(),
+            games: 0,
+        };
+        assert!(empty.rating_range().is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use core::fmt::Display;
+
+use edh_tourn::{
+    game::{DEFAULT_POD_SIZE, MAX_POD_SIZE, MIN_POD_SIZE, outcome::GameOutcome},
+    player::RegisteredPlayer,
+};
+
+/// Seats of a pod and the outcome picked for it, shared by the panels that record or edit games
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeatEditor {
+    /// Decks in turn order, `None` for seats that are still empty
+    pub seats: Vec<Option<u32>>,
+    /// Seat that went first, if anyone noted it
+    pub first: Option<usize>,
+    pub outcome: Option<OutcomeChoice>,
+    /// Seats that are left out of a draw
+    pub draw_excluded: Vec<usize>,
+}
+
+impl Default for SeatEditor {
+    fn default() -> Self {
+        Self::new(vec![None; DEFAULT_POD_SIZE])
+    }
+}
+
+impl SeatEditor {
+    #[must_use]
+    pub const fn new(seats: Vec<Option<u32>>) -> Self {
+        Self {
+            seats,
+            first: None,
+            outcome: None,
+            draw_excluded: Vec::new(),
+        }
+    }
+
+    pub fn set_player(&mut self, seat: usize, value: Option<u32>) {
+        if let Some(player) = self.seats.get_mut(seat) {
+            *player = value;
+        }
+    }
+
+    /// Seated decks, if every seat is taken
+    #[must_use]
+    pub fn players(&self) -> Option<Vec<u32>> {
+        self.seats.iter().copied().collect()
+    }
+
+    /// Adds an empty seat, returning whether there was room for it
+    pub fn add_seat(&mut self) -> bool {
+        let added = self.seats.len() < MAX_POD_SIZE;
+        if added {
+            self.seats.push(None);
+        }
+        added
+    }
+
+    /// Removes the last seat, returning whether the pod was big enough to lose it. The winner,
+    /// draw and first seat are dropped if they pointed at a seat that is gone.
+    pub fn remove_seat(&mut self) -> bool {
+        let removed = self.seats.len() > MIN_POD_SIZE;
+        if removed {
+            self.seats.pop();
+        }
+        if let Some(OutcomeChoice::Winner(winner)) = self.outcome
+            && !self.seats.contains(&Some(winner))
+        {
+            self.outcome = None;
+        }
+        let seats = self.seats.len();
+        self.draw_excluded.retain(|seat| *seat < seats);
+        self.first = self.first.filter(|first| *first < seats);
+        removed
+    }
+
+    pub fn toggle_draw_seat(&mut self, seat: usize) {
+        if let Some(index) = self.draw_excluded.iter().position(|s| *s == seat) {
+            self.draw_excluded.remove(index);
+        } else {
+            self.draw_excluded.push(seat);
+        }
+    }
+
+    /// The picked outcome. A draw is shared by the seated decks that weren't left out of it.
+    #[must_use]
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        Some(match self.outcome? {
+            OutcomeChoice::Winner(id) => GameOutcome::Win(id),
+            OutcomeChoice::Draw => GameOutcome::Draw(
+                self.seats
+                    .iter()
+                    .enumerate()
+                    .filter(|(seat, _)| !self.draw_excluded.contains(seat))
+                    .filter_map(|(_, id)| *id)
+                    .collect(),
+            ),
+            OutcomeChoice::NoResult => GameOutcome::NoResult,
+        })
+    }
+}
+
+/// What was picked in the winner selector
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OutcomeChoice {
+    Winner(u32),
+    Draw,
+    NoResult,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum OutcomeOption<'a> {
+    Winner(RegisteredPlayer<'a>),
+    Draw,
+    NoResult,
+}
+
+impl OutcomeOption<'_> {
+    #[must_use]
+    pub const fn choice(&self) -> OutcomeChoice {
+        match self {
+            Self::Winner(player) => OutcomeChoice::Winner(player.id()),
+            Self::Draw => OutcomeChoice::Draw,
+            Self::NoResult => OutcomeChoice::NoResult,
+        }
+    }
+}
+
+impl Display for OutcomeOption<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Winner(player) => write!(f, "{player}"),
+            Self::Draw => write!(f, "Draw"),
+            Self::NoResult => write!(f, "No Result"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edh_tourn::game::{MIN_POD_SIZE, outcome::GameOutcome};
+
+    use crate::view::seats::{OutcomeChoice, SeatEditor};
+
+    #[test]
+    fn removed_seats_drop_their_picks() {
+        let mut pod = SeatEditor::new(vec![Some(0), Some(1), Some(2), Some(3)]);
+        pod.first = Some(3);
+        pod.outcome = Some(OutcomeChoice::Winner(3));
+        pod.draw_excluded = vec![1, 3];
+
+        assert!(pod.remove_seat());
+        assert_eq!(None, pod.first);
+        assert_eq!(None, pod.outcome);
+        assert_eq!(vec![1], pod.draw_excluded);
+        assert_eq!(MIN_POD_SIZE, pod.seats.len());
+        assert!(!pod.remove_seat());
+
+        pod.outcome = Some(OutcomeChoice::Draw);
+        assert_eq!(Some(GameOutcome::Draw(vec![0, 2])), pod.outcome());
+    }
+}
@@ -0,0 +1,138 @@
+use edh_tourn::{command::Command, config::TournamentConfig, rating::RatingSystemKind};
+use iced::{
+    Element, Length, Task,
+    widget::{button, column, container, pick_list, row, space, text},
+};
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::Scene,
+};
+
+/// Edits the tournament config. Saving replays the history under it.
+#[derive(Debug)]
+pub struct SettingsScene {
+    config: TournamentConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsMessage {
+    Open,
+    SetRatingSystem(RatingSystemKind),
+    Save,
+    Close,
+}
+
+impl From<SettingsMessage> for Message {
+    fn from(value: SettingsMessage) -> Self {
+        Self::Settings(value)
+    }
+}
+
+impl HandleMessage<SettingsMessage> for App {
+    fn update(&mut self, msg: SettingsMessage) -> anyhow::Result<Task<Message>> {
+        if msg == SettingsMessage::Open {
+            self.scenes.push(Scene::Settings(SettingsScene {
+                config: self.tournament.config().clone(),
+            }));
+            return Message::done();
+        }
+        let Some(Scene::Settings(scene)) = self.scenes.last_mut() else {
+            return Message::done();
+        };
+
+        match msg {
+            SettingsMessage::Open => {}
+            SettingsMessage::SetRatingSystem(kind) => {
+                scene.config.rating_system = kind;
+            }
+            SettingsMessage::Save => {
+                let config = scene.config.clone();
+                if &config != self.tournament.config() {
+                    self.execute(Command::SetConfig(config))?;
+                }
+                self.scenes.pop();
+            }
+            SettingsMessage::Close => {
+                self.scenes.pop();
+            }
+        }
+        Message::done()
+    }
+}
+
+impl View<SettingsScene> for App {
+    fn view<'a>(&'a self, scene: &'a SettingsScene) -> Element<'a, Message> {
+        let menu_bar = row![
+            space().width(Length::Fill),
+            button(text("Save")).on_press(SettingsMessage::Save.into()),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(SettingsMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Settings").width(Length::Fill).center().size(50);
+
+        let rating_system = column![
+            row![
+                text("Rating system").width(200),
+                pick_list(
+                    RatingSystemKind::VALUES,
+                    Some(scene.config.rating_system),
+                    |kind| SettingsMessage::SetRatingSystem(kind).into(),
+                ),
+            ]
+            .spacing(10),
+            text("Changing it recalculates every rating from the first game").size(12),
+        ]
+        .spacing(6);
+
+        container(
+            column![menu_bar, title, rating_system]
+                .spacing(20)
+                .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edh_tourn::rating::RatingSystemKind;
+
+    use crate::{App, logic::Message, view::settings::SettingsMessage};
+
+    #[test]
+    fn switching_rating_system_can_be_undone() {
+        let (mut app, _) = App::test_with_tournament(6, 10);
+        let before = app.tournament.clone();
+
+        app.test_updates([
+            SettingsMessage::Open,
+            SettingsMessage::SetRatingSystem(RatingSystemKind::Glicko2),
+            SettingsMessage::Close,
+        ])
+        .unwrap();
+        assert_eq!(before, app.tournament);
+
+        app.test_updates([
+            SettingsMessage::Open,
+            SettingsMessage::SetRatingSystem(RatingSystemKind::Glicko2),
+            SettingsMessage::Save,
+        ])
+        .unwrap();
+        assert!(app.scenes.is_empty());
+        assert_eq!(
+            RatingSystemKind::Glicko2,
+            app.tournament.config().rating_system
+        );
+        assert!(app.tournament.default_stats().deviation().is_some());
+
+        app.test_update(Message::Undo).unwrap();
+        assert_eq!(before.config(), app.tournament.config());
+        assert_eq!(before.default_stats(), app.tournament.default_stats());
+    }
+}
@@ -0,0 +1,279 @@
+use edh_tourn::{
+    Tournament,
+    command::Command,
+    config::TournamentConfig,
+    tuning::{ConfigKnob, ConfigSuggestion, PredictionScore, ScoringRule, TuningSettings},
+};
+use iced::{
+    Element, Length, Task,
+    widget::{button, column, container, row, space, table, text},
+};
+
+use crate::{
+    App,
+    logic::Message,
+    traits::{HandleMessage, View},
+    view::Scene,
+};
+
+/// Compares the current config with one tuned to predict the history better, before applying it
+#[derive(Debug)]
+pub struct SuggestConfigScene {
+    rule: ScoringRule,
+    /// None while the search runs in the background
+    suggestion: Option<ConfigSuggestion>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SuggestConfigMessage {
+    Open,
+    SetRule(ScoringRule),
+    /// Result of the search by the rule, over the tournament at the snapshot
+    Suggested(ScoringRule, usize, Result<ConfigSuggestion, String>),
+    Apply,
+    Close,
+}
+
+impl From<SuggestConfigMessage> for Message {
+    fn from(value: SuggestConfigMessage) -> Self {
+        Self::SuggestConfig(value)
+    }
+}
+
+/// Searches for a config in the background, since it replays the history up to
+/// [`TuningSettings::max_evaluations`] times
+fn suggest_config(tournament: Tournament, rule: ScoringRule) -> Task<Message> {
+    let settings = TuningSettings {
+        rule,
+        ..TuningSettings::default()
+    };
+    let snapshot = tournament.snapshot();
+    Task::perform(
+        async move {
+            tournament
+                .suggest_config(&settings)
+                .map_err(|err| err.to_string())
+        },
+        move |result| SuggestConfigMessage::Suggested(rule, snapshot, result).into(),
+    )
+}
+
+impl HandleMessage<SuggestConfigMessage> for App {
+    fn update(&mut self, msg: SuggestConfigMessage) -> anyhow::Result<iced::Task<Message>> {
+        match msg {
+            SuggestConfigMessage::Open => {
+                let rule = ScoringRule::default();
+                self.scenes.push(Scene::SuggestConfig(SuggestConfigScene {
+                    rule,
+                    suggestion: None,
+                }));
+                Ok(suggest_config(self.tournament.clone(), rule))
+            }
+            SuggestConfigMessage::SetRule(rule) => {
+                let Some(Scene::SuggestConfig(scene)) = self.scenes.last_mut() else {
+                    return Message::done();
+                };
+                scene.rule = rule;
+                scene.suggestion = None;
+                Ok(suggest_config(self.tournament.clone(), rule))
+            }
+            SuggestConfigMessage::Suggested(rule, snapshot, result) => {
+                let Some(Scene::SuggestConfig(scene)) = self.scenes.last_mut() else {
+                    return Message::done();
+                };
+                // A search for another rule finishes on its own
+                if scene.rule != rule {
+                    return Message::done();
+                }
+                // The history changed while searching, such as by undoing, so search it again
+                if snapshot != self.tournament.snapshot() {
+                    return Ok(suggest_config(self.tournament.clone(), rule));
+                }
+                scene.suggestion = Some(result.map_err(anyhow::Error::msg)?);
+                Message::done()
+            }
+            SuggestConfigMessage::Apply => {
+                if let Some(Scene::SuggestConfig(SuggestConfigScene {
+                    suggestion: Some(suggestion),
+                    ..
+                })) = self.scenes.last()
+                {
+                    let config = suggestion.config.clone();
+                    self.scenes.pop();
+                    self.execute(Command::SetConfig(config))?;
+                }
+                Message::done()
+            }
+            SuggestConfigMessage::Close => {
+                if let Some(Scene::SuggestConfig(_)) = self.scenes.last() {
+                    self.scenes.pop();
+                }
+                Message::done()
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ComparisonRow {
+    label: String,
+    current: String,
+    suggested: String,
+}
+
+impl ComparisonRow {
+    fn knob(knob: ConfigKnob, current: &TournamentConfig, suggested: &TournamentConfig) -> Self {
+        Self {
+            label: knob.to_string(),
+            current: format!("{:.3}", knob.get(current)),
+            suggested: format!("{:.3}", knob.get(suggested)),
+        }
+    }
+
+    fn score(rule: ScoringRule, current: &PredictionScore, suggested: &PredictionScore) -> Self {
+        Self {
+            label: rule.to_string(),
+            current: format!("{:.4}", current.get(rule)),
+            suggested: format!("{:.4}", suggested.get(rule)),
+        }
+    }
+}
+
+impl View<SuggestConfigScene> for App {
+    fn view<'a>(&'a self, scene: &'a SuggestConfigScene) -> Element<'a, Message> {
+        let rule_buttons = row(ScoringRule::VALUES.into_iter().map(|rule| {
+            button(text(rule.to_string()))
+                .style(if rule == scene.rule {
+                    button::primary
+                } else {
+                    button::secondary
+                })
+                .on_press(SuggestConfigMessage::SetRule(rule).into())
+                .into()
+        }))
+        .spacing(5);
+
+        let menu_bar = row![
+            text("Score by"),
+            rule_buttons,
+            space().width(Length::Fill),
+            button(text("Apply")).on_press_maybe(
+                scene
+                    .suggestion
+                    .as_ref()
+                    .is_some_and(|suggestion| suggestion.improves(scene.rule))
+                    .then_some(SuggestConfigMessage::Apply.into())
+            ),
+            button(text("Close"))
+                .style(button::secondary)
+                .on_press(SuggestConfigMessage::Close.into()),
+        ]
+        .spacing(20);
+
+        let title = text("Suggested Config")
+            .width(Length::Fill)
+            .center()
+            .size(50);
+
+        let Some(suggestion) = &scene.suggestion else {
+            return container(
+                column![
+                    menu_bar,
+                    title,
+                    text("Replaying the history under other configs...")
+                ]
+                .spacing(20)
+                .width(Length::Fill),
+            )
+            .into();
+        };
+
+        let current_config = self.tournament.config();
+        let rows = ConfigKnob::for_system(current_config.rating_system)
+            .iter()
+            .map(|&knob| ComparisonRow::knob(knob, current_config, &suggestion.config))
+            .chain(ScoringRule::VALUES.into_iter().map(|rule| {
+                ComparisonRow::score(rule, &suggestion.current, &suggestion.suggested)
+            }));
+
+        let comparison = table(
+            [
+                table::column("Setting", |row: ComparisonRow| text(row.label)),
+                table::column("Current", |row: ComparisonRow| text(row.current)),
+                table::column("Suggested", |row: ComparisonRow| text(row.suggested)),
+            ],
+            rows,
+        );
+
+        let summary = text(format!(
+            "Scored on {} games, trying {} configs.",
+            suggestion.current.games, suggestion.evaluations
+        ))
+        .size(12);
+
+        container(
+            column![menu_bar, title, comparison, summary]
+                .spacing(20)
+                .width(Length::Fill),
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use edh_tourn::tuning::{ScoringRule, TuningSettings};
+
+    use crate::{App, logic::Message, view::Scene, view::tuning::SuggestConfigMessage};
+
+    #[test]
+    fn apply_suggestion_can_be_undone() {
+        let (mut app, _) = App::test_with_tournament(6, 40);
+        let config = app.tournament.config().clone();
+
+        // Nothing to apply until the search is done
+        app.test_update(SuggestConfigMessage::Open).unwrap();
+        app.test_update(SuggestConfigMessage::Apply).unwrap();
+        assert_eq!(1, app.scenes.len());
+
+        let rule = ScoringRule::Brier;
+        let search = |app: &App| {
+            let settings = TuningSettings {
+                rule,
+                max_evaluations: 20,
+                ..TuningSettings::default()
+            };
+            app.tournament.suggest_config(&settings).unwrap()
+        };
+        let snapshot = app.tournament.snapshot();
+        let outdated = search(&app);
+        app.test_update(SuggestConfigMessage::SetRule(rule))
+            .unwrap();
+        // Results of the previous rule are dropped
+        app.test_update(SuggestConfigMessage::Suggested(
+            ScoringRule::LogLoss,
+            snapshot,
+            Ok(outdated),
+        ))
+        .unwrap();
+        let Some(Scene::SuggestConfig(scene)) = app.scenes.last() else {
+            panic!("Suggestion should be open");
+        };
+        assert!(scene.suggestion.is_none());
+
+        let suggestion = search(&app);
+        let suggested = suggestion.config.clone();
+        app.test_update(SuggestConfigMessage::Suggested(
+            rule,
+            snapshot,
+            Ok(suggestion),
+        ))
+        .unwrap();
+        app.test_update(SuggestConfigMessage::Apply).unwrap();
+        assert!(app.scenes.is_empty());
+        assert_eq!(&suggested, app.tournament.config());
+
+        app.test_update(Message::Undo).unwrap();
+        assert_eq!(&config, app.tournament.config());
+    }
+}
@@ -0,0 +1,271 @@
+use core::fmt::Write;
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{Tournament, game::record::GameRecord, tuning::SeatScore};
+
+/// Predictions whose expected result fell in `low..high`, and how they turned out
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CalibrationBucket {
+    pub low: f64,
+    pub high: f64,
+    pub predictions: usize,
+    /// Average expected result of the predictions
+    pub expected: f64,
+    /// Share of the predictions that won, with draws counting as a share of a win
+    pub observed: f64,
+    /// Average squared error of the predictions
+    pub brier: f64,
+    /// Average binary log-loss of the predictions. Each seat is scored on both its chance of
+    /// winning and of losing, as a bucket holds seats rather than whole games. This is not the
+    /// `PredictionScore::log_loss` the tuning minimizes, which only scores the chances given to
+    /// the winners and averages over games.
+    pub log_loss: f64,
+}
+
+impl CalibrationBucket {
+    const fn new(low: f64, high: f64) -> Self {
+        Self {
+            low,
+            high,
+            predictions: 0,
+            expected: 0.0,
+            observed: 0.0,
+            brier: 0.0,
+            log_loss: 0.0,
+        }
+    }
+
+    fn add(&mut self, expected: f64, actual: f64) {
+        self.predictions += 1;
+        self.expected += expected;
+        self.observed += actual;
+        let seat = SeatScore::new(expected, actual);
+        self.brier += seat.brier;
+        self.log_loss += seat.win_log_loss + seat.loss_log_loss;
+    }
+
+    /// Turns the sums into averages
+    #[allow(clippy::cast_precision_loss)]
+    fn finish(&mut self) {
+        if self.predictions > 0 {
+            let count = self.predictions as f64;
+            self.expected /= count;
+            self.observed /= count;
+            self.brier /= count;
+            self.log_loss /= count;
+        }
+    }
+}
+
+/// How the expected results of a set of seats compare to how they turned out. Each seat of each
+/// game with a result is one prediction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    /// Every prediction, from 0 to 1
+    pub total: CalibrationBucket,
+    /// Predictions split into even ranges of expected result, lowest first
+    pub buckets: Vec<CalibrationBucket>,
+}
+
+impl Calibration {
+    #[allow(clippy::cast_precision_loss)]
+    fn new(buckets: usize) -> Self {
+        let width = 1.0 / buckets as f64;
+        Self {
+            total: CalibrationBucket::new(0.0, 1.0),
+            buckets: (0..buckets)
+                .map(|i| CalibrationBucket::new(i as f64 * width, (i + 1) as f64 * width))
+                .collect(),
+        }
+    }
+
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn add(&mut self, expected: f64, actual: f64) {
+        self.total.add(expected, actual);
+        let count = self.buckets.len();
+        let index = ((expected * count as f64) as usize).min(count.saturating_sub(1));
+        if let Some(bucket) = self.buckets.get_mut(index) {
+            bucket.add(expected, actual);
+        }
+    }
+
+    fn finish(&mut self) {
+        self.total.finish();
+        for bucket in &mut self.buckets {
+            bucket.finish();
+        }
+    }
+}
+
+/// Calibration of every prediction in the history, overall and for each deck
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationReport {
+    pub overall: Calibration,
+    pub decks: HashMap<u32, Calibration>,
+}
+
+impl CalibrationReport {
+    /// Writes the report as CSV, one row per bucket with a row for all predictions ahead of each
+    /// deck's buckets. The overall rows come first, then each deck by name.
+    #[must_use]
+    pub fn to_csv(&self, tourn: &Tournament) -> String {
+        let mut csv = String::from("deck,from,to,predictions,expected,observed,brier,log_loss\n");
+        let mut write_rows = |deck: &str, calibration: &Calibration| {
+            let deck = csv_field(deck);
+            for bucket in core::iter::once(&calibration.total).chain(&calibration.buckets) {
+                let _ = writeln!(
+                    csv,
+                    "{deck},{:.2},{:.2},{},{:.4},{:.4},{:.4},{:.4}",
+                    bucket.low,
+                    bucket.high,
+                    bucket.predictions,
+                    bucket.expected,
+                    bucket.observed,
+                    bucket.brier,
+                    bucket.log_loss,
+                );
+            }
+        };
+
+        write_rows("All", &self.overall);
+        let decks = self
+            .decks
+            .iter()
+            .map(|(id, calibration)| {
+                let name = tourn
+                    .get_player_name(id)
+                    .map_or_else(|| id.to_string(), ToOwned::to_owned);
+                (name, calibration)
+            })
+            .sorted_by(|(a, _), (b, _)| a.cmp(b));
+        for (name, calibration) in decks {
+            write_rows(&name, calibration);
+        }
+        csv
+    }
+}
+
+/// Quotes a CSV field if it needs it
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+impl Tournament {
+    /// Compares every expected result in the history with how the game turned out, split into
+    /// `buckets` even ranges of expected result
+    #[must_use]
+    pub fn calibration(&self, buckets: usize) -> CalibrationReport {
+        calibrate(&self.games, buckets.max(1))
+    }
+}
+
+fn calibrate(games: &[GameRecord], buckets: usize) -> CalibrationReport {
+    let mut overall = Calibration::new(buckets);
+    let mut decks: HashMap<u32, Calibration> = HashMap::new();
+    for game in games {
+        let Some(results) = game.seat_results() else {
+            continue;
+        };
+        for (player, result) in game.players().iter().zip(results) {
+            let expected = *player.expected();
+            overall.add(expected, result.share());
+            decks
+                .entry(player.id())
+                .or_insert_with(|| Calibration::new(buckets))
+                .add(expected, result.share());
+        }
+    }
+
+    overall.finish();
+    for calibration in decks.values_mut() {
+        calibration.finish();
+    }
+    CalibrationReport { overall, decks }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{Tournament, game::outcome::GameOutcome};
+
+    #[test]
+    fn even_pods_land_in_one_bucket() {
+        let mut tourn = Tournament::new();
+        let id = tourn.register_player("A, the deck".to_owned()).unwrap();
+        for outcome in [GameOutcome::Win(id), GameOutcome::NoResult] {
+            let record = tourn
+                .create_match(&[id, id, id, id])
+                .unwrap()
+                .record_outcome(outcome)
+                .unwrap();
+            tourn.register_record(record).unwrap();
+        }
+
+        let report = tourn.calibration(10);
+        let overall = &report.overall;
+        assert_eq!(4, overall.total.predictions);
+        assert_eq!(4, overall.buckets[2].predictions);
+        assert_relative_eq!(0.25, overall.buckets[2].expected, epsilon = 1e-9);
+        assert_relative_eq!(0.25, overall.buckets[2].observed, epsilon = 1e-9);
+        assert_relative_eq!(0.1875, overall.total.brier, epsilon = 1e-9);
+        assert_eq!(overall, &report.decks[&id]);
+
+        let csv = report.to_csv(&tourn);
+        let lines = csv.lines().collect_vec();
+        assert_eq!(1 + 2 * 11, lines.len());
+        assert!(lines[12].starts_with("\"A, the deck\",0.00,1.00,4,"));
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn scores_agree_with_tuning() {
+        let tourn = Tournament::generate_tournament(8, 32).unwrap();
+        let total = tourn.calibration(5).overall.total;
+        let score = tourn.prediction_score();
+        // Calibration averages over seats, tuning over games
+        let seats = total.predictions as f64;
+        let games = score.games as f64;
+        assert_relative_eq!(score.brier * games, total.brier * seats, epsilon = 1e-9);
+        assert!(total.log_loss * seats > score.log_loss * games);
+    }
+
+    #[test]
+    fn every_seat_is_a_prediction() {
+        let tourn = Tournament::generate_tournament(8, 32).unwrap();
+        let report = tourn.calibration(5);
+        let seats = tourn
+            .games()
+            .iter()
+            .map(|game| game.players().len())
+            .sum::<usize>();
+        assert_eq!(seats, report.overall.total.predictions);
+        assert_eq!(
+            seats,
+            report
+                .overall
+                .buckets
+                .iter()
+                .map(|bucket| bucket.predictions)
+                .sum::<usize>()
+        );
+        let deck_predictions = report
+            .decks
+            .values()
+            .map(|calibration| calibration.total.predictions)
+            .sum::<usize>();
+        assert_eq!(seats, deck_predictions);
+    }
+}
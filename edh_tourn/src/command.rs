@@ -0,0 +1,573 @@
+use itertools::Itertools;
+
+use crate::{
+    Tournament,
+    config::TournamentConfig,
+    error::TournamentError,
+    game::{entry::GameEntry, record::GameRecord},
+    matches::constraints::MatchConstraints,
+    pilot::PilotInfo,
+    player::info::PlayerInfo,
+};
+
+/// A change to a tournament. Applying a command returns the command that reverses it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    RegisterPlayer(PlayerInfo),
+    UnregisterPlayer(u32),
+    /// Registers a player under a known id and puts back the games it was in, at their old
+    /// positions, along with its match constraints. Reverses [`Command::UnregisterPlayer`].
+    RestorePlayer {
+        id: u32,
+        info: PlayerInfo,
+        games: Vec<(usize, GameEntry)>,
+        /// Decks it was kept out of pods with
+        exclusions: Vec<u32>,
+        required: bool,
+    },
+    SetPlayerInfo(u32, PlayerInfo),
+    RegisterPilot(PilotInfo),
+    UnregisterPilot(u32),
+    /// Registers a pilot under a known id and links it back to the decks it owned and the games
+    /// it piloted in. Reverses [`Command::UnregisterPilot`].
+    RestorePilot {
+        id: u32,
+        info: PilotInfo,
+        /// Decks it owned
+        decks: Vec<u32>,
+        /// Games it piloted a deck it doesn't own in, with the deck
+        games: Vec<(usize, u32)>,
+    },
+    SetDeckOwner(u32, Option<u32>),
+    RegisterGame(GameEntry),
+    InsertGame(usize, GameEntry),
+    UpdateGame(usize, GameEntry),
+    DeleteGame(usize),
+    SetConfig(TournamentConfig),
+    SetConstraints(MatchConstraints),
+    /// Several commands applied as one change, in order
+    Batch(Vec<Self>),
+    Merge(Box<Tournament>),
+    /// Swaps in a whole tournament. Reverses [`Command::Merge`].
+    Replace(Box<Tournament>),
+}
+
+impl Tournament {
+    /// Applies a command, returning the command that undoes it. Nothing changes if the command
+    /// fails. Adding a game clears "must play next" from its decks.
+    pub fn apply(&mut self, command: Command) -> Result<Command, TournamentError> {
+        Ok(match command {
+            Command::RegisterPlayer(info) => {
+                Command::UnregisterPlayer(self.register_player_with_info(info)?)
+            }
+            Command::UnregisterPlayer(id) => {
+                let info = self
+                    .get_player_info(&id)
+                    .ok_or(TournamentError::InvalidPlayerId(id))?
+                    .clone();
+                let games = self
+                    .games
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, game)| game.has_player(id))
+                    .map(|(gid, game)| (gid, game.to_entry()))
+                    .collect();
+                let exclusions = self
+                    .constraints
+                    .exclusions()
+                    .iter()
+                    .filter_map(|(a, b)| match (*a == id, *b == id) {
+                        (true, _) => Some(*b),
+                        (_, true) => Some(*a),
+                        _ => None,
+                    })
+                    .collect();
+                let required = self.constraints.is_required(id);
+                self.unregister_player(id)?;
+                Command::RestorePlayer {
+                    id,
+                    info,
+                    games,
+                    exclusions,
+                    required,
+                }
+            }
+            Command::RestorePlayer {
+                id,
+                info,
+                games,
+                exclusions,
+                required,
+            } => {
+                let mut constraints = self.constraints.clone();
+                for other in exclusions {
+                    constraints.add_exclusion(id, other);
+                }
+                constraints.set_required(id, required);
+                self.restore_player(id, info, games, constraints)?;
+                Command::UnregisterPlayer(id)
+            }
+            Command::SetPlayerInfo(id, info) => {
+                let old = self
+                    .get_player_info(&id)
+                    .ok_or(TournamentError::InvalidPlayerId(id))?
+                    .clone();
+                self.set_player_info(id, info)?;
+                self.snapshot += 1;
+                Command::SetPlayerInfo(id, old)
+            }
+            Command::RegisterPilot(info) => {
+                Command::UnregisterPilot(self.register_pilot(info.name().to_owned())?)
+            }
+            Command::UnregisterPilot(id) => {
+                let info = self
+                    .get_pilot_info(id)
+                    .ok_or(TournamentError::InvalidPilotId(id))?
+                    .clone();
+                let decks = self
+                    .players
+                    .iter()
+                    .filter(|(_, info)| info.owner() == Some(id))
+                    .map(|(deck, _)| *deck)
+                    .sorted()
+                    .collect();
+                let games = self
+                    .games
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(gid, game)| {
+                        game.details()
+                            .pilots
+                            .iter()
+                            .filter(|(_, pilot)| **pilot == id)
+                            .map(move |(deck, _)| (gid, *deck))
+                    })
+                    .collect();
+                self.unregister_pilot(id)?;
+                Command::RestorePilot {
+                    id,
+                    info,
+                    decks,
+                    games,
+                }
+            }
+            Command::RestorePilot {
+                id,
+                info,
+                decks,
+                games,
+            } => {
+                self.restore_pilot(id, info, &decks, &games)?;
+                Command::UnregisterPilot(id)
+            }
+            Command::SetDeckOwner(deck, owner) => {
+                let old = self
+                    .get_player_info(&deck)
+                    .ok_or(TournamentError::InvalidPlayerId(deck))?
+                    .owner();
+                self.set_deck_owner(deck, owner)?;
+                Command::SetDeckOwner(deck, old)
+            }
+            Command::RegisterGame(entry) => {
+                let old = self.constraints.clone();
+                self.register_entry(entry)?;
+                self.restoring_constraints(old, Command::DeleteGame(self.games.len() - 1))
+            }
+            Command::InsertGame(index, entry) => {
+                let old = self.constraints.clone();
+                self.insert_game_at(index, entry)?;
+                self.restoring_constraints(old, Command::DeleteGame(index))
+            }
+            Command::UpdateGame(gid, entry) => {
+                let old = self.game_entry(gid)?;
+                self.update_game(gid, entry)?;
+                Command::UpdateGame(gid, old)
+            }
+            Command::DeleteGame(gid) => {
+                let old = self.game_entry(gid)?;
+                self.delete_game(gid)?;
+                Command::InsertGame(gid, old)
+            }
+            Command::SetConfig(config) => {
+                let old = self.config.clone();
+                self.set_config(config)?;
+                Command::SetConfig(old)
+            }
+            Command::SetConstraints(constraints) => {
+                let old = self.constraints.clone();
+                self.set_constraints(constraints)?;
+                Command::SetConstraints(old)
+            }
+            Command::Batch(commands) => {
+                let mut inverses = Vec::with_capacity(commands.len());
+                for command in commands {
+                    match self.apply(command) {
+                        Ok(inverse) => inverses.push(inverse),
+                        Err(err) => {
+                            for inverse in inverses.into_iter().rev() {
+                                self.apply(inverse)?;
+                            }
+                            return Err(err);
+                        }
+                    }
+                }
+                inverses.reverse();
+                Command::Batch(inverses)
+            }
+            Command::Merge(other) => {
+                let before = self.clone();
+                if let Err(err) = self.merge(&other) {
+                    *self = before;
+                    return Err(err);
+                }
+                Command::Replace(Box::new(before))
+            }
+            Command::Replace(tournament) => {
+                let snapshot = self.snapshot;
+                let before = core::mem::replace(self, *tournament);
+                self.snapshot = snapshot + 1;
+                Command::Replace(Box::new(before))
+            }
+        })
+    }
+
+    /// Adding a game lets its decks off "must play next". When that changed the constraints, the
+    /// old ones are put back before the game is removed again.
+    fn restoring_constraints(&self, old: MatchConstraints, inverse: Command) -> Command {
+        if self.constraints == old {
+            return inverse;
+        }
+        Command::Batch(vec![Command::SetConstraints(old), inverse])
+    }
+
+    fn game_entry(&self, gid: usize) -> Result<GameEntry, TournamentError> {
+        self.games
+            .get(gid)
+            .map(GameRecord::to_entry)
+            .ok_or(TournamentError::GameNotFound(gid))
+    }
+
+    fn restore_player(
+        &mut self,
+        id: u32,
+        info: PlayerInfo,
+        mut games: Vec<(usize, GameEntry)>,
+        constraints: MatchConstraints,
+    ) -> Result<(), TournamentError> {
+        if self.is_id_registered(&id) {
+            return Err(TournamentError::InvalidPlayerId(id));
+        }
+        if let Some(old_id) = self.get_player_id(info.name()) {
+            return Err(TournamentError::PlayerAlreadyRegistered(
+                info.name().to_owned(),
+                old_id,
+            ));
+        }
+        games.sort_by_key(|(gid, _)| *gid);
+
+        let before = self.clone();
+        self.player_names.insert(info.name().to_owned(), id);
+        self.players.insert(id, info);
+
+        let start = games.first().map_or(self.games.len(), |(gid, _)| *gid);
+        if start > self.games.len() {
+            *self = before;
+            return Err(TournamentError::GameNotFound(start));
+        }
+        let mut entries = self.split_games_off(start);
+        for (gid, entry) in games {
+            let index = gid - start;
+            if index > entries.len() {
+                *self = before;
+                return Err(TournamentError::GameNotFound(gid));
+            }
+            entries.insert(index, entry);
+        }
+        if let Err(err) = self
+            .replay_games(entries)
+            .and_then(|()| self.set_constraints(constraints))
+        {
+            *self = before;
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+/// One change in a [`CommandLog`], along with the command that reverses it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub command: Command,
+    pub inverse: Command,
+}
+
+/// Changes made to a tournament, so that they can be undone and redone
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandLog {
+    undo: Vec<LogEntry>,
+    redo: Vec<LogEntry>,
+}
+
+impl CommandLog {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies a command to the tournament and records it. Anything that was undone can no
+    /// longer be redone.
+    pub fn execute(
+        &mut self,
+        tournament: &mut Tournament,
+        command: Command,
+    ) -> Result<(), TournamentError> {
+        let inverse = tournament.apply(command.clone())?;
+        self.undo.push(LogEntry { command, inverse });
+        self.redo.clear();
+        Ok(())
+    }
+
+    /// Reverses the last change. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, tournament: &mut Tournament) -> Result<bool, TournamentError> {
+        let Some(entry) = self.undo.pop() else {
+            return Ok(false);
+        };
+        match tournament.apply(entry.inverse.clone()) {
+            Ok(command) => {
+                self.redo.push(LogEntry {
+                    command,
+                    inverse: entry.inverse,
+                });
+                Ok(true)
+            }
+            Err(err) => {
+                self.undo.push(entry);
+                Err(err)
+            }
+        }
+    }
+
+    /// Applies the last undone change again. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, tournament: &mut Tournament) -> Result<bool, TournamentError> {
+        let Some(entry) = self.redo.pop() else {
+            return Ok(false);
+        };
+        match tournament.apply(entry.command.clone()) {
+            Ok(inverse) => {
+                self.undo.push(LogEntry {
+                    command: entry.command,
+                    inverse,
+                });
+                Ok(true)
+            }
+            Err(err) => {
+                self.redo.push(entry);
+                Err(err)
+            }
+        }
+    }
+
+    #[must_use]
+    pub const fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    #[must_use]
+    pub const fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        command::{Command, CommandLog},
+        game::{entry::GameEntry, outcome::GameOutcome, record::GameRecord},
+        matches::constraints::MatchConstraints,
+        pilot::PilotInfo,
+        player::info::PlayerInfo,
+    };
+
+    /// Runs each command, then checks that undoing them one at a time goes back through every
+    /// state to the start and that redoing them goes forward again to the end
+    fn assert_round_trip(mut tourn: Tournament, commands: Vec<Command>) {
+        let mut states = vec![tourn.clone()];
+        let mut log = CommandLog::new();
+        for command in commands {
+            log.execute(&mut tourn, command).unwrap();
+            states.push(tourn.clone());
+        }
+
+        for state in states.iter().rev().skip(1) {
+            assert!(log.undo(&mut tourn).unwrap());
+            assert_same(state, &tourn);
+        }
+        assert!(!log.can_undo());
+
+        for state in states.iter().skip(1) {
+            assert!(log.redo(&mut tourn).unwrap());
+            assert_same(state, &tourn);
+        }
+        assert!(!log.can_redo());
+    }
+
+    fn assert_same(expected: &Tournament, actual: &Tournament) {
+        assert_eq!(expected.players(), actual.players());
+        assert_eq!(expected.pilots(), actual.pilots());
+        assert_eq!(expected.config(), actual.config());
+        assert_eq!(expected.constraints(), actual.constraints());
+        let entries = |t: &Tournament| t.games().iter().map(GameRecord::to_entry).collect_vec();
+        assert_eq!(entries(expected), entries(actual));
+        for id in expected.players().keys() {
+            let elo = |t: &Tournament| t.get_player_or_default_stats(*id).elo();
+            assert_relative_eq!(elo(expected), elo(actual), epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn player_commands_round_trip() {
+        let tourn = Tournament::generate_tournament(6, 20).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let mut info = tourn.get_player_info(&ids[1]).unwrap().clone();
+        info.set_name("Renamed".to_owned());
+        let mut constraints = MatchConstraints::new();
+        constraints.add_exclusion(ids[2], ids[0]);
+        constraints.add_exclusion(ids[3], ids[2]);
+        constraints.set_required(ids[2], true);
+        assert_round_trip(
+            tourn,
+            vec![
+                Command::RegisterPlayer(PlayerInfo::new("New".to_owned())),
+                Command::SetPlayerInfo(ids[1], info),
+                Command::SetConstraints(constraints),
+                Command::UnregisterPlayer(ids[2]),
+            ],
+        );
+    }
+
+    #[test]
+    fn pilot_commands_round_trip() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let alice = tourn.register_pilot("Alice".to_owned()).unwrap();
+        let mut entry = GameEntry::new(ids.clone(), GameOutcome::Win(ids[0])).unwrap();
+        entry.details_mut().pilots.insert(ids[1], alice);
+        tourn.register_entry(entry).unwrap();
+        let bob = tourn.next_pilot_id();
+        assert_round_trip(
+            tourn,
+            vec![
+                Command::SetDeckOwner(ids[0], Some(alice)),
+                Command::RegisterPilot(PilotInfo::new("Bob".to_owned())),
+                Command::SetDeckOwner(ids[2], Some(bob)),
+                Command::UnregisterPilot(alice),
+            ],
+        );
+    }
+
+    #[test]
+    fn game_commands_round_trip() {
+        let tourn = Tournament::generate_tournament(6, 20).unwrap();
+        let ids = tourn
+            .players()
+            .keys()
+            .copied()
+            .sorted()
+            .take(4)
+            .collect_vec();
+        let entry = GameEntry::new(ids.clone(), GameOutcome::Win(ids[0])).unwrap();
+        let mut config = tourn.config().clone();
+        config.starting_elo += 100.0;
+        let mut constraints = tourn.constraints().clone();
+        constraints.add_exclusion(ids[0], ids[1]);
+        assert_round_trip(
+            tourn,
+            vec![
+                Command::RegisterGame(entry.clone()),
+                Command::InsertGame(3, entry.clone()),
+                Command::UpdateGame(10, entry),
+                Command::DeleteGame(0),
+                Command::SetConfig(config),
+                Command::SetConstraints(constraints),
+            ],
+        );
+    }
+
+    #[test]
+    fn playing_clears_required() {
+        let mut tourn = Tournament::generate_tournament(6, 20).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let mut constraints = MatchConstraints::new();
+        constraints.set_required(ids[0], true);
+        constraints.set_required(ids[5], true);
+        tourn.set_constraints(constraints).unwrap();
+
+        let entry = GameEntry::new(ids[..4].to_vec(), GameOutcome::Win(ids[0])).unwrap();
+        let mut log = CommandLog::new();
+        log.execute(&mut tourn, Command::RegisterGame(entry))
+            .unwrap();
+        assert_eq!(&[ids[5]], tourn.constraints().required());
+
+        log.undo(&mut tourn).unwrap();
+        assert_eq!(&[ids[0], ids[5]], tourn.constraints().required());
+        assert_eq!(20, tourn.games().len());
+        log.redo(&mut tourn).unwrap();
+        assert_eq!(&[ids[5]], tourn.constraints().required());
+
+        let entry = GameEntry::new(ids[2..].to_vec(), GameOutcome::Win(ids[5])).unwrap();
+        assert_round_trip(tourn, vec![Command::InsertGame(0, entry)]);
+    }
+
+    #[test]
+    fn failed_batch_changes_nothing() {
+        let mut tourn = Tournament::generate_tournament(4, 2).unwrap();
+        let before = tourn.clone();
+        tourn
+            .apply(Command::Batch(vec![
+                Command::DeleteGame(0),
+                Command::DeleteGame(5),
+            ]))
+            .unwrap_err();
+        assert_same(&before, &tourn);
+    }
+
+    #[test]
+    fn merge_round_trips() {
+        let tourn = Tournament::generate_tournament(6, 10).unwrap();
+        let other = Tournament::generate_tournament(8, 10).unwrap();
+        assert_round_trip(tourn, vec![Command::Merge(Box::new(other))]);
+    }
+
+    #[test]
+    fn failed_command_is_not_logged() {
+        let mut tourn = Tournament::generate_tournament(4, 2).unwrap();
+        let mut log = CommandLog::new();
+        log.execute(&mut tourn, Command::DeleteGame(5)).unwrap_err();
+        log.execute(&mut tourn, Command::UnregisterPlayer(100))
+            .unwrap_err();
+        assert!(!log.can_undo());
+        assert!(!log.undo(&mut tourn).unwrap());
+    }
+
+    #[test]
+    fn new_command_clears_redo() {
+        let mut tourn = Tournament::generate_tournament(4, 2).unwrap();
+        let mut log = CommandLog::new();
+        log.execute(&mut tourn, Command::DeleteGame(0)).unwrap();
+        log.undo(&mut tourn).unwrap();
+        assert!(log.can_redo());
+        log.execute(&mut tourn, Command::DeleteGame(1)).unwrap();
+        assert!(!log.can_redo());
+        assert_eq!(1, tourn.games().len());
+    }
+}
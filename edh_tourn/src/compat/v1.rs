@@ -106,20 +106,24 @@ impl TryFrom<TournamentCompatV1> for Tournament {
             tournament.register_player_with_info(info)?;
         }
 
-        let config = TournamentConfig {
+        let mut config = TournamentConfig {
             starting_elo: value.score_config.starting_elo,
             game_points: value.score_config.game_points,
             game_elo_pow_scale: value.score_config.elo_pow,
             game_wr_pow_scale: value.score_config.wr_pow,
             game_elo_weight: value.score_config.elo_weight,
             game_wr_weight: value.score_config.wr_weight,
-            match_weight_least_played: value.match_config.weight_least_played,
-            match_weight_nemesis: value.match_config.weight_nemesis,
-            match_weight_elo_neighbor: value.match_config.weight_neighbor,
-            match_weight_wr_neighbor: value.match_config.weight_wr_neighbor,
-            match_weight_lost_with: value.match_config.weight_lost_with,
             ..TournamentConfig::default()
         };
+        for (id, weight) in [
+            ("least_played", value.match_config.weight_least_played),
+            ("nemesis", value.match_config.weight_nemesis),
+            ("elo_neighbor", value.match_config.weight_neighbor),
+            ("wr_neighbor", value.match_config.weight_wr_neighbor),
+            ("lost_with", value.match_config.weight_lost_with),
+        ] {
+            config.set_match_weight(id, weight);
+        }
 
         tournament.set_config(config)?;
 
@@ -136,7 +140,7 @@ impl TryFrom<TournamentCompatV1> for Tournament {
 
             let winner = tournament.get_or_register_player(winner)?;
 
-            tournament.register_record(tournament.create_match(players)?.record(winner)?)?;
+            tournament.register_record(tournament.create_match(&players)?.record(winner)?)?;
         }
 
         Ok(tournament)
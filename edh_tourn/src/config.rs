@@ -1,10 +1,31 @@
-use crate::{Tournament, error::TournamentError};
+use std::collections::BTreeMap;
+
+use crate::{
+    Tournament,
+    error::TournamentError,
+    matches::criteria::{CRITERIA, MatchCriterion, criterion},
+    player::stats::PlayerStats,
+    rating::{RatingSystem, RatingSystemKind},
+    serialization::SerdeConfig,
+};
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
-#[serde(default = "Default::default")]
+#[serde(from = "SerdeConfig")]
 pub struct TournamentConfig {
     #[serde(rename = "se", alias = "starting_elo")]
     pub starting_elo: f64,
+    /// How expected results and rating changes are worked out
+    #[serde(rename = "rs", alias = "rating_system")]
+    pub rating_system: RatingSystemKind,
+    /// Deviation of a deck that hasn't played yet, for rating systems that track uncertainty
+    #[serde(rename = "sd", alias = "starting_deviation")]
+    pub starting_deviation: f64,
+    /// Volatility of a deck that hasn't played yet, for Glicko-2
+    #[serde(rename = "sv", alias = "starting_volatility")]
+    pub starting_volatility: f64,
+    /// How fast the volatility may change in Glicko-2. Smaller values keep it steadier.
+    #[serde(rename = "vt", alias = "volatility_tau")]
+    pub volatility_tau: f64,
     #[serde(rename = "gp", alias = "game_points")]
     pub game_points: f64,
     #[serde(rename = "geps", alias = "game_elo_pow_scale")]
@@ -15,45 +36,73 @@ pub struct TournamentConfig {
     pub game_elo_weight: f64,
     #[serde(rename = "gww", alias = "game_wr_weight")]
     pub game_wr_weight: f64,
-    #[serde(rename = "mwlp", alias = "match_weight_least_played")]
-    pub match_weight_least_played: f64,
-    #[serde(rename = "mwn", alias = "match_weight_nemesis")]
-    pub match_weight_nemesis: f64,
-    #[serde(rename = "mwlw", alias = "match_weight_lost_with")]
-    pub match_weight_lost_with: f64,
-    #[serde(
-        rename = "mwln",
-        alias = "match_weight_neighbor",
-        alias = "mwne",
-        alias = "match_weight_elo_neighbor"
-    )]
-    pub match_weight_elo_neighbor: f64,
-    #[serde(rename = "mwwn", alias = "match_weight_wr_neighbor")]
-    pub match_weight_wr_neighbor: f64,
-
-    #[serde(rename = "mwen", alias = "match_weight_expected_neighbor")]
-    pub match_weight_expected_neighbor: f64,
+    /// How much of a game's result is decided by finishing order rather than by the win alone,
+    /// from 0 to 1. Only applies to games with placements.
+    #[serde(rename = "gpw", alias = "game_placement_weight")]
+    pub game_placement_weight: f64,
+    /// Adjusts the expected result of each seat by how often its turn order position wins, for
+    /// games with a known turn order
+    #[serde(rename = "sa", alias = "seat_advantage")]
+    pub seat_advantage: bool,
+    /// Games of a pod size needed before seat advantage applies to that pod size
+    #[serde(rename = "sag", alias = "seat_advantage_min_games")]
+    pub seat_advantage_min_games: u32,
+    /// Weight of each matchmaking criterion in the combined ranking, keyed by criterion id
+    #[serde(rename = "mw", alias = "match_weights")]
+    pub match_weights: BTreeMap<String, f64>,
 }
 
 impl Default for TournamentConfig {
     fn default() -> Self {
         Self {
             starting_elo: 1500.0,
+            rating_system: RatingSystemKind::default(),
+            starting_deviation: 250.0,
+            starting_volatility: 0.06,
+            volatility_tau: 0.5,
             game_points: 25.0,
             game_elo_pow_scale: 6.0,
             game_wr_pow_scale: 1.0,
             game_elo_weight: 65.0,
             game_wr_weight: 35.0,
-            match_weight_least_played: 6.0,
-            match_weight_nemesis: 4.0,
-            match_weight_elo_neighbor: 5.0,
-            match_weight_wr_neighbor: 3.0,
-            match_weight_lost_with: 3.0,
-            match_weight_expected_neighbor: 4.0,
+            game_placement_weight: 0.5,
+            seat_advantage: false,
+            seat_advantage_min_games: 40,
+            match_weights: CRITERIA
+                .iter()
+                .map(|criterion| (criterion.id().to_owned(), criterion.default_weight()))
+                .collect(),
         }
     }
 }
 
+impl TournamentConfig {
+    #[must_use]
+    pub const fn rating(&self) -> &'static dyn RatingSystem {
+        self.rating_system.system()
+    }
+
+    /// Stats of a deck that hasn't played a game yet
+    #[must_use]
+    pub fn initial_stats(&self) -> PlayerStats {
+        self.rating().initial_stats(self)
+    }
+
+    /// Weight of the criterion in the combined ranking, or its default weight when the config
+    /// doesn't set one
+    #[must_use]
+    pub fn match_weight(&self, id: &str) -> f64 {
+        self.match_weights
+            .get(id)
+            .copied()
+            .unwrap_or_else(|| criterion(id).map_or(0.0, MatchCriterion::default_weight))
+    }
+
+    pub fn set_match_weight(&mut self, id: &str, weight: f64) {
+        self.match_weights.insert(id.to_owned(), weight);
+    }
+}
+
 impl Tournament {
     #[must_use]
     pub const fn config(&self) -> &TournamentConfig {
@@ -69,7 +118,7 @@ impl Tournament {
 
 #[cfg(test)]
 mod tests {
-    use crate::Tournament;
+    use crate::{Tournament, config::TournamentConfig};
 
     #[test]
     fn updating_config_updates_stats() {
@@ -94,4 +143,37 @@ mod tests {
         let new_version = tournament.snapshot;
         assert_eq!(version + 1, new_version);
     }
+
+    #[test]
+    fn old_match_weights_load_into_the_map() {
+        let config = Tournament::sample_game().config().clone();
+        assert!((config.match_weight("least_played") - 6.0).abs() < f64::EPSILON);
+        assert!((config.match_weight("elo_neighbor") - 4.0).abs() < f64::EPSILON);
+        assert!((config.match_weight("wr_neighbor") - 3.5).abs() < f64::EPSILON);
+        assert!((config.match_weight("lost_with") - 2.0).abs() < f64::EPSILON);
+
+        let config: TournamentConfig =
+            serde_json::from_str(r#"{"starting_elo":1000.0,"match_weight_nemesis":1.5}"#).unwrap();
+        assert!((config.starting_elo - 1000.0).abs() < f64::EPSILON);
+        assert!((config.match_weight("nemesis") - 1.5).abs() < f64::EPSILON);
+        assert!((config.match_weight("least_played") - 6.0).abs() < f64::EPSILON);
+        assert_eq!(
+            TournamentConfig::default().rating_system,
+            config.rating_system
+        );
+    }
+
+    #[test]
+    fn match_weights_round_trip() {
+        let mut config = TournamentConfig::default();
+        config.set_match_weight("kill_nemesis", 2.5);
+        let ron = ron::to_string(&config).unwrap();
+        assert!(ron.contains("mw:"));
+        assert_eq!(config, ron::from_str(&ron).unwrap());
+
+        let config: TournamentConfig = ron::from_str("(mw:{\"nemesis\":1.0})").unwrap();
+        assert!((config.match_weight("nemesis") - 1.0).abs() < f64::EPSILON);
+        assert!((config.match_weight("elo_neighbor") - 5.0).abs() < f64::EPSILON);
+        assert!(config.match_weight("missing").abs() < f64::EPSILON);
+    }
 }
@@ -6,6 +6,8 @@ pub enum TournamentError {
     PlayerNotInMatch(u32),
     #[error("Player name is not registered: {0}")]
     PlayerNameNotRegistered(String),
+    #[error("Player is in the pod more than once: {0}")]
+    DuplicatePlayer(u32),
     #[error("Player ID is not valid: {0}")]
     InvalidPlayerId(u32),
     #[error("Player name is already registered: {0}, id {1}")]
@@ -14,8 +16,30 @@ pub enum TournamentError {
     GameNotFound(usize),
     #[error("Not enough players")]
     NotEnoughPlayers,
+    #[error("Invalid pod size: {0}")]
+    InvalidPodSize(usize),
+    #[error("{0} decks can't be split into pods of three and four")]
+    CannotSplitIntoPods(usize),
+    #[error("The decks can't be split into pods without breaking the matchmaking constraints")]
+    UnsatisfiableConstraints,
+    #[error("A draw needs at least two players and no winner")]
+    InvalidDraw,
+    #[error("Invalid placement of player: {0}")]
+    InvalidPlacement(u32),
+    #[error("Invalid seat: {0}")]
+    InvalidSeat(usize),
+    #[error("Invalid elimination of player: {0}")]
+    InvalidElimination(u32),
     #[error("Player name is invalid: '{0}'")]
     InvalidPlayerName(String),
+    #[error("Pilot ID is not valid: {0}")]
+    InvalidPilotId(u32),
+    #[error("Pilot name is already registered: {0}, id {1}")]
+    PilotAlreadyRegistered(String, u32),
+    #[error("Pilot name is invalid: '{0}'")]
+    InvalidPilotName(String),
+    #[error("Line {0} has a blank cell among its decks or winner")]
+    BlankCell(usize),
     #[error("Record has no elo data")]
     RecordNoEloData,
 }
@@ -1,48 +1,97 @@
+pub mod details;
+pub mod elimination;
 pub mod entry;
 pub mod match_player;
 pub mod matchup;
+pub mod outcome;
+pub mod preview;
 pub mod record;
+pub mod seat;
+
+use itertools::Itertools;
 
 use crate::game::entry::GameEntry;
+use crate::game::match_player::MatchPlayer;
+use crate::game::outcome::SeatResult;
 use crate::game::record::GameRecord;
 use crate::{
     Tournament,
     error::{TournResult, TournamentError},
 };
 
+/// Smallest number of decks that can sit at a table
+pub const MIN_POD_SIZE: usize = 3;
+/// Largest number of decks that can sit at a table
+pub const MAX_POD_SIZE: usize = 6;
+/// Number of decks at a usual table
+pub const DEFAULT_POD_SIZE: usize = 4;
+
+pub(crate) const fn ensure_pod_size(size: usize) -> Result<(), TournamentError> {
+    if size < MIN_POD_SIZE || size > MAX_POD_SIZE {
+        return Err(TournamentError::InvalidPodSize(size));
+    }
+    Ok(())
+}
+
 impl Tournament {
     pub fn register_entry(&mut self, entry: GameEntry) -> Result<(), TournamentError> {
-        let matchup = self.create_match(*entry.players())?;
-        let record = matchup.record(entry.winner())?;
+        let record = self.create_entry_record(entry)?;
+        self.clear_played_required(record.players().iter().map(MatchPlayer::id));
         self.insert_game_record(record);
         self.snapshot += 1;
         Ok(())
     }
 
     pub fn register_record(&mut self, record: GameRecord) -> Result<(), TournamentError> {
-        self.insert_game_record(self.update_record(record)?);
+        let record = self.update_record(record)?;
+        self.clear_played_required(record.players().iter().map(MatchPlayer::id));
+        self.insert_game_record(record);
         self.snapshot += 1;
         Ok(())
     }
 
+    /// Decks that must play next are let off once they are in a new game
+    fn clear_played_required(&mut self, ids: impl IntoIterator<Item = u32>) {
+        for id in ids {
+            self.constraints.set_required(id, false);
+        }
+    }
+
     pub(super) fn insert_game_record(&mut self, record: GameRecord) {
-        let mut winner_tracked = false;
-
-        for player in record.matchup().players() {
-            let stats = self
-                .stats
-                .entry(player.id())
-                .or_insert_with(|| self.default_stats.clone());
-
-            if !winner_tracked && player.id() == record.winner() {
-                stats.add_win(*player.elo_win());
-                winner_tracked = true;
-            } else {
-                stats.add_loss(*player.elo_loss());
+        // Games without a result are kept in the history without touching anyone's stats
+        let mut checkpoint = Vec::new();
+        if let (Some(results), Some(scores)) = (record.seat_results(), record.seat_scores()) {
+            for ((player, result), score) in record.players().iter().zip(results).zip(scores) {
+                let stats = self
+                    .stats
+                    .entry(player.id())
+                    .or_insert_with(|| self.default_stats.clone());
+
+                let elo_change = player.elo_change(score);
+                match result {
+                    SeatResult::Win => stats.add_win(elo_change),
+                    SeatResult::Draw(_) => stats.add_draw(elo_change),
+                    SeatResult::Loss => stats.add_loss(-elo_change),
+                }
+                self.config.rating().update_state(
+                    stats,
+                    player,
+                    record.players(),
+                    score,
+                    &self.config,
+                );
+            }
+
+            for id in record.players().iter().map(MatchPlayer::id).unique() {
+                if let Some(stats) = self.stats.get(&id) {
+                    checkpoint.push((id, stats.clone()));
+                }
             }
         }
 
+        seat::tally_game(&mut self.seat_tallies, &record);
         self.games.push(record);
+        self.checkpoints.push(checkpoint);
     }
 
     #[must_use]
@@ -65,8 +114,99 @@ impl Tournament {
         if gid >= self.games.len() {
             return Err(TournamentError::GameNotFound(gid));
         }
-        self.games.remove(gid);
-        self.reload()?;
+        self.edit_games_from(gid, |mut entries| {
+            entries.remove(0);
+            Ok(entries)
+        })
+    }
+
+    /// Replaces a game, keeping its place in the history. Games after it are recorded again, as
+    /// their ratings depend on it.
+    pub fn update_game(&mut self, gid: usize, entry: GameEntry) -> TournResult<()> {
+        if gid >= self.games.len() {
+            return Err(TournamentError::GameNotFound(gid));
+        }
+        self.create_entry_record(entry.clone())?;
+
+        self.edit_games_from(gid, |mut entries| {
+            if let Some(game) = entries.first_mut() {
+                *game = entry;
+            }
+            Ok(entries)
+        })
+    }
+
+    /// Adds a game before the game at `index`, or at the end if `index` is the number of games.
+    /// Games after it are recorded again, as their ratings depend on it.
+    pub fn insert_game_at(&mut self, index: usize, entry: GameEntry) -> TournResult<()> {
+        if index > self.games.len() {
+            return Err(TournamentError::GameNotFound(index));
+        }
+        self.create_entry_record(entry.clone())?;
+
+        let ids = entry.players().to_vec();
+        self.edit_games_from(index, |mut entries| {
+            entries.insert(0, entry);
+            Ok(entries)
+        })?;
+        self.clear_played_required(ids);
+        Ok(())
+    }
+
+    /// Replaces the games from `index` onwards with the entries `edit` makes of them, and replays
+    /// those. If `edit` fails or an entry can't be recorded, the games, checkpoints and stats are
+    /// put back as they were, so a failed edit doesn't cut the history short.
+    pub(crate) fn edit_games_from<F>(&mut self, index: usize, edit: F) -> TournResult<()>
+    where
+        F: FnOnce(Vec<GameEntry>) -> TournResult<Vec<GameEntry>>,
+    {
+        let games = self.games.get(index..).unwrap_or_default().to_vec();
+        let checkpoints = self.checkpoints.get(index..).unwrap_or_default().to_vec();
+        let stats = self.stats.clone();
+        let seat_tallies = self.seat_tallies.clone();
+
+        let result =
+            edit(self.split_games_off(index)).and_then(|entries| self.replay_games(entries));
+        if result.is_err() {
+            self.games.truncate(index);
+            self.games.extend(games);
+            self.checkpoints.truncate(index);
+            self.checkpoints.extend(checkpoints);
+            self.stats = stats;
+            self.seat_tallies = seat_tallies;
+        }
+        result
+    }
+
+    /// Removes the games from `index` onwards, returning them as entries. The stats are rolled
+    /// back to how they were before the game at `index`.
+    pub(crate) fn split_games_off(&mut self, index: usize) -> Vec<GameEntry> {
+        self.checkpoints.truncate(index);
+        self.stats.clear();
+        for checkpoint in &self.checkpoints {
+            for (id, stats) in checkpoint {
+                self.stats.insert(*id, stats.clone());
+            }
+        }
+
+        let entries = self
+            .games
+            .split_off(index)
+            .into_iter()
+            .map(GameEntry::from)
+            .collect();
+        self.recount_seats();
+        entries
+    }
+
+    /// Records the entries after the games still in the history. The games that are kept aren't
+    /// matched again, as nothing before them changed.
+    pub(crate) fn replay_games(&mut self, entries: Vec<GameEntry>) -> TournResult<()> {
+        for entry in entries {
+            let record = self.create_entry_record(entry)?;
+            self.insert_game_record(record);
+        }
+        self.snapshot += 1;
         Ok(())
     }
 }
@@ -75,18 +215,22 @@ impl Tournament {
 mod tests {
     #![allow(clippy::indexing_slicing)]
 
+    use std::collections::HashMap;
+
     use itertools::Itertools;
 
-    use crate::Tournament;
+    use crate::{
+        Tournament,
+        error::TournamentError,
+        game::{entry::GameEntry, outcome::GameOutcome, record::GameRecord},
+    };
 
     #[test]
     fn winner_gains_points() -> anyhow::Result<()> {
         for i in 0..4 {
             let mut tourn = Tournament::generate_tournament(4, 0)?;
-            let ids = tourn.players().keys().copied().collect_vec();
-            let mut match_ids = [0; 4];
-            match_ids.copy_from_slice(&ids);
-            let matchup = tourn.create_match(match_ids)?;
+            let match_ids = tourn.players().keys().copied().collect_vec();
+            let matchup = tourn.create_match(&match_ids)?;
             let starting_elo = matchup.players()[i].stats().elo();
             tourn.register_record(matchup.record(match_ids[i])?)?;
             let elo = tourn.stats[&match_ids[i]].elo();
@@ -105,9 +249,7 @@ mod tests {
             let tourn = Tournament::generate_tournament(4, 0)?;
             let ids = tourn.players().keys().copied().collect_vec();
             let winner_id = ids[winner_i];
-            let mut match_ids = [0; 4];
-            match_ids.copy_from_slice(&ids);
-            let matchup = tourn.create_match(match_ids)?;
+            let matchup = tourn.create_match(&ids)?;
             for loser_i in 0..4 {
                 let mut tourn = tourn.clone();
                 let matchup = matchup.clone();
@@ -130,7 +272,7 @@ mod tests {
     fn winner_only_counted_once() -> anyhow::Result<()> {
         let mut tourn = Tournament::new();
         let id = tourn.register_player(String::from("sample"))?;
-        let matchup = tourn.create_match([id, id, id, id])?;
+        let matchup = tourn.create_match(&[id, id, id, id])?;
         let starting_elo = matchup.players()[0].stats().elo();
         tourn.register_record(matchup.record(id)?)?;
         let elo = tourn.stats[&id].elo();
@@ -141,4 +283,257 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn odd_pod_sizes_register() -> anyhow::Result<()> {
+        for size in [3, 5, 6] {
+            let mut tourn = Tournament::generate_tournament(size, 0)?;
+            let ids = tourn.players().keys().copied().sorted().collect_vec();
+            let winner = ids[0];
+            let starting_elo = tourn.default_stats().elo();
+            tourn.register_record(tourn.create_match(&ids)?.record(winner)?)?;
+
+            assert_eq!(1, tourn.games().len());
+            assert!(tourn.stats[&winner].elo() > starting_elo);
+            let total_change = ids
+                .iter()
+                .map(|id| tourn.stats[id].elo() - starting_elo)
+                .sum::<f64>();
+            assert!(total_change.abs() <= 1e-9, "Pod of {size} was not zero-sum");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn draw_splits_points() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(4, 0)?;
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let starting_elo = tourn.default_stats().elo();
+        let outcome = GameOutcome::Draw(vec![ids[0], ids[1]]);
+        tourn.register_record(tourn.create_match(&ids)?.record_outcome(outcome)?)?;
+
+        for id in &ids[..2] {
+            let stats = &tourn.stats[id];
+            assert_eq!((1, 0, 1), (stats.games(), stats.wins(), stats.draws()));
+            assert!(stats.elo() > starting_elo);
+        }
+        for id in &ids[2..] {
+            assert!(tourn.stats[id].elo() < starting_elo);
+        }
+        let total_change = ids
+            .iter()
+            .map(|id| tourn.stats[id].elo() - starting_elo)
+            .sum::<f64>();
+        assert!(total_change.abs() <= 1e-9, "Draw was not zero-sum");
+        Ok(())
+    }
+
+    #[test]
+    fn placements_reward_later_eliminations() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(4, 0)?;
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let starting_elo = tourn.default_stats().elo();
+        let placements = vec![ids[0], ids[1], ids[2], ids[3]];
+        let record = tourn
+            .create_match(&ids)?
+            .record_placements(GameOutcome::Win(ids[0]), placements)?;
+        assert_eq!(Some(2), record.placement(ids[1]));
+        tourn.register_record(record)?;
+
+        let elos = ids.iter().map(|id| tourn.stats[id].elo()).collect_vec();
+        assert!(elos[0] > elos[1]);
+        assert!(elos[1] > elos[2]);
+        assert!(elos[2] > elos[3]);
+        assert_eq!(0, tourn.stats[&ids[1]].wins());
+        let total_change = elos.iter().map(|elo| elo - starting_elo).sum::<f64>();
+        assert!(total_change.abs() <= 1e-9, "Placements were not zero-sum");
+        Ok(())
+    }
+
+    #[test]
+    fn no_result_changes_nothing() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(4, 8)?;
+        let stats = tourn.stats.clone();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let record = tourn
+            .create_match(&ids)?
+            .record_outcome(GameOutcome::NoResult)?;
+        tourn.register_record(record)?;
+
+        assert_eq!(9, tourn.games().len());
+        assert_eq!(stats, tourn.stats);
+        Ok(())
+    }
+
+    /// Copy of the tournament without any games, with the given games registered one by one the
+    /// way they would have been entered. Doesn't go through the replay code used by edits.
+    fn rebuilt(tourn: &Tournament, entries: Vec<GameEntry>) -> anyhow::Result<Tournament> {
+        let mut rebuilt = Tournament {
+            stats: HashMap::new(),
+            default_stats: tourn.config.initial_stats(),
+            games: Vec::new(),
+            checkpoints: Vec::new(),
+            seat_tallies: HashMap::new(),
+            ..tourn.clone()
+        };
+        for entry in entries {
+            rebuilt.register_entry(entry)?;
+        }
+        Ok(rebuilt)
+    }
+
+    #[test]
+    fn update_game_keeps_position() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(6, 20)?;
+        let mut entries = tourn.games().iter().map(GameRecord::to_entry).collect_vec();
+        let players = entries[5].players().to_vec();
+        let entry = GameEntry::new(players.clone(), GameOutcome::Win(players[1]))?;
+        entries[5] = entry.clone();
+
+        let snapshot = tourn.snapshot;
+        tourn.update_game(5, entry)?;
+        assert!(tourn.snapshot > snapshot);
+        assert_eq!(20, tourn.games().len());
+        assert_eq!(Some(players[1]), tourn.games()[5].winner());
+
+        let expected = rebuilt(&tourn, entries)?;
+        for (id, stats) in &expected.stats {
+            assert_relative_eq!(stats.elo(), tourn.stats[id].elo(), epsilon = 1e-9);
+        }
+        tourn
+            .update_game(20, GameEntry::new(players, GameOutcome::NoResult)?)
+            .unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn insert_game_at_position() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(6, 10)?;
+        let mut entries = tourn.games().iter().map(GameRecord::to_entry).collect_vec();
+        let ids = tourn
+            .players()
+            .keys()
+            .copied()
+            .sorted()
+            .take(4)
+            .collect_vec();
+        let entry = GameEntry::new(ids.clone(), GameOutcome::Win(ids[0]))?;
+        entries.insert(3, entry.clone());
+
+        tourn.insert_game_at(3, entry.clone())?;
+        assert_eq!(11, tourn.games().len());
+        assert_eq!(ids, tourn.games()[3].ids());
+
+        let expected = rebuilt(&tourn, entries)?;
+        for (id, stats) in &expected.stats {
+            assert_relative_eq!(stats.elo(), tourn.stats[id].elo(), epsilon = 1e-9);
+        }
+
+        tourn.insert_game_at(11, entry.clone())?;
+        assert_eq!(12, tourn.games().len());
+        tourn.insert_game_at(13, entry).unwrap_err();
+        Ok(())
+    }
+
+    #[test]
+    fn new_games_clear_must_play_next() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(6, 10)?;
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for id in &ids {
+            tourn.constraints.set_required(*id, true);
+        }
+
+        tourn.register_entry(GameEntry::new(ids[..4].to_vec(), GameOutcome::Win(ids[0]))?)?;
+        tourn.insert_game_at(
+            2,
+            GameEntry::new(ids[2..5].to_vec(), GameOutcome::Win(ids[2]))?,
+        )?;
+        let required = ids.iter().filter(|id| tourn.constraints.is_required(**id));
+        assert!(required.eq(&ids[5..]));
+
+        // Replaying the history after a delete doesn't touch the constraints
+        tourn.constraints.set_required(ids[0], true);
+        tourn.delete_game(0)?;
+        assert!(tourn.constraints.is_required(ids[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn failed_replay_keeps_history() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(6, 10)?;
+        let before = tourn.clone();
+        let unregistered = GameEntry::new(vec![0, 1, 2, 100], GameOutcome::Win(0))?;
+
+        // The bad entry is only reached after the first few games were replayed
+        tourn
+            .edit_games_from(3, |mut entries| {
+                entries.push(unregistered);
+                Ok(entries)
+            })
+            .unwrap_err();
+        assert_eq!(before, tourn);
+        assert_eq!(10, tourn.checkpoints.len());
+
+        tourn
+            .edit_games_from(3, |_| Err(TournamentError::GameNotFound(3)))
+            .unwrap_err();
+        assert_eq!(before, tourn);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_edits_change_nothing() -> anyhow::Result<()> {
+        let mut tourn = Tournament::generate_tournament(6, 10)?;
+        let before = tourn.clone();
+        let unregistered = GameEntry::new(vec![0, 1, 2, 100], GameOutcome::Win(0))?;
+        tourn.update_game(2, unregistered.clone()).unwrap_err();
+        tourn.insert_game_at(2, unregistered).unwrap_err();
+        assert_eq!(before, tourn);
+        Ok(())
+    }
+
+    /// Checks that a tournament edited in place is the same as one with its final games
+    /// registered in order from scratch
+    fn assert_matches_rebuilt(tourn: &Tournament) -> anyhow::Result<()> {
+        let entries = tourn.games().iter().map(GameRecord::to_entry).collect_vec();
+        let expected = rebuilt(tourn, entries)?;
+        assert_eq!(expected.stats, tourn.stats);
+        assert_eq!(expected.checkpoints, tourn.checkpoints);
+        assert_eq!(expected.seat_tallies, tourn.seat_tallies);
+        assert_eq!(tourn.games().len(), tourn.checkpoints.len());
+        Ok(())
+    }
+
+    #[test]
+    fn incremental_edits_match_rebuilt() -> anyhow::Result<()> {
+        for tourn in Tournament::test_tournaments() {
+            assert_matches_rebuilt(&tourn)?;
+            let len = tourn.games().len();
+            for gid in [0, len / 2, len.saturating_sub(1)] {
+                let Some(game) = tourn.games().get(gid) else {
+                    continue;
+                };
+                let ids = game.ids();
+
+                let mut deleted = tourn.clone();
+                deleted.delete_game(gid)?;
+                assert_matches_rebuilt(&deleted)?;
+
+                let mut updated = tourn.clone();
+                let outcome = GameOutcome::Win(*ids.last().unwrap());
+                updated.update_game(gid, GameEntry::new(ids.clone(), outcome)?)?;
+                assert_matches_rebuilt(&updated)?;
+
+                let mut inserted = tourn.clone();
+                inserted.insert_game_at(gid, game.to_entry())?;
+                assert_matches_rebuilt(&inserted)?;
+
+                let mut unregistered = tourn.clone();
+                unregistered.unregister_player(ids[0])?;
+                assert_matches_rebuilt(&unregistered)?;
+                assert!(!unregistered.stats.contains_key(&ids[0]));
+            }
+        }
+        Ok(())
+    }
 }
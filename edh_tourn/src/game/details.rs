@@ -0,0 +1,311 @@
+use core::{fmt::Display, ops::RangeBounds};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    Tournament,
+    error::TournamentError,
+    game::{elimination::Elimination, outcome::GameOutcome, record::GameRecord},
+};
+
+/// How a game was won
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
+pub enum WinCondition {
+    #[serde(rename = "cb", alias = "Combat")]
+    Combat,
+    #[serde(rename = "co", alias = "Combo")]
+    Combo,
+    #[serde(rename = "cd", alias = "CommanderDamage")]
+    CommanderDamage,
+    #[serde(rename = "aw", alias = "AltWin")]
+    AltWin,
+    #[serde(rename = "cn", alias = "Concession")]
+    Concession,
+}
+
+impl WinCondition {
+    pub const VALUES: [Self; 5] = [
+        Self::Combat,
+        Self::Combo,
+        Self::CommanderDamage,
+        Self::AltWin,
+        Self::Concession,
+    ];
+}
+
+impl Display for WinCondition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Combat => write!(f, "Combat"),
+            Self::Combo => write!(f, "Combo"),
+            Self::CommanderDamage => write!(f, "Commander Damage"),
+            Self::AltWin => write!(f, "Alt Win"),
+            Self::Concession => write!(f, "Concession"),
+        }
+    }
+}
+
+/// Information about a game that doesn't affect ratings
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq, Eq)]
+pub struct GameDetails {
+    /// When the game was played, in seconds since the unix epoch
+    pub played_at: Option<u64>,
+    /// The game night, or any other grouping of games, that the game was played in
+    pub session: Option<String>,
+    /// Pilots that borrowed a deck for the game, by deck id. Decks that aren't listed were
+    /// piloted by their owner.
+    pub pilots: BTreeMap<u32, u32>,
+    /// Turn the game ended on
+    pub turns: Option<u32>,
+    /// How long the game took, in minutes
+    pub duration: Option<u32>,
+    pub win_condition: Option<WinCondition>,
+    pub notes: String,
+    /// Decks knocked out during the game, in the order they were knocked out
+    pub eliminations: Vec<Elimination>,
+}
+
+impl GameDetails {
+    #[must_use]
+    pub const fn played_at(&self) -> Option<u64> {
+        self.played_at
+    }
+
+    #[must_use]
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    #[must_use]
+    pub const fn turns(&self) -> Option<u32> {
+        self.turns
+    }
+
+    #[must_use]
+    pub const fn duration(&self) -> Option<u32> {
+        self.duration
+    }
+
+    #[must_use]
+    pub const fn win_condition(&self) -> Option<WinCondition> {
+        self.win_condition
+    }
+
+    #[must_use]
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    #[must_use]
+    pub fn eliminations(&self) -> &[Elimination] {
+        &self.eliminations
+    }
+}
+
+/// Average length of a deck's games, counting only the games where it was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GameLength {
+    pub turns: Option<f64>,
+    /// In minutes
+    pub duration: Option<f64>,
+}
+
+fn average(values: impl Iterator<Item = u32>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0u32), |(sum, count), value| {
+        (sum + f64::from(value), count + 1)
+    });
+    (count > 0).then(|| sum / f64::from(count))
+}
+
+impl Tournament {
+    /// Ensures that every pilot override is for a deck in the game and names a registered pilot,
+    /// and that the eliminations are between decks in the game that didn't win it
+    pub(crate) fn ensure_details(
+        &self,
+        details: &GameDetails,
+        players: &[u32],
+        outcome: &GameOutcome,
+    ) -> Result<(), TournamentError> {
+        for (deck, pilot) in &details.pilots {
+            if !players.contains(deck) {
+                return Err(TournamentError::PlayerNotInMatch(*deck));
+            }
+            self.ensure_pilot(*pilot)?;
+        }
+        Self::ensure_eliminations(&details.eliminations, players, outcome)
+    }
+
+    pub fn get_average_game_length(&self, id: u32) -> Result<GameLength, TournamentError> {
+        let games = self.get_player_games(id)?.collect::<Vec<_>>();
+        Ok(GameLength {
+            turns: average(games.iter().filter_map(|game| game.details().turns())),
+            duration: average(games.iter().filter_map(|game| game.details().duration())),
+        })
+    }
+
+    /// Number of games the deck won (or shared the win of) by each win condition
+    pub fn get_win_conditions(
+        &self,
+        id: u32,
+    ) -> Result<HashMap<WinCondition, u32>, TournamentError> {
+        let mut counts = HashMap::new();
+        for game in self.get_player_games(id)? {
+            if game.outcome().is_credited(id)
+                && let Some(condition) = game.details().win_condition()
+            {
+                *counts.entry(condition).or_insert(0) += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Every session that has been recorded, ordered by their first game
+    #[must_use]
+    pub fn sessions(&self) -> Vec<&str> {
+        let mut sessions = Vec::new();
+        for session in self
+            .games
+            .iter()
+            .filter_map(|game| game.details().session())
+        {
+            if !sessions.contains(&session) {
+                sessions.push(session);
+            }
+        }
+        sessions
+    }
+
+    pub fn get_session_games<'a>(
+        &'a self,
+        session: &'a str,
+    ) -> impl Iterator<Item = &'a GameRecord> {
+        self.games
+            .iter()
+            .filter(move |game| game.details().session() == Some(session))
+    }
+
+    /// Games played within a range of unix timestamps, in seconds. Games without a timestamp are
+    /// left out.
+    pub fn get_games_between<R>(&self, range: R) -> impl Iterator<Item = &GameRecord>
+    where
+        R: RangeBounds<u64>,
+    {
+        self.games.iter().filter(move |game| {
+            game.details()
+                .played_at()
+                .is_some_and(|played_at| range.contains(&played_at))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        game::{
+            details::{GameDetails, WinCondition},
+            outcome::GameOutcome,
+        },
+    };
+
+    fn tournament_with_details(details: &[GameDetails]) -> Tournament {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for details in details {
+            let record = tourn
+                .create_match(&ids)
+                .unwrap()
+                .record_outcome(GameOutcome::NoResult)
+                .unwrap()
+                .with_details(details.clone());
+            tourn.register_record(record).unwrap();
+        }
+        tourn
+    }
+
+    fn details(played_at: Option<u64>, session: Option<&str>) -> GameDetails {
+        GameDetails {
+            played_at,
+            session: session.map(ToOwned::to_owned),
+            ..GameDetails::default()
+        }
+    }
+
+    #[test]
+    fn sessions_in_order_of_first_game() {
+        let tourn = tournament_with_details(&[
+            details(None, Some("b")),
+            details(None, None),
+            details(None, Some("a")),
+            details(None, Some("b")),
+        ]);
+        assert_eq!(vec!["b", "a"], tourn.sessions());
+        assert_eq!(2, tourn.get_session_games("b").count());
+        assert_eq!(1, tourn.get_session_games("a").count());
+        assert_eq!(0, tourn.get_session_games("c").count());
+    }
+
+    #[test]
+    fn game_length_averages_recorded_games() {
+        let tourn = tournament_with_details(&[
+            GameDetails {
+                turns: Some(8),
+                duration: Some(60),
+                ..GameDetails::default()
+            },
+            GameDetails {
+                turns: Some(10),
+                ..GameDetails::default()
+            },
+            GameDetails::default(),
+        ]);
+        let id = *tourn.players().keys().next().unwrap();
+        let length = tourn.get_average_game_length(id).unwrap();
+        assert_eq!(Some(9.0), length.turns);
+        assert_eq!(Some(60.0), length.duration);
+    }
+
+    #[test]
+    fn win_conditions_count_credited_games() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for (winner, condition) in [
+            (ids[0], WinCondition::Combo),
+            (ids[0], WinCondition::Combo),
+            (ids[0], WinCondition::Combat),
+            (ids[1], WinCondition::Combo),
+        ] {
+            let record = tourn
+                .create_match(&ids)
+                .unwrap()
+                .record(winner)
+                .unwrap()
+                .with_details(GameDetails {
+                    win_condition: Some(condition),
+                    ..GameDetails::default()
+                });
+            tourn.register_record(record).unwrap();
+        }
+
+        let conditions = tourn.get_win_conditions(ids[0]).unwrap();
+        assert_eq!(Some(&2), conditions.get(&WinCondition::Combo));
+        assert_eq!(Some(&1), conditions.get(&WinCondition::Combat));
+        assert_eq!(None, conditions.get(&WinCondition::AltWin));
+    }
+
+    #[test]
+    fn games_between_skips_undated_games() {
+        let tourn = tournament_with_details(&[
+            details(Some(100), None),
+            details(None, None),
+            details(Some(200), None),
+            details(Some(300), None),
+        ]);
+        assert_eq!(2, tourn.get_games_between(100..300).count());
+        assert_eq!(3, tourn.get_games_between(..).count());
+        assert_eq!(1, tourn.get_games_between(250..).count());
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use crate::{
+    Tournament, error::TournamentError, game::outcome::GameOutcome, serialization::bare_option,
+};
+
+/// What knocked a deck out of a game
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash)]
+pub enum Eliminator {
+    /// Another deck at the table
+    #[serde(rename = "d", alias = "Deck")]
+    Deck(u32),
+    /// The deck lost to its own cards, such as by decking or paying too much life
+    #[serde(rename = "s", alias = "SelfInflicted")]
+    SelfInflicted,
+    /// The whole table, such as from a board wipe or a symmetrical effect
+    #[serde(rename = "t", alias = "Table")]
+    Table,
+}
+
+/// A deck being knocked out of a game
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+pub struct Elimination {
+    #[serde(rename = "e", alias = "eliminated")]
+    pub eliminated: u32,
+    #[serde(rename = "b", alias = "by")]
+    pub by: Eliminator,
+    #[serde(
+        rename = "t",
+        alias = "turn",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    pub turn: Option<u32>,
+}
+
+impl Elimination {
+    #[must_use]
+    pub const fn new(eliminated: u32, by: Eliminator) -> Self {
+        Self {
+            eliminated,
+            by,
+            turn: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_turn(mut self, turn: u32) -> Self {
+        self.turn = Some(turn);
+        self
+    }
+
+    /// The deck that did the eliminating, if it was another deck
+    #[must_use]
+    pub const fn killer(&self) -> Option<u32> {
+        match self.by {
+            Eliminator::Deck(id) => Some(id),
+            Eliminator::SelfInflicted | Eliminator::Table => None,
+        }
+    }
+
+    /// Replaces the deck ids of the elimination using a map of old to new ids
+    pub(crate) fn map_ids(self, ids: &HashMap<u32, u32>) -> Result<Self, TournamentError> {
+        let map_id = |id: u32| {
+            ids.get(&id)
+                .copied()
+                .ok_or(TournamentError::InvalidPlayerId(id))
+        };
+        Ok(Self {
+            eliminated: map_id(self.eliminated)?,
+            by: match self.by {
+                Eliminator::Deck(id) => Eliminator::Deck(map_id(id)?),
+                by => by,
+            },
+            turn: self.turn,
+        })
+    }
+}
+
+/// Eliminations a deck has been part of, across all of its games
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KillStats {
+    /// Decks this deck knocked out
+    pub kills: u32,
+    /// Times this deck was knocked out by another deck
+    pub deaths: u32,
+    pub self_eliminations: u32,
+    pub table_eliminations: u32,
+}
+
+/// Kills between two decks, from the point of view of one of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HeadToHead {
+    /// Times the deck knocked out the opponent
+    pub kills: u32,
+    /// Times the opponent knocked out the deck
+    pub deaths: u32,
+}
+
+impl Tournament {
+    /// Ensures that every eliminated deck is in the game, only eliminated once and not credited
+    /// with the outcome, and that every eliminating deck is another deck in the game
+    pub(crate) fn ensure_eliminations(
+        eliminations: &[Elimination],
+        players: &[u32],
+        outcome: &GameOutcome,
+    ) -> Result<(), TournamentError> {
+        for (i, elimination) in eliminations.iter().enumerate() {
+            let seated = players
+                .iter()
+                .filter(|id| **id == elimination.eliminated)
+                .count();
+            if seated == 0 {
+                return Err(TournamentError::PlayerNotInMatch(elimination.eliminated));
+            }
+            // A deck seated more than once, as in a mirror match, can lose one seat and win another
+            let credited = outcome
+                .credited()
+                .iter()
+                .filter(|id| **id == elimination.eliminated)
+                .count();
+            if credited >= seated {
+                return Err(TournamentError::InvalidElimination(elimination.eliminated));
+            }
+            if eliminations
+                .iter()
+                .take(i)
+                .any(|other| other.eliminated == elimination.eliminated)
+            {
+                return Err(TournamentError::InvalidElimination(elimination.eliminated));
+            }
+            if let Some(killer) = elimination.killer() {
+                if !players.contains(&killer) {
+                    return Err(TournamentError::PlayerNotInMatch(killer));
+                }
+                if killer == elimination.eliminated {
+                    return Err(TournamentError::InvalidElimination(killer));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_kill_stats(&self, id: u32) -> Result<KillStats, TournamentError> {
+        let mut stats = KillStats::default();
+        for game in self.get_player_games(id)? {
+            for elimination in &game.details().eliminations {
+                match elimination.by {
+                    Eliminator::Deck(killer) if killer == id => stats.kills += 1,
+                    _ if elimination.eliminated != id => {}
+                    Eliminator::Deck(_) => stats.deaths += 1,
+                    Eliminator::SelfInflicted => stats.self_eliminations += 1,
+                    Eliminator::Table => stats.table_eliminations += 1,
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Kills between a deck and every opponent it has knocked out or been knocked out by
+    pub fn get_head_to_head_kills(
+        &self,
+        id: u32,
+    ) -> Result<HashMap<u32, HeadToHead>, TournamentError> {
+        let mut records: HashMap<u32, HeadToHead> = HashMap::new();
+        for game in self.get_player_games(id)? {
+            for elimination in &game.details().eliminations {
+                let Some(killer) = elimination.killer() else {
+                    continue;
+                };
+                if killer == id {
+                    records.entry(elimination.eliminated).or_default().kills += 1;
+                } else if elimination.eliminated == id {
+                    records.entry(killer).or_default().deaths += 1;
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        error::TournamentError,
+        game::{
+            elimination::{Elimination, Eliminator, HeadToHead},
+            entry::GameEntry,
+            outcome::GameOutcome,
+        },
+    };
+
+    fn entry(ids: &[u32], winner: u32, eliminations: Vec<Elimination>) -> GameEntry {
+        let mut entry = GameEntry::new(ids.to_vec(), GameOutcome::Win(winner)).unwrap();
+        entry.details_mut().eliminations = eliminations;
+        entry
+    }
+
+    #[test]
+    fn kill_stats_count_each_side() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let kill = |eliminated, killer| Elimination::new(eliminated, Eliminator::Deck(killer));
+        tourn
+            .register_entry(entry(
+                &ids,
+                ids[0],
+                vec![
+                    kill(ids[1], ids[0]),
+                    Elimination::new(ids[2], Eliminator::SelfInflicted).with_turn(6),
+                    kill(ids[3], ids[0]),
+                ],
+            ))
+            .unwrap();
+        tourn
+            .register_entry(entry(&ids, ids[1], vec![kill(ids[0], ids[1])]))
+            .unwrap();
+
+        let stats = tourn.get_kill_stats(ids[0]).unwrap();
+        assert_eq!((2, 1), (stats.kills, stats.deaths));
+        assert_eq!(1, tourn.get_kill_stats(ids[2]).unwrap().self_eliminations);
+
+        let records = tourn.get_head_to_head_kills(ids[0]).unwrap();
+        assert_eq!(
+            Some(&HeadToHead {
+                kills: 1,
+                deaths: 1
+            }),
+            records.get(&ids[1])
+        );
+        assert_eq!(None, records.get(&ids[2]));
+    }
+
+    #[test]
+    fn eliminations_must_be_valid() {
+        let mut tourn = Tournament::generate_tournament(5, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let pod = &ids[..4];
+
+        for eliminations in [
+            vec![Elimination::new(ids[4], Eliminator::Table)],
+            vec![Elimination::new(ids[1], Eliminator::Deck(ids[4]))],
+            vec![Elimination::new(ids[1], Eliminator::Deck(ids[1]))],
+            vec![
+                Elimination::new(ids[1], Eliminator::Table),
+                Elimination::new(ids[1], Eliminator::Deck(ids[0])),
+            ],
+            vec![Elimination::new(ids[0], Eliminator::Deck(ids[1]))],
+            pod.iter()
+                .map(|id| Elimination::new(*id, Eliminator::Table))
+                .collect(),
+        ] {
+            tourn
+                .register_entry(entry(pod, ids[0], eliminations))
+                .unwrap_err();
+        }
+        assert!(matches!(
+            tourn.register_entry(entry(pod, ids[0], vec![Elimination::new(ids[0], Eliminator::Table)])),
+            Err(TournamentError::InvalidElimination(id)) if id == ids[0]
+        ));
+        assert!(tourn.games().is_empty());
+
+        // The other seat of a mirror match can be knocked out by the seat that won
+        let mirror = [ids[0], ids[0], ids[1], ids[2]];
+        tourn
+            .register_entry(entry(
+                &mirror,
+                ids[0],
+                vec![Elimination::new(ids[0], Eliminator::Deck(ids[1]))],
+            ))
+            .unwrap();
+    }
+}
@@ -1,89 +1,342 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::{Tournament, error::TournamentError, game::record::GameRecord};
+use crate::{
+    Tournament,
+    error::TournamentError,
+    game::{details::GameDetails, ensure_pod_size, outcome::GameOutcome, record::GameRecord},
+    serialization::SerdeGameEntry,
+};
 
-/// Stores only the player IDs and the winner ID. Primarily used for serialization or conversions
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq, Copy, Eq)]
+/// Stores only the player IDs and the outcome. Primarily used for serialization or conversions
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq, Eq)]
+#[serde(try_from = "SerdeGameEntry", into = "SerdeGameEntry")]
 pub struct GameEntry {
-    #[serde(rename = "p", alias = "players")]
-    players: [u32; 4],
-    #[serde(rename = "w", alias = "winner")]
-    winner: u32,
+    players: Vec<u32>,
+    outcome: GameOutcome,
+    /// Seat of the player that went first, if the turn order is known
+    first: Option<usize>,
+    /// Finishing order, best to worst. May be partial or empty.
+    placements: Vec<u32>,
+    details: GameDetails,
 }
 
 impl GameEntry {
-    pub fn new(players: [u32; 4], winner: u32) -> Result<Self, TournamentError> {
-        if !players.contains(&winner) {
-            return Err(TournamentError::PlayerNotInMatch(winner));
+    pub fn new(players: Vec<u32>, outcome: GameOutcome) -> Result<Self, TournamentError> {
+        ensure_pod_size(players.len())?;
+        outcome.validate(&players)?;
+        Ok(Self::new_unchecked(players, outcome))
+    }
+
+    #[must_use]
+    pub(crate) const fn new_unchecked(players: Vec<u32>, outcome: GameOutcome) -> Self {
+        Self {
+            players,
+            outcome,
+            first: None,
+            placements: Vec::new(),
+            details: GameDetails {
+                played_at: None,
+                session: None,
+                pilots: BTreeMap::new(),
+                turns: None,
+                duration: None,
+                win_condition: None,
+                notes: String::new(),
+                eliminations: Vec::new(),
+            },
         }
-        Ok(Self::new_unchecked(players, winner))
+    }
+
+    pub fn with_placements(self, placements: Vec<u32>) -> Result<Self, TournamentError> {
+        self.outcome
+            .validate_placements(&self.players, &placements)?;
+        Ok(self.with_placements_unchecked(placements))
+    }
+
+    #[must_use]
+    pub fn with_details(mut self, details: GameDetails) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Records the turn order. The seats take turns in order, starting from seat `first`.
+    pub fn with_first(self, first: usize) -> Result<Self, TournamentError> {
+        if first >= self.players.len() {
+            return Err(TournamentError::InvalidSeat(first));
+        }
+        Ok(self.with_first_unchecked(Some(first)))
+    }
+
+    #[must_use]
+    pub(crate) const fn with_first_unchecked(mut self, first: Option<usize>) -> Self {
+        self.first = first;
+        self
+    }
+
+    #[must_use]
+    pub const fn first(&self) -> Option<usize> {
+        self.first
     }
 
     #[must_use]
-    pub(crate) const fn new_unchecked(players: [u32; 4], winner: u32) -> Self {
-        Self { players, winner }
+    pub(crate) fn with_placements_unchecked(mut self, placements: Vec<u32>) -> Self {
+        self.placements = placements;
+        self
     }
 
     #[must_use]
-    pub const fn players(&self) -> &[u32; 4] {
+    pub fn players(&self) -> &[u32] {
         &self.players
     }
 
     #[must_use]
-    pub const fn winner(&self) -> u32 {
-        self.winner
+    pub const fn outcome(&self) -> &GameOutcome {
+        &self.outcome
+    }
+
+    #[must_use]
+    pub const fn winner(&self) -> Option<u32> {
+        self.outcome.winner()
+    }
+
+    #[must_use]
+    pub fn placements(&self) -> &[u32] {
+        &self.placements
+    }
+
+    #[must_use]
+    pub const fn details(&self) -> &GameDetails {
+        &self.details
+    }
+
+    pub const fn details_mut(&mut self) -> &mut GameDetails {
+        &mut self.details
     }
 
     pub fn map_ids(&self, ids: &HashMap<u32, u32>) -> Result<Self, TournamentError> {
-        let [a, b, c, d] = self.players;
-        let a = ids.get(&a).ok_or(TournamentError::InvalidPlayerId(a))?;
-        let b = ids.get(&b).ok_or(TournamentError::InvalidPlayerId(b))?;
-        let c = ids.get(&c).ok_or(TournamentError::InvalidPlayerId(c))?;
-        let d = ids.get(&d).ok_or(TournamentError::InvalidPlayerId(d))?;
-        let winner = ids
-            .get(&self.winner)
-            .ok_or(TournamentError::InvalidPlayerId(self.winner))?;
+        let map_id = |id: &u32| {
+            ids.get(id)
+                .copied()
+                .ok_or(TournamentError::InvalidPlayerId(*id))
+        };
+        let map = |list: &[u32]| list.iter().map(map_id).collect::<Result<Vec<_>, _>>();
+        let players = map(&self.players)?;
+        let outcome = self.outcome.map_ids(ids)?;
+
+        let mut details = self.details.clone();
+        details.pilots = self
+            .details
+            .pilots
+            .iter()
+            .map(|(deck, pilot)| Ok((map_id(deck)?, *pilot)))
+            .collect::<Result<_, TournamentError>>()?;
+        details.eliminations = self
+            .details
+            .eliminations
+            .iter()
+            .map(|elimination| elimination.map_ids(ids))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(players, outcome)?
+            .with_first_unchecked(self.first)
+            .with_placements(map(&self.placements)?)?
+            .with_details(details))
+    }
 
-        Self::new([*a, *b, *c, *d], *winner)
+    /// Replaces the pilots of the game using a map of old to new pilot ids
+    pub fn map_pilots(mut self, pilots: &HashMap<u32, u32>) -> Result<Self, TournamentError> {
+        for pilot in self.details.pilots.values_mut() {
+            *pilot = pilots
+                .get(pilot)
+                .copied()
+                .ok_or(TournamentError::InvalidPilotId(*pilot))?;
+        }
+        Ok(self)
     }
 }
 
 impl Tournament {
     pub fn create_entry_record(&self, entry: GameEntry) -> Result<GameRecord, TournamentError> {
-        self.create_match(entry.players)?.record(entry.winner)
+        let GameEntry {
+            players,
+            outcome,
+            first,
+            placements,
+            details,
+        } = entry;
+        self.ensure_details(&details, &players, &outcome)?;
+        Ok(self
+            .create_seated_match(&players, first)?
+            .record_placements(outcome, placements)?
+            .with_details(details))
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::game::{
+        details::WinCondition,
+        elimination::{Elimination, Eliminator},
+    };
 
     #[test]
     fn winner_must_be_player() {
-        GameEntry::new([0, 1, 2, 3], 0).unwrap();
-        GameEntry::new([0, 1, 2, 3], 1).unwrap();
-        GameEntry::new([0, 1, 2, 3], 2).unwrap();
-        GameEntry::new([0, 1, 2, 3], 3).unwrap();
-        GameEntry::new([0, 1, 2, 3], 4).unwrap_err();
+        GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Win(0)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Win(1)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Win(2)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Win(3)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3], GameOutcome::Win(4)).unwrap_err();
+    }
+
+    #[test]
+    fn pod_size_must_be_valid() {
+        GameEntry::new(vec![0, 1], GameOutcome::Win(0)).unwrap_err();
+        GameEntry::new(vec![0, 1, 2], GameOutcome::Win(0)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3, 4], GameOutcome::Win(0)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3, 4, 5], GameOutcome::Win(0)).unwrap();
+        GameEntry::new(vec![0, 1, 2, 3, 4, 5, 6], GameOutcome::Win(0)).unwrap_err();
     }
 
     #[test]
     fn maps_to_correct_ids() {
-        let starting = [1, 2, 3, 4];
-        let ending = [5, 6, 7, 8];
+        let starting = vec![1, 2, 3, 4];
+        let ending = vec![5, 6, 7, 8];
         let map = [(1, 5), (2, 6), (3, 7), (4, 8)]
             .into_iter()
             .collect::<HashMap<_, _>>();
 
-        let entry = GameEntry::new(starting, 1).unwrap();
+        let entry = GameEntry::new(starting, GameOutcome::Win(1)).unwrap();
         let mapped_entry = entry.map_ids(&map).unwrap();
         assert_eq!(ending, mapped_entry.players);
-        assert_eq!(5, mapped_entry.winner);
+        assert_eq!(Some(5), mapped_entry.winner());
+
+        let entry = GameEntry::new(vec![1, 2, 3, 4], GameOutcome::Draw(vec![2, 4])).unwrap();
+        let mapped_entry = entry.map_ids(&map).unwrap();
+        assert_eq!(&GameOutcome::Draw(vec![6, 8]), mapped_entry.outcome());
     }
 
     #[test]
     fn map_fails_invalid_id() {
-        let entry = GameEntry::new([1, 2, 3, 4], 1).unwrap();
+        let entry = GameEntry::new(vec![1, 2, 3, 4], GameOutcome::Win(1)).unwrap();
         entry.map_ids(&HashMap::new()).unwrap_err();
     }
+
+    #[test]
+    fn deserializes_legacy_tuple_players() {
+        let entry: GameEntry = ron::from_str("(p:(0,1,2,3),w:2)").unwrap();
+        assert_eq!(&[0, 1, 2, 3], entry.players());
+
+        let entry: GameEntry = ron::from_str("(p:[0,1,2],w:2)").unwrap();
+        assert_eq!(&[0, 1, 2], entry.players());
+
+        let entry: GameEntry = ron::from_str("(players:(0,1,2,3),winner:1)").unwrap();
+        assert_eq!(Some(1), entry.winner());
+    }
+
+    #[test]
+    fn serializes_outcomes_with_short_keys() {
+        let cases = [
+            (GameOutcome::Win(2), "(p:[0,1,2],w:2)"),
+            (GameOutcome::Draw(vec![0, 2]), "(p:[0,1,2],d:[0,2])"),
+            (GameOutcome::NoResult, "(p:[0,1,2])"),
+        ];
+        for (outcome, expected) in cases {
+            let entry = GameEntry::new(vec![0, 1, 2], outcome).unwrap();
+            let serialized = ron::to_string(&entry).unwrap();
+            assert_eq!(expected, serialized);
+            assert_eq!(entry, ron::from_str(&serialized).unwrap());
+        }
+    }
+
+    #[test]
+    fn serializes_placements() {
+        let entry = GameEntry::new(vec![0, 1, 2], GameOutcome::Win(2))
+            .unwrap()
+            .with_placements(vec![2, 0, 1])
+            .unwrap();
+        let serialized = ron::to_string(&entry).unwrap();
+        assert_eq!("(p:[0,1,2],w:2,r:[2,0,1])", serialized);
+        assert_eq!(entry, ron::from_str(&serialized).unwrap());
+
+        ron::from_str::<GameEntry>("(p:[0,1,2],w:2,r:[0,2])").unwrap_err();
+    }
+
+    #[test]
+    fn serializes_details() {
+        let entry = GameEntry::new(vec![0, 1, 2], GameOutcome::Win(2))
+            .unwrap()
+            .with_details(GameDetails {
+                played_at: Some(1_700_000_000),
+                session: Some("Friday".to_owned()),
+                ..GameDetails::default()
+            });
+        let serialized = ron::to_string(&entry).unwrap();
+        assert_eq!("(p:[0,1,2],w:2,t:1700000000,s:\"Friday\")", serialized);
+        assert_eq!(entry, ron::from_str(&serialized).unwrap());
+    }
+
+    #[test]
+    fn serializes_first_seat() {
+        let entry = GameEntry::new(vec![0, 1, 2], GameOutcome::Win(2))
+            .unwrap()
+            .with_first(1)
+            .unwrap();
+        let serialized = ron::to_string(&entry).unwrap();
+        assert_eq!("(p:[0,1,2],w:2,f:1)", serialized);
+        assert_eq!(entry, ron::from_str(&serialized).unwrap());
+
+        ron::from_str::<GameEntry>("(p:[0,1,2],w:2,f:3)").unwrap_err();
+    }
+
+    #[test]
+    fn serializes_metadata() {
+        let entry = GameEntry::new(vec![0, 1, 2], GameOutcome::Win(2))
+            .unwrap()
+            .with_details(GameDetails {
+                turns: Some(9),
+                duration: Some(75),
+                win_condition: Some(WinCondition::CommanderDamage),
+                notes: "Voltron".to_owned(),
+                ..GameDetails::default()
+            });
+        let serialized = ron::to_string(&entry).unwrap();
+        assert_eq!(
+            "(p:[0,1,2],w:2,tu:9,du:75,wc:cd,no:\"Voltron\")",
+            serialized
+        );
+        assert_eq!(entry, ron::from_str(&serialized).unwrap());
+    }
+
+    #[test]
+    fn serializes_eliminations() {
+        let entry = GameEntry::new(vec![0, 1, 2], GameOutcome::Win(2))
+            .unwrap()
+            .with_details(GameDetails {
+                eliminations: vec![
+                    Elimination::new(0, Eliminator::Deck(2)).with_turn(7),
+                    Elimination::new(1, Eliminator::SelfInflicted),
+                ],
+                ..GameDetails::default()
+            });
+        let serialized = ron::to_string(&entry).unwrap();
+        assert_eq!(
+            "(p:[0,1,2],w:2,el:[(e:0,b:d(2),t:7),(e:1,b:s)])",
+            serialized
+        );
+        assert_eq!(entry, ron::from_str(&serialized).unwrap());
+
+        let map = [(0, 5), (1, 6), (2, 7)].into_iter().collect();
+        let mapped = entry.map_ids(&map).unwrap();
+        assert_eq!(
+            Elimination::new(5, Eliminator::Deck(7)).with_turn(7),
+            mapped.details().eliminations().first().copied().unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_rejects_winner_and_draw() {
+        ron::from_str::<GameEntry>("(p:[0,1,2],w:0,d:[1,2])").unwrap_err();
+        ron::from_str::<GameEntry>("(p:[0,1,2],w:4)").unwrap_err();
+    }
 }
@@ -50,4 +50,17 @@ impl MatchPlayer {
     pub const fn elo_loss(&self) -> &f64 {
         &self.elo_loss
     }
+
+    /// Elo gained (or lost, if negative) for a seat score, where 1 is an outright win and 0 is a
+    /// loss with nothing to show for it. Scores in between interpolate between the two.
+    #[must_use]
+    pub fn elo_change(&self, score: f64) -> f64 {
+        if score >= 1.0 {
+            self.elo_win
+        } else if score <= 0.0 {
+            -self.elo_loss
+        } else {
+            score.mul_add(self.elo_win + self.elo_loss, -self.elo_loss)
+        }
+    }
 }
@@ -1,20 +1,53 @@
+use itertools::izip;
+
 use crate::{
     Tournament,
     error::TournamentError,
-    game::{match_player::MatchPlayer, record::GameRecord},
-    player::stats::PlayerStats,
+    game::{ensure_pod_size, match_player::MatchPlayer, outcome::GameOutcome, record::GameRecord},
 };
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct Matchup {
-    players: [MatchPlayer; 4],
+    players: Vec<MatchPlayer>,
     version: usize,
+    #[serde(default)]
+    placement_weight: f64,
+    /// Seat of the player that went first. The seats take turns in order from there.
+    #[serde(default)]
+    first: Option<usize>,
 }
 
 impl Matchup {
     #[must_use]
-    pub(crate) const fn new(players: [MatchPlayer; 4], version: usize) -> Self {
-        Self { players, version }
+    pub(crate) const fn new(
+        players: Vec<MatchPlayer>,
+        version: usize,
+        placement_weight: f64,
+        first: Option<usize>,
+    ) -> Self {
+        Self {
+            players,
+            version,
+            placement_weight,
+            first,
+        }
+    }
+
+    #[must_use]
+    pub const fn first(&self) -> Option<usize> {
+        self.first
+    }
+
+    /// Turn order position of a seat, 0 for the player that went first. Returns `None` when the
+    /// turn order is unknown.
+    #[must_use]
+    pub const fn turn_position(&self, seat: usize) -> Option<usize> {
+        match self.first {
+            Some(first) if seat < self.players.len() => {
+                Some((seat + self.players.len() - first) % self.players.len())
+            }
+            _ => None,
+        }
     }
 
     #[must_use]
@@ -23,34 +56,37 @@ impl Matchup {
     }
 
     #[must_use]
-    pub const fn players(&self) -> &[MatchPlayer; 4] {
+    pub fn players(&self) -> &[MatchPlayer] {
         &self.players
     }
 
+    /// Weight of the finishing order in games recorded with placements
     #[must_use]
-    pub const fn ids(&self) -> [u32; 4] {
-        let [player_a, player_b, player_c, player_d] = &self.players;
-        [player_a.id(), player_b.id(), player_c.id(), player_d.id()]
+    pub const fn placement_weight(&self) -> f64 {
+        self.placement_weight
+    }
+
+    #[must_use]
+    pub fn ids(&self) -> Vec<u32> {
+        self.players.iter().map(MatchPlayer::id).collect()
     }
 
     pub fn record(self, winner: u32) -> Result<GameRecord, TournamentError> {
-        GameRecord::new(self, winner)
+        self.record_outcome(GameOutcome::Win(winner))
     }
-}
 
-struct TempMatchPlayer<'a> {
-    id: u32,
-    stats: &'a PlayerStats,
-    scaled_elo: f64,
-    scaled_wr: f64,
-}
+    pub fn record_outcome(self, outcome: GameOutcome) -> Result<GameRecord, TournamentError> {
+        GameRecord::new(self, outcome)
+    }
 
-impl TempMatchPlayer<'_> {
-    fn into_match_player(self, game_points: f64, coef_elo: f64, coef_wr: f64) -> MatchPlayer {
-        let expected = coef_wr.mul_add(self.scaled_wr, coef_elo * self.scaled_elo);
-        let elo_win = game_points * (1.0 - expected) / 0.75;
-        let elo_loss = game_points * expected / 0.75;
-        MatchPlayer::new(self.id, self.stats.clone(), expected, elo_win, elo_loss)
+    /// Records the outcome along with the finishing order, best to worst. See
+    /// [`GameRecord::with_placements`].
+    pub fn record_placements(
+        self,
+        outcome: GameOutcome,
+        placements: Vec<u32>,
+    ) -> Result<GameRecord, TournamentError> {
+        GameRecord::new(self, outcome)?.with_placements(placements)
     }
 }
 
@@ -59,70 +95,139 @@ impl Tournament {
         if matchup.version() == self.snapshot {
             return Ok(matchup);
         }
-        self.create_match(matchup.ids())
-    }
-
-    fn create_temp_match_player(&self, id: u32) -> TempMatchPlayer<'_> {
-        let stats = self.get_player_or_default_stats(id);
-        TempMatchPlayer {
-            scaled_wr: stats
-                .wr()
-                .unwrap_or(0.25)
-                .powf(self.config.game_wr_pow_scale),
-            scaled_elo: stats.elo().powf(self.config.game_elo_pow_scale),
-            stats,
-            id,
-        }
+        self.create_seated_match(&matchup.ids(), matchup.first())
     }
 
-    pub fn create_match(&self, ids: [u32; 4]) -> Result<Matchup, TournamentError> {
+    /// Creates a matchup with an unknown turn order
+    pub fn create_match(&self, ids: &[u32]) -> Result<Matchup, TournamentError> {
+        self.create_seated_match(ids, None)
+    }
+
+    /// Creates a matchup where the seats take turns in order, starting from seat `first`. The
+    /// expected results and rating changes come from the configured rating system.
+    pub fn create_seated_match(
+        &self,
+        ids: &[u32],
+        first: Option<usize>,
+    ) -> Result<Matchup, TournamentError> {
+        ensure_pod_size(ids.len())?;
+        if let Some(first) = first
+            && first >= ids.len()
+        {
+            return Err(TournamentError::InvalidSeat(first));
+        }
+
         // First check registration
-        for id in &ids {
+        for id in ids {
             if !self.is_id_registered(id) {
                 return Err(TournamentError::InvalidPlayerId(*id));
             }
         }
 
-        let players = ids.map(|id| self.create_temp_match_player(id));
-
-        let sum_elo = players.iter().map(|player| player.scaled_elo).sum::<f64>();
-        let sum_wr = players.iter().map(|player| player.scaled_wr).sum::<f64>();
-
-        let weight_total = self.config.game_wr_weight + self.config.game_elo_weight;
-        let weight_wr = self.config.game_wr_weight / weight_total;
-        let weight_elo = self.config.game_elo_weight / weight_total;
-
-        let coef_wr = weight_wr / sum_wr;
-        let coef_elo = weight_elo / sum_elo;
-
-        let match_players = players
-            .map(|player| player.into_match_player(self.config.game_points, coef_elo, coef_wr));
+        let system = self.config.rating();
+        let pod = ids
+            .iter()
+            .map(|id| self.get_player_or_default_stats(*id))
+            .collect::<Vec<_>>();
+        let mut expected = system.expected(&pod, &self.config);
+
+        if let Some(first) = first
+            && let Some(factors) = self.seat_factors(ids.len())
+        {
+            for (seat, value) in expected.iter_mut().enumerate() {
+                let position = (seat + ids.len() - first) % ids.len();
+                *value *= factors.get(position).copied().unwrap_or(1.0);
+            }
+            let total = expected.iter().sum::<f64>();
+            for value in &mut expected {
+                *value /= total;
+            }
+        }
 
-        Ok(Matchup::new(match_players, self.snapshot))
+        let deltas = system.deltas(&pod, &expected, &self.config);
+        let match_players = izip!(ids, pod, expected, deltas)
+            .map(|(id, stats, expected, delta)| {
+                MatchPlayer::new(*id, stats.clone(), expected, delta.win, delta.loss)
+            })
+            .collect();
+
+        Ok(Matchup::new(
+            match_players,
+            self.snapshot,
+            self.config.game_placement_weight.clamp(0.0, 1.0),
+            first,
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::cast_precision_loss, clippy::indexing_slicing)]
+
+    use itertools::Itertools;
 
     use super::*;
 
     #[test]
     fn create_match_invalid_ids() {
         let tourn = Tournament::new();
-        tourn.create_match([1, 2, 3, 4]).unwrap_err();
+        tourn.create_match(&[1, 2, 3, 4]).unwrap_err();
+    }
+
+    #[test]
+    fn create_match_invalid_pod_size() {
+        let mut tourn = Tournament::new();
+        let id = tourn.register_player("A".to_owned()).unwrap();
+        for size in [0, 1, 2, 7] {
+            let ids = vec![id; size];
+            assert!(matches!(
+                tourn.create_match(&ids),
+                Err(TournamentError::InvalidPodSize(s)) if s == size
+            ));
+        }
+    }
+
+    #[test]
+    fn expected_sums_to_one_for_every_pod_size() {
+        let tourn = Tournament::generate_tournament(8, 24).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for size in 3..=6 {
+            let mu = tourn.create_match(&ids[..size]).unwrap();
+            assert_eq!(size, mu.players().len());
+            let total = mu.players().iter().map(MatchPlayer::expected).sum::<f64>();
+            assert_relative_eq!(1.0, total, epsilon = 1e-9);
+        }
     }
 
     #[test]
     fn mirror_matchup_equal_expected() {
         let mut tourn = Tournament::new();
         let id = tourn.register_player("A".to_owned()).unwrap();
-        let mu = tourn.create_match([id, id, id, id]).unwrap();
+        let mu = tourn.create_match(&[id, id, id, id]).unwrap();
         for p in mu.players() {
             assert_relative_eq!(0.25, *p.expected());
         }
     }
 
+    #[test]
+    fn mirror_matchup_zero_sum_for_any_pod_size() {
+        let mut tourn = Tournament::new();
+        let id = tourn.register_player("A".to_owned()).unwrap();
+        for size in 3..=6 {
+            let mu = tourn.create_match(&vec![id; size]).unwrap();
+            for p in mu.players() {
+                assert_relative_eq!(1.0 / size as f64, *p.expected(), epsilon = 1e-9);
+                assert_relative_eq!(*p.elo_win(), tourn.config().game_points, epsilon = 1e-9);
+                // One win must pay for every loss in the pod
+                assert_relative_eq!(
+                    *p.elo_win(),
+                    *p.elo_loss() * (size - 1) as f64,
+                    epsilon = 1e-9
+                );
+            }
+        }
+    }
+
     #[test]
     fn record_winner_must_be_player() {
         let tournament = Tournament::generate_tournament(5, 0).unwrap();
@@ -134,7 +239,7 @@ mod tests {
         let player_e = ids.next().unwrap();
 
         let mu = tournament
-            .create_match([player_a, player_b, player_c, player_d])
+            .create_match(&[player_a, player_b, player_c, player_d])
             .unwrap();
         mu.clone().record(player_a).unwrap();
         mu.clone().record(player_b).unwrap();
@@ -0,0 +1,399 @@
+use core::slice;
+use std::collections::HashMap;
+
+use crate::error::TournamentError;
+
+/// How a game ended.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq, Default)]
+pub enum GameOutcome {
+    /// A single deck won the game
+    #[serde(rename = "w", alias = "Win")]
+    Win(u32),
+    /// The listed decks split the win, such as when the last decks standing die together
+    #[serde(rename = "d", alias = "Draw")]
+    Draw(Vec<u32>),
+    /// The game was abandoned or timed out. Nobody's rating changes.
+    #[default]
+    #[serde(rename = "n", alias = "NoResult")]
+    NoResult,
+}
+
+/// The result of a single seat in a game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeatResult {
+    Win,
+    /// The seat shared the win. Holds the share of the win awarded to the seat.
+    Draw(f64),
+    Loss,
+}
+
+impl SeatResult {
+    /// Share of the win that went to the seat
+    #[must_use]
+    pub const fn share(&self) -> f64 {
+        match self {
+            Self::Win => 1.0,
+            Self::Draw(share) => *share,
+            Self::Loss => 0.0,
+        }
+    }
+}
+
+impl From<u32> for GameOutcome {
+    fn from(value: u32) -> Self {
+        Self::Win(value)
+    }
+}
+
+impl GameOutcome {
+    #[must_use]
+    pub const fn winner(&self) -> Option<u32> {
+        match self {
+            Self::Win(id) => Some(*id),
+            Self::Draw(_) | Self::NoResult => None,
+        }
+    }
+
+    /// The players that were credited with (a share of) the win
+    #[must_use]
+    pub fn credited(&self) -> &[u32] {
+        match self {
+            Self::Win(id) => slice::from_ref(id),
+            Self::Draw(ids) => ids,
+            Self::NoResult => &[],
+        }
+    }
+
+    #[must_use]
+    pub fn is_credited(&self, id: u32) -> bool {
+        self.credited().contains(&id)
+    }
+
+    /// Ensures that every credited player is seated in the pod, and isn't credited more often
+    /// than it is seated
+    pub fn validate(&self, players: &[u32]) -> Result<(), TournamentError> {
+        if let Self::Draw(ids) = self
+            && ids.len() < 2
+        {
+            return Err(TournamentError::InvalidDraw);
+        }
+
+        let credited = self.credited();
+        for id in credited {
+            let listed = credited.iter().filter(|c| *c == id).count();
+            let seated = players.iter().filter(|p| *p == id).count();
+            if seated == 0 {
+                return Err(TournamentError::PlayerNotInMatch(*id));
+            }
+            // Each seat can only take one share of the draw, so the shares would add up to less
+            // than a whole win
+            if listed > seated {
+                return Err(TournamentError::InvalidDraw);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn map_ids(&self, ids: &HashMap<u32, u32>) -> Result<Self, TournamentError> {
+        let map = |id: &u32| {
+            ids.get(id)
+                .copied()
+                .ok_or(TournamentError::InvalidPlayerId(*id))
+        };
+        Ok(match self {
+            Self::Win(id) => Self::Win(map(id)?),
+            Self::Draw(draw) => Self::Draw(draw.iter().map(map).collect::<Result<_, _>>()?),
+            Self::NoResult => Self::NoResult,
+        })
+    }
+
+    /// Result of each seat of the pod, in the same order as `players`. Returns `None` when the
+    /// game has no result.
+    ///
+    /// A player seated more than once is only credited once per mention in the outcome, so a
+    /// mirror match still has a single winner.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn seat_results(&self, players: &[u32]) -> Option<Vec<SeatResult>> {
+        let credited = self.credited();
+        if credited.is_empty() {
+            return None;
+        }
+
+        let result = match self {
+            Self::Draw(_) => SeatResult::Draw(1.0 / credited.len() as f64),
+            _ => SeatResult::Win,
+        };
+
+        let mut results = vec![SeatResult::Loss; players.len()];
+        for id in credited {
+            let seat = players
+                .iter()
+                .zip(&results)
+                .position(|(player, seat)| player == id && *seat == SeatResult::Loss);
+            if let Some(seat) = seat.and_then(|seat| results.get_mut(seat)) {
+                *seat = result;
+            }
+        }
+
+        Some(results)
+    }
+
+    /// Ensures that a finishing order fits the pod. Every listed player must be seated, and
+    /// players credited by the outcome may only be listed ahead of everyone else.
+    pub fn validate_placements(
+        &self,
+        players: &[u32],
+        placements: &[u32],
+    ) -> Result<(), TournamentError> {
+        for (index, id) in placements.iter().enumerate() {
+            let listed = placements.iter().filter(|p| *p == id).count();
+            let seated = players.iter().filter(|p| *p == id).count();
+            if listed > seated {
+                return Err(TournamentError::InvalidPlacement(*id));
+            }
+
+            let credited_late = self.is_credited(*id)
+                && placements
+                    .iter()
+                    .take(index)
+                    .any(|placed| !self.is_credited(*placed));
+            if credited_late {
+                return Err(TournamentError::InvalidPlacement(*id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Seats grouped by finishing position, best first, with tied seats sharing a group. Returns
+    /// `None` when the game has no result.
+    #[must_use]
+    pub fn finish_order(&self, players: &[u32], placements: &[u32]) -> Option<Vec<Vec<usize>>> {
+        let results = self.seat_results(players)?;
+        Some(finish_groups(self, &results, players, placements))
+    }
+
+    /// Share of the win earned by each seat, in the same order as `players`. The shares sum to 1
+    /// over the pod. Returns `None` when the game has no result.
+    ///
+    /// Without placements, the winner (or each drawing player) takes the whole share. With
+    /// placements, `placement_weight` of the share is instead split by finishing order, so
+    /// surviving longer earns more. See [`finish_shares`].
+    #[must_use]
+    pub fn seat_scores(
+        &self,
+        players: &[u32],
+        placements: &[u32],
+        placement_weight: f64,
+    ) -> Option<Vec<f64>> {
+        let results = self.seat_results(players)?;
+
+        let mut scores = results.iter().map(SeatResult::share).collect::<Vec<_>>();
+
+        if placements.is_empty() || placement_weight <= 0.0 {
+            return Some(scores);
+        }
+
+        let finish = finish_shares(&finish_groups(self, &results, players, placements), players);
+        for (score, finish) in scores.iter_mut().zip(finish) {
+            *score = placement_weight.mul_add(finish - *score, *score);
+        }
+
+        Some(scores)
+    }
+}
+
+/// Seats grouped by finishing position, best first. Players credited by the outcome tie for first,
+/// and seats missing from a partial order tie for the remaining positions.
+fn finish_groups(
+    outcome: &GameOutcome,
+    results: &[SeatResult],
+    players: &[u32],
+    placements: &[u32],
+) -> Vec<Vec<usize>> {
+    let mut placed = results
+        .iter()
+        .map(|result| *result != SeatResult::Loss)
+        .collect::<Vec<_>>();
+
+    let mut groups = vec![
+        placed
+            .iter()
+            .enumerate()
+            .filter_map(|(seat, placed)| placed.then_some(seat))
+            .collect::<Vec<_>>(),
+    ];
+
+    for id in placements.iter().filter(|id| !outcome.is_credited(**id)) {
+        let seat = players
+            .iter()
+            .zip(&placed)
+            .position(|(player, placed)| player == id && !placed);
+        if let Some(seat) = seat
+            && let Some(placed) = placed.get_mut(seat)
+        {
+            *placed = true;
+            groups.push(vec![seat]);
+        }
+    }
+
+    groups.push(
+        placed
+            .iter()
+            .enumerate()
+            .filter_map(|(seat, placed)| (!placed).then_some(seat))
+            .collect(),
+    );
+    groups.retain(|group| !group.is_empty());
+    groups
+}
+
+/// Splits a single win between the seats by finishing order. A seat in position `p` (0 for first)
+/// of `n` seats is worth `n - 1 - p` parts, out of `n(n - 1) / 2`. Tied seats split their
+/// positions evenly.
+#[allow(clippy::cast_precision_loss)]
+fn finish_shares(groups: &[Vec<usize>], players: &[u32]) -> Vec<f64> {
+    let seats = players.len();
+    let total = (seats * (seats - 1)) as f64 / 2.0;
+    let mut shares = vec![0.0; seats];
+    let mut position = 0;
+    for group in groups {
+        let parts = (position..position + group.len())
+            .map(|p| (seats - 1 - p) as f64)
+            .sum::<f64>();
+        let share = parts / group.len() as f64 / total;
+        for seat in group {
+            if let Some(value) = shares.get_mut(*seat) {
+                *value = share;
+            }
+        }
+        position += group.len();
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use super::*;
+
+    #[test]
+    fn win_credits_single_seat() {
+        let results = GameOutcome::Win(2).seat_results(&[1, 2, 3, 2]).unwrap();
+        assert_eq!(
+            vec![
+                SeatResult::Loss,
+                SeatResult::Win,
+                SeatResult::Loss,
+                SeatResult::Loss
+            ],
+            results
+        );
+    }
+
+    #[test]
+    fn draw_splits_win() {
+        let results = GameOutcome::Draw(vec![1, 3])
+            .seat_results(&[1, 2, 3, 4])
+            .unwrap();
+        assert_eq!(
+            vec![
+                SeatResult::Draw(0.5),
+                SeatResult::Loss,
+                SeatResult::Draw(0.5),
+                SeatResult::Loss
+            ],
+            results
+        );
+    }
+
+    #[test]
+    fn no_result_has_no_seat_results() {
+        assert!(GameOutcome::NoResult.seat_results(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn placements_reward_surviving() {
+        let scores = GameOutcome::Win(1)
+            .seat_scores(&[1, 2, 3, 4], &[1, 3, 2, 4], 1.0)
+            .unwrap();
+        assert_relative_eq!(3.0 / 6.0, scores[0]);
+        assert_relative_eq!(1.0 / 6.0, scores[1]);
+        assert_relative_eq!(2.0 / 6.0, scores[2]);
+        assert_relative_eq!(0.0, scores[3]);
+    }
+
+    #[test]
+    fn partial_placements_tie_unlisted() {
+        let scores = GameOutcome::Win(1)
+            .seat_scores(&[1, 2, 3, 4], &[1, 2], 1.0)
+            .unwrap();
+        assert_relative_eq!(3.0 / 6.0, scores[0]);
+        assert_relative_eq!(2.0 / 6.0, scores[1]);
+        assert_relative_eq!(0.5 / 6.0, scores[2]);
+        assert_relative_eq!(0.5 / 6.0, scores[3]);
+    }
+
+    #[test]
+    fn placement_weight_blends_scores() {
+        let players = [1, 2, 3, 4, 5];
+        for weight in [0.0, 0.25, 0.5, 1.0] {
+            let scores = GameOutcome::Draw(vec![1, 2])
+                .seat_scores(&players, &[3, 5], weight)
+                .unwrap();
+            assert_relative_eq!(1.0, scores.iter().sum::<f64>(), epsilon = 1e-9);
+            assert_relative_eq!(scores[0], scores[1]);
+            assert!(scores[2] >= scores[4]);
+            assert!(scores[4] >= scores[3]);
+        }
+    }
+
+    #[test]
+    fn without_placements_scores_match_outcome() {
+        let scores = GameOutcome::Win(3)
+            .seat_scores(&[1, 2, 3], &[], 0.5)
+            .unwrap();
+        assert_eq!(vec![0.0, 0.0, 1.0], scores);
+    }
+
+    #[test]
+    fn validate_placement_orders() {
+        let players = [1, 2, 3, 4];
+        let win = GameOutcome::Win(1);
+        win.validate_placements(&players, &[1, 2, 3, 4]).unwrap();
+        win.validate_placements(&players, &[2, 3]).unwrap();
+        win.validate_placements(&players, &[2, 1]).unwrap_err();
+        win.validate_placements(&players, &[1, 5]).unwrap_err();
+        win.validate_placements(&players, &[1, 2, 2]).unwrap_err();
+
+        let draw = GameOutcome::Draw(vec![1, 2]);
+        draw.validate_placements(&players, &[2, 1, 4]).unwrap();
+        draw.validate_placements(&players, &[1, 4, 2]).unwrap_err();
+    }
+
+    #[test]
+    fn validate_outcomes() {
+        let players = [1, 2, 3, 4];
+        GameOutcome::Win(1).validate(&players).unwrap();
+        GameOutcome::Win(5).validate(&players).unwrap_err();
+        GameOutcome::Draw(vec![1, 2, 3, 4])
+            .validate(&players)
+            .unwrap();
+        GameOutcome::Draw(vec![1]).validate(&players).unwrap_err();
+        GameOutcome::Draw(vec![1, 5])
+            .validate(&players)
+            .unwrap_err();
+        assert!(matches!(
+            GameOutcome::Draw(vec![1, 1]).validate(&players),
+            Err(TournamentError::InvalidDraw)
+        ));
+        GameOutcome::Draw(vec![1, 1])
+            .validate(&[1, 1, 2, 3])
+            .unwrap();
+        GameOutcome::NoResult.validate(&players).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+use core::cmp::Ordering;
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{Tournament, error::TournamentError, game::matchup::Matchup, player::info::PlayerInfo};
+
+/// Where a deck stands on the leaderboard after a previewed game, compared to before it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Standing {
+    pub id: u32,
+    /// Place on the leaderboard, starting at 1
+    pub rank: usize,
+    /// Places gained, negative when the deck dropped
+    pub rank_change: isize,
+    pub elo: f64,
+    pub elo_change: f64,
+    /// Whether the game takes the deck past its highest elo so far
+    pub new_peak: bool,
+}
+
+/// The leaderboard after the matchup is won by `winner`, best deck first
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutcomePreview {
+    pub winner: u32,
+    pub leaderboard: Vec<Standing>,
+}
+
+impl OutcomePreview {
+    #[must_use]
+    pub fn get(&self, id: u32) -> Option<&Standing> {
+        self.leaderboard.iter().find(|standing| standing.id == id)
+    }
+
+    /// Decks whose rank or elo would change
+    pub fn changes(&self) -> impl Iterator<Item = &Standing> {
+        self.leaderboard.iter().filter(|standing| {
+            standing.rank_change != 0 || standing.elo_change.abs() > f64::EPSILON
+        })
+    }
+}
+
+impl Tournament {
+    /// Ids of the decks on the leaderboard, highest elo first. Retired decks are left out, and
+    /// ties are broken by name.
+    #[must_use]
+    pub fn leaderboard(&self) -> Vec<u32> {
+        self.ranking(|_| false)
+    }
+
+    /// Ranks the active decks, along with any retired deck `include` picks
+    fn ranking<F: Fn(u32) -> bool>(&self, include: F) -> Vec<u32> {
+        self.get_registered_players()
+            .filter(|player| !player.info().is_archived() || include(player.id()))
+            .sorted_by(|a, b| {
+                self.leaderboard_order((a.id(), a.stats().elo()), (b.id(), b.stats().elo()))
+            })
+            .map(|player| player.id())
+            .collect()
+    }
+
+    /// Order of two decks with the elos on the leaderboard: higher elo first, then by name
+    fn leaderboard_order(&self, (a, a_elo): (u32, f64), (b, b_elo): (u32, f64)) -> Ordering {
+        b_elo
+            .total_cmp(&a_elo)
+            .then_with(|| self.get_player_name(&a).cmp(&self.get_player_name(&b)))
+    }
+
+    /// Place the deck with the elo would take on the leaderboard if the decks had `ratings`,
+    /// starting at 1. Like [`Tournament::leaderboard`], retired decks other than the deck itself
+    /// are left out and ties are broken by name.
+    pub(crate) fn leaderboard_rank(&self, ratings: &HashMap<u32, f64>, id: u32, elo: f64) -> usize {
+        let retired = |other: u32| {
+            self.get_player_info(&other)
+                .is_some_and(PlayerInfo::is_archived)
+        };
+        ratings
+            .iter()
+            .filter(|(other, _)| **other != id && !retired(**other))
+            .filter(|(other, other_elo)| {
+                self.leaderboard_order((**other, **other_elo), (id, elo)) == Ordering::Less
+            })
+            .count()
+            + 1
+    }
+
+    /// The leaderboard that would follow each deck in the matchup winning it, in seat order.
+    /// The tournament itself isn't changed.
+    pub fn preview_outcomes(
+        &self,
+        matchup: &Matchup,
+    ) -> Result<Vec<OutcomePreview>, TournamentError> {
+        let ids = matchup.ids();
+        let in_pod = |id: u32| ids.contains(&id);
+        let before = self
+            .ranking(in_pod)
+            .into_iter()
+            .enumerate()
+            .map(|(index, id)| (id, index))
+            .collect::<HashMap<_, _>>();
+
+        let base = self.without_history();
+        ids.iter()
+            .unique()
+            .map(|winner| {
+                let mut tourn = base.clone();
+                tourn.register_record(matchup.clone().record(*winner)?)?;
+                let leaderboard = tourn
+                    .ranking(in_pod)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, id)| {
+                        let old = self.get_player_or_default_stats(id);
+                        let new = tourn.get_player_or_default_stats(id);
+                        #[allow(clippy::cast_possible_wrap)]
+                        let rank_change = before
+                            .get(&id)
+                            .map_or(0, |old_index| *old_index as isize - index as isize);
+                        Standing {
+                            id,
+                            rank: index + 1,
+                            rank_change,
+                            elo: new.elo(),
+                            elo_change: new.elo() - old.elo(),
+                            new_peak: new.elo() > old.elo_peak(),
+                        }
+                    })
+                    .collect();
+                Ok(OutcomePreview {
+                    winner: *winner,
+                    leaderboard,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::Tournament;
+
+    #[test]
+    fn preview_matches_submitting() {
+        let tourn = Tournament::generate_tournament(8, 24).unwrap();
+        let ids = tourn.leaderboard();
+        let pod = [ids[7], ids[6], ids[1], ids[0]];
+        let matchup = tourn.create_match(&pod).unwrap();
+
+        let previews = tourn.preview_outcomes(&matchup).unwrap();
+        assert_eq!(
+            pod.to_vec(),
+            previews.iter().map(|p| p.winner).collect_vec()
+        );
+        assert_eq!(ids, tourn.leaderboard());
+
+        for preview in &previews {
+            let mut submitted = tourn.clone();
+            submitted
+                .register_record(matchup.clone().record(preview.winner).unwrap())
+                .unwrap();
+            assert_eq!(
+                submitted.leaderboard(),
+                preview.leaderboard.iter().map(|s| s.id).collect_vec()
+            );
+            let winner = preview.get(preview.winner).unwrap();
+            assert!(winner.elo_change > 0.0);
+            assert!(winner.rank_change >= 0);
+            assert_eq!(
+                0,
+                preview
+                    .leaderboard
+                    .iter()
+                    .map(|standing| standing.rank_change)
+                    .sum::<isize>()
+            );
+        }
+
+        let underdog = previews[0].get(pod[0]).unwrap();
+        assert!(underdog.rank_change > 0);
+        assert!(previews[0].changes().count() >= 4);
+    }
+
+    #[test]
+    fn winning_new_deck_sets_a_peak() {
+        let tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let pod = tourn.leaderboard();
+        let matchup = tourn.create_match(&pod).unwrap();
+        let previews = tourn.preview_outcomes(&matchup).unwrap();
+        for preview in previews {
+            for standing in &preview.leaderboard {
+                assert_eq!(standing.id == preview.winner, standing.new_peak);
+            }
+            assert_eq!(1, preview.get(preview.winner).unwrap().rank);
+        }
+    }
+}
@@ -1,27 +1,47 @@
 use crate::{
     Tournament,
     error::TournamentError,
-    game::{entry::GameEntry, match_player::MatchPlayer, matchup::Matchup},
+    game::{
+        details::GameDetails,
+        entry::GameEntry,
+        match_player::MatchPlayer,
+        matchup::Matchup,
+        outcome::{GameOutcome, SeatResult},
+    },
 };
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct GameRecord {
     matchup: Matchup,
-    winner: u32,
+    outcome: GameOutcome,
+    placements: Vec<u32>,
+    details: GameDetails,
 }
 
 impl GameRecord {
-    pub fn new(matchup: Matchup, winner: u32) -> Result<Self, TournamentError> {
-        let winner_in_matchup = matchup
-            .players()
-            .iter()
-            .map(MatchPlayer::id)
-            .any(|i| i == winner);
-        if !winner_in_matchup {
-            return Err(TournamentError::PlayerNotInMatch(winner));
-        }
+    pub fn new(matchup: Matchup, outcome: GameOutcome) -> Result<Self, TournamentError> {
+        outcome.validate(&matchup.ids())?;
 
-        Ok(Self { matchup, winner })
+        Ok(Self {
+            matchup,
+            outcome,
+            placements: Vec::new(),
+            details: GameDetails::default(),
+        })
+    }
+
+    /// Sets the finishing order of the game, best to worst. The order may be partial, in which
+    /// case the players left out tie for the remaining places.
+    pub fn with_placements(mut self, placements: Vec<u32>) -> Result<Self, TournamentError> {
+        self.outcome.validate_placements(&self.ids(), &placements)?;
+        self.placements = placements;
+        Ok(self)
+    }
+
+    #[must_use]
+    pub fn with_details(mut self, details: GameDetails) -> Self {
+        self.details = details;
+        self
     }
 
     #[must_use]
@@ -45,48 +65,125 @@ impl GameRecord {
     }
 
     #[must_use]
-    pub const fn players(&self) -> &[MatchPlayer; 4] {
+    pub fn players(&self) -> &[MatchPlayer] {
         self.matchup().players()
     }
 
     #[must_use]
-    pub const fn ids(&self) -> [u32; 4] {
+    pub fn ids(&self) -> Vec<u32> {
         self.matchup.ids()
     }
 
     #[must_use]
-    pub const fn winner(&self) -> u32 {
-        self.winner
+    pub const fn outcome(&self) -> &GameOutcome {
+        &self.outcome
+    }
+
+    #[must_use]
+    pub const fn winner(&self) -> Option<u32> {
+        self.outcome.winner()
+    }
+
+    /// Turn order position of a seat, 0 for the player that went first. Returns `None` when the
+    /// turn order is unknown.
+    #[must_use]
+    pub const fn turn_position(&self, seat: usize) -> Option<usize> {
+        self.matchup.turn_position(seat)
+    }
+
+    #[must_use]
+    pub fn placements(&self) -> &[u32] {
+        &self.placements
+    }
+
+    #[must_use]
+    pub const fn details(&self) -> &GameDetails {
+        &self.details
+    }
+
+    pub(crate) const fn details_mut(&mut self) -> &mut GameDetails {
+        &mut self.details
+    }
+
+    /// Finishing place of a player, starting at 1. Players credited by the outcome share first
+    /// place. Returns `None` if the player's place wasn't recorded.
+    #[must_use]
+    pub fn placement(&self, id: u32) -> Option<usize> {
+        let credited = self.outcome.credited();
+        if credited.contains(&id) {
+            return Some(1);
+        }
+
+        self.placements
+            .iter()
+            .filter(|placed| !credited.contains(placed))
+            .position(|placed| *placed == id)
+            .map(|position| credited.len() + position + 1)
+    }
+
+    /// Result of each seat, or `None` if the game has no result
+    #[must_use]
+    pub fn seat_results(&self) -> Option<Vec<SeatResult>> {
+        self.outcome.seat_results(&self.ids())
+    }
+
+    /// Share of the win earned by each seat, or `None` if the game has no result
+    #[must_use]
+    pub fn seat_scores(&self) -> Option<Vec<f64>> {
+        self.outcome.seat_scores(
+            &self.ids(),
+            &self.placements,
+            self.matchup.placement_weight(),
+        )
+    }
+
+    /// Seats grouped by finishing position, best first. See [`GameOutcome::finish_order`].
+    #[must_use]
+    pub fn finish_order(&self) -> Option<Vec<Vec<usize>>> {
+        self.outcome.finish_order(&self.ids(), &self.placements)
     }
 
     pub fn get_player_elo_change(&self, id: u32) -> Result<f64, TournamentError> {
         let mut score = 0.0;
-        let mut won = false;
 
-        for player in self.matchup.players() {
+        let Some(scores) = self.seat_scores() else {
+            return Ok(score);
+        };
+
+        for (player, seat_score) in self.matchup.players().iter().zip(scores) {
             if player.id() != id {
                 continue;
             }
-            if player.id() == self.winner && !won {
-                won = true;
-                score += player.elo_win();
-            } else {
-                score -= player.elo_loss();
-            }
+            score += player.elo_change(seat_score);
         }
 
         Ok(score)
     }
+
+    #[must_use]
+    pub fn to_entry(&self) -> GameEntry {
+        GameEntry::new_unchecked(self.ids(), self.outcome.clone())
+            .with_first_unchecked(self.matchup.first())
+            .with_placements_unchecked(self.placements.clone())
+            .with_details(self.details.clone())
+    }
 }
 
 impl Tournament {
     pub fn update_record(&self, record: GameRecord) -> Result<GameRecord, TournamentError> {
-        self.update_match(record.matchup)?.record(record.winner)
+        self.ensure_details(&record.details, &record.ids(), &record.outcome)?;
+        Ok(self
+            .update_match(record.matchup)?
+            .record_placements(record.outcome, record.placements)?
+            .with_details(record.details))
     }
 }
 
 impl From<GameRecord> for GameEntry {
     fn from(value: GameRecord) -> Self {
-        Self::new_unchecked(value.ids(), value.winner)
+        Self::new_unchecked(value.ids(), value.outcome)
+            .with_first_unchecked(value.matchup.first())
+            .with_placements_unchecked(value.placements)
+            .with_details(value.details)
     }
 }
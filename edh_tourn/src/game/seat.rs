@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::{
+    Tournament,
+    error::TournamentError,
+    game::{MAX_POD_SIZE, outcome::SeatResult, record::GameRecord},
+};
+
+/// Results of a turn order position, such as going first
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SeatStats {
+    games: u32,
+    /// Shared wins count as the share of the win
+    wins: f64,
+}
+
+impl SeatStats {
+    #[must_use]
+    pub const fn games(&self) -> u32 {
+        self.games
+    }
+
+    #[must_use]
+    pub const fn wins(&self) -> f64 {
+        self.wins
+    }
+
+    #[must_use]
+    pub fn wr(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.wins / f64::from(self.games))
+    }
+}
+
+/// Adds the result of each seat of the game that `include` accepts to the stats of its turn
+/// order position. Games without a turn order or a result are skipped.
+fn add_game<F>(stats: &mut [SeatStats], game: &GameRecord, include: F)
+where
+    F: Fn(u32) -> bool,
+{
+    let Some(results) = game.seat_results() else {
+        return;
+    };
+
+    for (seat, (player, result)) in game.players().iter().zip(results).enumerate() {
+        if !include(player.id()) {
+            continue;
+        }
+        let Some(seat_stats) = game
+            .turn_position(seat)
+            .and_then(|position| stats.get_mut(position))
+        else {
+            continue;
+        };
+
+        seat_stats.games += 1;
+        seat_stats.wins += match result {
+            SeatResult::Win => 1.0,
+            SeatResult::Draw(share) => share,
+            SeatResult::Loss => 0.0,
+        };
+    }
+}
+
+/// Adds a game to the seat stats of its pod size
+pub(crate) fn tally_game(tallies: &mut HashMap<usize, Vec<SeatStats>>, game: &GameRecord) {
+    let size = game.players().len();
+    let stats = tallies
+        .entry(size)
+        .or_insert_with(|| vec![SeatStats::default(); size]);
+    add_game(stats, game, |_| true);
+}
+
+impl Tournament {
+    /// Win rate of each turn order position, first player at index 0. Only games with a recorded
+    /// turn order and a result count. With a pod size, only games of that size count.
+    #[must_use]
+    pub fn get_seat_stats(&self, pod_size: Option<usize>) -> Vec<SeatStats> {
+        self.collect_seat_stats(pod_size, |_| true)
+    }
+
+    /// Same as [`Tournament::get_seat_stats`], but only for the seats a deck sat in
+    pub fn get_player_seat_stats(
+        &self,
+        id: u32,
+        pod_size: Option<usize>,
+    ) -> Result<Vec<SeatStats>, TournamentError> {
+        if !self.is_id_registered(&id) {
+            return Err(TournamentError::InvalidPlayerId(id));
+        }
+        Ok(self.collect_seat_stats(pod_size, |player| player == id))
+    }
+
+    fn collect_seat_stats<F>(&self, pod_size: Option<usize>, include: F) -> Vec<SeatStats>
+    where
+        F: Fn(u32) -> bool,
+    {
+        let mut stats = vec![SeatStats::default(); pod_size.unwrap_or(MAX_POD_SIZE)];
+
+        let games = self
+            .games
+            .iter()
+            .filter(|game| pod_size.is_none_or(|size| game.players().len() == size));
+        for game in games {
+            add_game(&mut stats, game, &include);
+        }
+
+        stats
+    }
+
+    /// Counts the seat stats of every pod size again from the games in the history
+    pub(crate) fn recount_seats(&mut self) {
+        let mut tallies = HashMap::new();
+        for game in &self.games {
+            tally_game(&mut tallies, game);
+        }
+        self.seat_tallies = tallies;
+    }
+
+    /// How much more (or less) likely each turn order position is to win than an even share, for
+    /// pods of the given size. Returns `None` unless seat advantage is enabled and enough games
+    /// have been recorded. Win rates are smoothed towards an even share. Uses the seat stats kept
+    /// up to date as games are recorded, so the history isn't scanned for every matchup.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn seat_factors(&self, pod_size: usize) -> Option<Vec<f64>> {
+        if !self.config.seat_advantage {
+            return None;
+        }
+
+        let stats = self.seat_tallies.get(&pod_size)?;
+        let games = stats.first().map_or(0, SeatStats::games);
+        if games == 0 || games < self.config.seat_advantage_min_games {
+            return None;
+        }
+
+        let size = pod_size as f64;
+        Some(
+            stats
+                .iter()
+                .map(|seat| size * (seat.wins + 1.0) / (f64::from(seat.games) + size))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::Tournament;
+
+    /// The player going first wins every game
+    fn first_player_wins(games: usize) -> Tournament {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for game in 0..games {
+            let first = game % 4;
+            let record = tourn
+                .create_seated_match(&ids, Some(first))
+                .unwrap()
+                .record(ids[first])
+                .unwrap();
+            tourn.register_record(record).unwrap();
+        }
+        tourn
+    }
+
+    #[test]
+    fn seat_stats_by_turn_position() {
+        let tourn = first_player_wins(8);
+        let stats = tourn.get_seat_stats(Some(4));
+        assert_eq!(4, stats.len());
+        assert_eq!(Some(1.0), stats[0].wr());
+        assert_eq!(Some(0.0), stats[3].wr());
+        assert_eq!(8, stats[2].games());
+
+        let id = *tourn.players().keys().next().unwrap();
+        let player_stats = tourn.get_player_seat_stats(id, None).unwrap();
+        assert_eq!(2, player_stats[0].games());
+        assert_eq!(None, player_stats[5].wr());
+    }
+
+    #[test]
+    fn tallies_follow_edits() {
+        let mut tourn = first_player_wins(12);
+        assert_eq!(tourn.get_seat_stats(Some(4)), tourn.seat_tallies[&4]);
+
+        tourn.delete_game(3).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let entry = tourn
+            .create_seated_match(&ids, Some(2))
+            .unwrap()
+            .record(ids[0])
+            .unwrap()
+            .to_entry();
+        tourn.insert_game_at(5, entry).unwrap();
+        assert_eq!(tourn.get_seat_stats(Some(4)), tourn.seat_tallies[&4]);
+        assert_eq!(12, tourn.seat_tallies[&4][0].games());
+
+        let mut reloaded = tourn.clone();
+        reloaded.reload().unwrap();
+        assert_eq!(reloaded.seat_tallies, tourn.seat_tallies);
+    }
+
+    #[test]
+    fn unseated_games_are_ignored() {
+        let tourn = Tournament::generate_tournament(6, 20).unwrap();
+        assert!(tourn.get_seat_stats(None).iter().all(|s| s.games() == 0));
+    }
+
+    #[test]
+    fn seat_advantage_needs_enough_games() {
+        let mut tourn = first_player_wins(12);
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let even = tourn.create_seated_match(&ids, Some(0)).unwrap();
+
+        let mut config = tourn.config().clone();
+        config.seat_advantage = true;
+        config.seat_advantage_min_games = 100;
+        tourn.set_config(config.clone()).unwrap();
+        let too_few = tourn.create_seated_match(&ids, Some(0)).unwrap();
+        assert_eq!(even.players(), too_few.players());
+
+        config.seat_advantage_min_games = 10;
+        tourn.set_config(config).unwrap();
+        let seated = tourn.create_seated_match(&ids, Some(1)).unwrap();
+        let expected = seated.players().iter().map(|p| *p.expected()).collect_vec();
+        assert_relative_eq!(1.0, expected.iter().copied().sum::<f64>(), epsilon = 1e-9);
+        assert!(expected[1] > expected[0]);
+        assert!(expected[1] > expected[2]);
+
+        // Without a known turn order, seats aren't adjusted
+        let unseated = tourn.create_match(&ids).unwrap();
+        assert!(*unseated.players()[1].expected() < expected[1]);
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::Tournament;
+
+/// How a deck did in the games it shared with one opponent
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PairRecord {
+    /// Games both decks played in
+    pub games: u32,
+    /// Shared games with a single winner
+    pub decided: u32,
+    /// Shared games the deck won
+    pub wins: u32,
+    /// Shared games the opponent won
+    pub opponent_wins: u32,
+    /// Shared games with a result, which the expected and actual scores are summed over
+    pub scored: u32,
+    /// Sum of the deck's expected results
+    pub expected: f64,
+    /// Sum of the deck's actual results, with draws counting as a share of a win
+    pub actual: f64,
+    /// Index of each shared game in the history
+    pub game_ids: Vec<usize>,
+}
+
+impl PairRecord {
+    #[must_use]
+    #[allow(clippy::cast_lossless)]
+    pub fn mean_expected(&self) -> Option<f64> {
+        (self.scored > 0).then(|| self.expected / f64::from(self.scored))
+    }
+
+    #[must_use]
+    pub fn mean_actual(&self) -> Option<f64> {
+        (self.scored > 0).then(|| self.actual / f64::from(self.scored))
+    }
+
+    /// How much better the deck did than expected, on average per shared game
+    #[must_use]
+    pub fn over_performance(&self) -> Option<f64> {
+        Some(self.mean_actual()? - self.mean_expected()?)
+    }
+}
+
+/// Records between every ordered pair of decks that have shared a game
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HeadToHeadMatrix {
+    pairs: HashMap<(u32, u32), PairRecord>,
+}
+
+impl HeadToHeadMatrix {
+    /// How `deck` did in the games it shared with `opponent`
+    #[must_use]
+    pub fn get(&self, deck: u32, opponent: u32) -> Option<&PairRecord> {
+        self.pairs.get(&(deck, opponent))
+    }
+
+    /// Every opponent `deck` has shared a game with
+    pub fn opponents(&self, deck: u32) -> impl Iterator<Item = (u32, &PairRecord)> {
+        self.pairs
+            .iter()
+            .filter(move |((id, _), _)| *id == deck)
+            .map(|((_, opponent), record)| (*opponent, record))
+    }
+}
+
+impl Tournament {
+    /// Records between every ordered pair of decks, from the games they played together
+    #[must_use]
+    pub fn head_to_head(&self) -> HeadToHeadMatrix {
+        let mut pairs: HashMap<(u32, u32), PairRecord> = HashMap::new();
+        for (index, game) in self.games.iter().enumerate() {
+            let winner = game.winner();
+            let results = game.seat_results();
+            let pod = game.ids().into_iter().unique().collect_vec();
+            let scores = pod
+                .iter()
+                .map(|id| {
+                    let results = results.as_ref()?;
+                    game.players()
+                        .iter()
+                        .zip(results)
+                        .find(|(player, _)| player.id() == *id)
+                        .map(|(player, result)| (*player.expected(), result.share()))
+                })
+                .collect_vec();
+
+            for ((deck, score), opponent) in pod
+                .iter()
+                .zip(&scores)
+                .cartesian_product(&pod)
+                .filter(|((deck, _), opponent)| deck != opponent)
+            {
+                let record = pairs.entry((*deck, *opponent)).or_default();
+                record.games += 1;
+                record.game_ids.push(index);
+                if let Some(winner) = winner {
+                    record.decided += 1;
+                    record.wins += u32::from(winner == *deck);
+                    record.opponent_wins += u32::from(winner == *opponent);
+                }
+                if let Some((expected, actual)) = score {
+                    record.scored += 1;
+                    record.expected += expected;
+                    record.actual += actual;
+                }
+            }
+        }
+        HeadToHeadMatrix { pairs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        game::{entry::GameEntry, outcome::GameOutcome},
+    };
+
+    #[test]
+    fn pairs_mirror_each_other() {
+        let tourn = Tournament::generate_tournament(6, 30).unwrap();
+        let matrix = tourn.head_to_head();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for (deck, opponent) in ids.iter().tuple_combinations() {
+            let Some(record) = matrix.get(*deck, *opponent) else {
+                assert!(matrix.get(*opponent, *deck).is_none());
+                continue;
+            };
+            let mirror = matrix.get(*opponent, *deck).unwrap();
+            assert_eq!(record.games, mirror.games);
+            assert_eq!(record.wins, mirror.opponent_wins);
+            assert_eq!(record.game_ids, mirror.game_ids);
+            for gid in &record.game_ids {
+                let game = &tourn.games()[*gid];
+                assert!(game.has_player(*deck) && game.has_player(*opponent));
+            }
+        }
+        assert!(matrix.get(ids[0], ids[0]).is_none());
+    }
+
+    #[test]
+    fn counts_wins_and_scores() {
+        let mut tourn = Tournament::generate_tournament(5, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let pod = ids[..4].to_vec();
+        let entries = [
+            GameEntry::new(pod.clone(), GameOutcome::Win(ids[0])).unwrap(),
+            GameEntry::new(pod.clone(), GameOutcome::Win(ids[1])).unwrap(),
+            GameEntry::new(pod.clone(), GameOutcome::Draw(vec![ids[0], ids[1]])).unwrap(),
+            GameEntry::new(pod, GameOutcome::NoResult).unwrap(),
+        ];
+        for entry in entries {
+            tourn.register_entry(entry).unwrap();
+        }
+
+        let matrix = tourn.head_to_head();
+        let record = matrix.get(ids[0], ids[1]).unwrap();
+        assert_eq!(4, record.games);
+        assert_eq!(2, record.decided);
+        assert_eq!(1, record.wins);
+        assert_eq!(1, record.opponent_wins);
+        assert_eq!(3, record.scored);
+        assert_relative_eq!(1.5, record.actual);
+        assert_relative_eq!(0.5, record.mean_actual().unwrap());
+        assert_eq!(vec![0, 1, 2, 3], record.game_ids);
+        assert!(matrix.opponents(ids[4]).next().is_none());
+        assert_eq!(3, matrix.opponents(ids[0]).count());
+    }
+}
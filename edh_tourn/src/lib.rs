@@ -2,23 +2,34 @@
 #[macro_use]
 extern crate approx;
 
+pub mod calibration;
+pub mod command;
 pub mod compat;
 pub mod config;
 #[cfg(feature = "dev")]
 pub mod dev;
 pub mod error;
 pub mod game;
+pub mod head_to_head;
 pub mod matches;
+pub mod meta;
+pub mod pilot;
+pub mod planner;
 pub mod player;
+pub mod rating;
 pub mod serialization;
+pub mod simulation;
 pub mod tsv;
+pub mod tuning;
 
 use std::collections::HashMap;
 
 use crate::{
     config::TournamentConfig,
     error::TournamentError,
-    game::{entry::GameEntry, record::GameRecord},
+    game::{record::GameRecord, seat::SeatStats},
+    matches::constraints::MatchConstraints,
+    pilot::PilotInfo,
     player::info::PlayerInfo,
     player::stats::PlayerStats,
     serialization::{convert_games, ordered_map},
@@ -37,8 +48,28 @@ pub struct Tournament {
     players: HashMap<u32, PlayerInfo>,
     #[serde(skip)]
     player_names: HashMap<String, u32>,
+    #[serde(
+        serialize_with = "ordered_map",
+        rename = "pl",
+        alias = "pilots",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pilots: HashMap<u32, PilotInfo>,
     #[serde(serialize_with = "convert_games", rename = "g", alias = "games")]
     games: Vec<GameRecord>,
+    #[serde(
+        rename = "mc",
+        alias = "constraints",
+        skip_serializing_if = "MatchConstraints::is_empty"
+    )]
+    constraints: MatchConstraints,
+    /// Stats of the players of each game right after it, in the same order as `games`. The stats
+    /// before any game can be rebuilt from these, so an edit only replays the games after it.
+    #[serde(skip)]
+    checkpoints: Vec<Vec<(u32, PlayerStats)>>,
+    /// Seat stats of the games of each pod size, kept up to date as games are recorded
+    #[serde(skip)]
+    seat_tallies: HashMap<usize, Vec<SeatStats>>,
     #[serde(skip)]
     snapshot: usize,
 }
@@ -48,10 +79,14 @@ impl Default for Tournament {
         let config = TournamentConfig::default();
         Self {
             stats: HashMap::default(),
-            default_stats: PlayerStats::new(config.starting_elo),
+            default_stats: config.initial_stats(),
             players: HashMap::default(),
             player_names: HashMap::default(),
+            pilots: HashMap::default(),
             games: Vec::new(),
+            constraints: MatchConstraints::new(),
+            checkpoints: Vec::new(),
+            seat_tallies: HashMap::new(),
             snapshot: 0,
             config,
         }
@@ -64,6 +99,24 @@ impl Tournament {
         Self::default()
     }
 
+    /// Copy of the tournament without the game history or its checkpoints, so that it stays cheap
+    /// to copy as the history grows. Only fit for recording more games on and ranking the decks.
+    pub(crate) fn without_history(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            stats: self.stats.clone(),
+            default_stats: self.default_stats.clone(),
+            players: self.players.clone(),
+            player_names: self.player_names.clone(),
+            pilots: self.pilots.clone(),
+            games: Vec::new(),
+            constraints: self.constraints.clone(),
+            checkpoints: Vec::new(),
+            seat_tallies: self.seat_tallies.clone(),
+            snapshot: self.snapshot,
+        }
+    }
+
     #[must_use]
     pub fn get_player_id(&self, name: &String) -> Option<u32> {
         self.player_names.get(name).copied()
@@ -74,18 +127,35 @@ impl Tournament {
         self.players.contains_key(id)
     }
 
+    /// Removes a player along with every game they were in. Only the games after their first
+    /// game are replayed. Nothing changes if the replay fails.
     pub fn unregister_player(&mut self, id: u32) -> Result<(), TournamentError> {
-        self.players
-            .remove(&id)
-            .ok_or(TournamentError::InvalidPlayerId(id))?;
-        self.games.retain(|game| !game.has_player(id));
-        self.reload()?;
+        if !self.is_id_registered(&id) {
+            return Err(TournamentError::InvalidPlayerId(id));
+        }
+
+        if let Some(first) = self.games.iter().position(|game| game.has_player(id)) {
+            self.edit_games_from(first, |entries| {
+                Ok(entries
+                    .into_iter()
+                    .filter(|entry| !entry.players().contains(&id))
+                    .collect())
+            })?;
+        } else {
+            self.snapshot += 1;
+        }
+
+        if let Some(info) = self.players.remove(&id) {
+            self.player_names.remove(info.name());
+        }
+        self.constraints.remove_deck(id);
         Ok(())
     }
 
+    /// Replays every game from the start, such as after the config changed
     pub fn reload(&mut self) -> Result<(), TournamentError> {
         let version = self.snapshot;
-        self.default_stats = PlayerStats::new(self.config.starting_elo);
+        self.default_stats = self.config.initial_stats();
         // Update player_names to the player info
         self.player_names = self
             .players
@@ -93,13 +163,7 @@ impl Tournament {
             .map(|(id, info)| (info.name().to_owned(), *id))
             .collect();
 
-        self.stats.clear();
-
-        let mut games = Vec::new();
-        core::mem::swap(&mut self.games, &mut games);
-        for record in games {
-            self.register_entry(record.into())?;
-        }
+        self.edit_games_from(0, Ok)?;
         self.snapshot = version + 1;
 
         Ok(())
@@ -110,9 +174,23 @@ impl Tournament {
         &self.players
     }
 
-    /// Merges with another tournament. If decks from either game have the same name, they are
-    /// merged. Games are added to the end of the base tournament.
+    /// Bumped on every change, so values computed from the tournament can be kept until it
+    /// changes. Loading a tournament starts it over at 0.
+    #[must_use]
+    pub const fn snapshot(&self) -> usize {
+        self.snapshot
+    }
+
+    /// Merges with another tournament. If decks or pilots from either game have the same name,
+    /// they are merged. Games are added to the end of the base tournament.
     pub fn merge(&mut self, other: &Self) -> Result<(), TournamentError> {
+        let mut pilot_map = HashMap::new();
+
+        for (old_id, info) in &other.pilots {
+            let id = self.get_or_register_pilot(info.name().to_owned())?;
+            pilot_map.insert(*old_id, id);
+        }
+
         let mut id_map = HashMap::new();
 
         for (old_id, info) in &other.players {
@@ -120,17 +198,28 @@ impl Tournament {
                 *old_id,
                 match self.get_player_id(info.name()) {
                     Some(id) => id,
-                    None => self.register_player_with_info(info.clone())?,
+                    None => self.register_player_with_info(info.map_owner(&pilot_map)?)?,
                 },
             );
         }
 
         for game in &other.games {
-            let entry = GameEntry::new(game.ids(), game.winner())?;
-            let entry_mapped = entry.map_ids(&id_map)?;
+            let entry = game.to_entry();
+            let entry_mapped = entry.map_ids(&id_map)?.map_pilots(&pilot_map)?;
             self.register_entry(entry_mapped)?;
         }
 
+        let constraints = other.constraints.map_ids(&id_map)?;
+        for (a, b) in constraints.exclusions() {
+            self.constraints.add_exclusion(*a, *b);
+        }
+        for id in constraints.required() {
+            self.constraints.set_required(*id, true);
+        }
+        self.constraints.set_one_deck_per_owner(
+            self.constraints.one_deck_per_owner() || constraints.one_deck_per_owner(),
+        );
+
         Ok(())
     }
 
@@ -143,20 +232,28 @@ impl Tournament {
         self.config.clone_into(&mut tourn.config);
         tourn.snapshot = 0;
 
+        // Register pilots
+        let mut pilot_map = HashMap::new();
+        for (old_id, info) in &self.pilots {
+            let id = tourn.register_pilot(info.name().to_owned())?;
+            pilot_map.insert(*old_id, id);
+        }
+
         let mut id_map = HashMap::new();
 
         // Register players
         for (old_id, info) in &self.players {
-            let id = tourn.register_player_with_info(info.clone())?;
+            let id = tourn.register_player_with_info(info.map_owner(&pilot_map)?)?;
             id_map.insert(*old_id, id);
         }
 
         // Register Games
         for game in &self.games {
-            let entry = GameEntry::new(game.ids(), game.winner())?;
-            let mapped = entry.map_ids(&id_map)?;
+            let entry = game.to_entry();
+            let mapped = entry.map_ids(&id_map)?.map_pilots(&pilot_map)?;
             tourn.register_entry(mapped)?;
         }
+        tourn.constraints = self.constraints.map_ids(&id_map)?;
 
         tourn.snapshot = 0;
 
@@ -177,9 +274,14 @@ impl FromIterator<Self> for Tournament {
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::indexing_slicing)]
+
     use itertools::Itertools;
 
-    use crate::Tournament;
+    use crate::{
+        Tournament,
+        game::{entry::GameEntry, outcome::GameOutcome},
+    };
 
     #[test]
     fn new_tournament_snapshot_is_0() {
@@ -207,7 +309,7 @@ mod tests {
             tourn.unregister_player(*id).unwrap();
             for game in tourn.games() {
                 assert!(!game.has_player(*id));
-                assert_ne!(game.winner(), *id);
+                assert_ne!(game.winner(), Some(*id));
             }
         }
     }
@@ -246,6 +348,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn merge_maps_pilots() -> anyhow::Result<()> {
+        let mut other = Tournament::generate_tournament(4, 0)?;
+        let decks = other.players.keys().copied().sorted().collect_vec();
+        other.register_pilot("Unused".to_owned())?;
+        let alice = other.register_pilot("Alice".to_owned())?;
+        other.set_deck_owner(decks[0], Some(alice))?;
+        let mut entry = GameEntry::new(decks.clone(), GameOutcome::Win(decks[1]))?;
+        entry.details_mut().pilots.insert(decks[1], alice);
+        other.register_entry(entry)?;
+
+        let mut base = Tournament::new();
+        let base_alice = base.register_pilot("Alice".to_owned())?;
+        base.merge(&other)?;
+
+        assert_eq!(2, base.pilots().len());
+        let deck = base.get_player_id(other.players[&decks[0]].name()).unwrap();
+        assert_eq!(Some(base_alice), base.players[&deck].owner());
+        assert!(
+            base.games[0]
+                .details()
+                .pilots
+                .values()
+                .all(|p| *p == base_alice)
+        );
+
+        let fresh = other.into_fresh()?;
+        assert_eq!(
+            other.get_all_pilot_stats().len(),
+            fresh.get_all_pilot_stats().len()
+        );
+        Ok(())
+    }
+
     #[test]
     fn into_fresh_resets_snapshot() {
         let mut game = Tournament::new();
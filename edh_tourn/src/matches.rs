@@ -1,15 +1,20 @@
 use core::cmp::Ordering;
 use std::collections::HashMap;
 
-use itertools::{Itertools, chain};
+use itertools::Itertools;
 
 use crate::{
     Tournament,
     error::TournamentError,
-    game::{match_player::MatchPlayer, record::GameRecord},
+    game::{DEFAULT_POD_SIZE, match_player::MatchPlayer, record::GameRecord},
+    head_to_head::HeadToHeadMatrix,
+    matches::criteria::CRITERIA,
     player::stats::PlayerStats,
 };
 
+pub mod constraints;
+pub mod criteria;
+
 fn with_tie_breaker(cmp: Ordering, tie_breaker: impl Fn() -> Ordering) -> Ordering {
     match cmp {
         Ordering::Equal => tie_breaker(),
@@ -17,13 +22,6 @@ fn with_tie_breaker(cmp: Ordering, tie_breaker: impl Fn() -> Ordering) -> Orderi
     }
 }
 
-fn get_mut_from_map<'a, T>(
-    id: &'a u32,
-    map: &'a mut HashMap<u32, T>,
-) -> Result<&'a mut T, TournamentError> {
-    map.get_mut(id).ok_or(TournamentError::InvalidPlayerId(*id))
-}
-
 #[allow(clippy::cast_precision_loss)]
 fn to_weight_rank(
     ranking: impl IntoIterator<Item = u32>,
@@ -48,10 +46,11 @@ impl Tournament {
             .map_or(self.config.starting_elo, PlayerStats::elo)
     }
 
+    #[allow(clippy::cast_precision_loss)]
     fn get_wr(&self, id: u32) -> f64 {
         self.get_player_stats(id)
             .and_then(PlayerStats::wr)
-            .unwrap_or(0.25)
+            .unwrap_or(1.0 / DEFAULT_POD_SIZE as f64)
     }
 
     pub fn rank_least_played(&self, id: u32) -> Result<impl Iterator<Item = u32>, TournamentError> {
@@ -72,127 +71,132 @@ impl Tournament {
 
         let cmp_elo = self.get_elo(id);
 
-        Ok(counts
-            .into_iter()
-            .map(|(id, count)| (id, count, (cmp_elo - self.get_elo(id)).abs()))
-            .sorted_by(|(id1, c1, elo1), (id2, c2, elo2)| {
-                with_tie_breaker(c1.cmp(c2), || {
-                    with_tie_breaker(elo1.total_cmp(elo2), || id1.cmp(id2))
+        Ok(self.constrained(
+            id,
+            counts
+                .into_iter()
+                .filter(|(id, _)| !self.is_player_archived(*id))
+                .map(|(id, count)| (id, count, (cmp_elo - self.get_elo(id)).abs()))
+                .sorted_by(|(id1, c1, elo1), (id2, c2, elo2)| {
+                    with_tie_breaker(c1.cmp(c2), || {
+                        with_tie_breaker(elo1.total_cmp(elo2), || id1.cmp(id2))
+                    })
                 })
-            })
-            .map(|(id, _, _)| id))
+                .map(|(id, _, _)| id),
+        ))
     }
 
+    /// Ranks opponents by how even a game between the two decks is expected to be, under the
+    /// rating system of the config
     pub fn rank_expected_neighbors(
         &self,
         id: u32,
     ) -> Result<impl Iterator<Item = u32>, TournamentError> {
-        struct R {
-            elo: f64,
-            wr: f64,
-        }
-
         self.ensure_id_registered(id)?;
-
-        let mut players = HashMap::new();
-
-        let mut sum_wr = 0.0;
-        let mut sum_elo = 0.0;
-
-        for player in self.players().keys() {
-            let stats = self.get_player_or_default_stats(*player);
-            let wr = stats
-                .wr()
-                .unwrap_or(0.25)
-                .powf(self.config.game_wr_pow_scale);
-            let elo = stats.elo().powf(self.config.game_wr_pow_scale);
-            sum_elo += elo;
-            sum_wr += wr;
-            players.insert(*player, R { elo, wr });
-        }
-
-        let coef_elo = self.config.game_elo_weight / sum_elo;
-        let coef_wr = self.config.game_wr_weight / sum_wr;
-
-        let player = players
-            .remove(&id)
-            .ok_or(TournamentError::InvalidPlayerId(id))?;
-        let target = player.wr.mul_add(coef_wr, player.elo * coef_elo);
-
-        Ok(players
-            .into_iter()
-            .map(move |(id, R { wr, elo })| {
-                (id, (target - wr.mul_add(coef_wr, elo * coef_elo)).abs())
-            })
-            .sorted_by(|(id1, s1), (id2, s2)| with_tie_breaker(s1.total_cmp(s2), || id1.cmp(id2)))
-            .map(|(id, _)| id))
+        let stats = self.get_player_or_default_stats(id);
+        let rating = self.config.rating();
+
+        Ok(self.constrained(
+            id,
+            self.players
+                .keys()
+                .filter(|pid| id != **pid && !self.is_player_archived(**pid))
+                .map(|pid| {
+                    let other = self.get_player_or_default_stats(*pid);
+                    let expected = rating.expected(&[stats, other], &self.config);
+                    let chance = expected.first().copied().unwrap_or(0.5);
+                    (*pid, (chance - 0.5).abs())
+                })
+                .sorted_by(|(id1, s1), (id2, s2)| {
+                    with_tie_breaker(s1.total_cmp(s2), || id1.cmp(id2))
+                })
+                .map(|(id, _)| id),
+        ))
     }
 
-    pub fn rank_nemesis(&self, id: u32) -> Result<impl Iterator<Item = u32>, TournamentError> {
+    /// Ranks opponents by how much more often they beat the deck than it beat them, using the
+    /// head to head records of [`Tournament::head_to_head`]
+    pub fn rank_nemesis(
+        &self,
+        id: u32,
+        matrix: &HeadToHeadMatrix,
+    ) -> Result<impl Iterator<Item = u32>, TournamentError> {
         self.ensure_id_registered(id)?;
 
-        let mut counts = self
-            .players
-            .keys()
-            .map(|i| (*i, 0))
-            .collect::<HashMap<u32, i32>>();
-
-        for game in &self.games {
-            if game.has_player(id) {
-                let val = if game.winner() == id { 1 } else { -1 };
-                for player in game.players() {
-                    *get_mut_from_map(&player.id(), &mut counts)? += val;
-                }
-            }
-        }
-
-        counts.remove(&id);
+        // Games without a single winner say nothing about who beats whom
+        Ok(self.constrained(
+            id,
+            self.players
+                .keys()
+                .filter(|pid| id != **pid && !self.is_player_archived(**pid))
+                .map(|pid| {
+                    let score = matrix.get(id, *pid).map_or(0, |record| {
+                        i64::from(record.wins) * 2 - i64::from(record.decided)
+                    });
+                    (*pid, score, self.get_elo(*pid))
+                })
+                .sorted_by(|(id1, s1, e1), (id2, s2, e2)| {
+                    with_tie_breaker(s1.cmp(s2), || {
+                        with_tie_breaker(e1.total_cmp(e2), || id1.cmp(id2))
+                    })
+                })
+                .map(|(id, _, _)| id),
+        ))
+    }
 
-        Ok(counts
-            .into_iter()
-            .map(|(id, score)| (id, score, self.get_elo(id)))
-            .sorted_by(|(id1, s1, e1), (id2, s2, e2)| {
-                with_tie_breaker(s1.cmp(s2), || {
-                    with_tie_breaker(e1.total_cmp(e2), || id1.cmp(id2))
+    /// Ranks opponents by how often they knocked the deck out, less how often the deck knocked
+    /// them out. Unlike [`Tournament::rank_nemesis`], only recorded eliminations count.
+    pub fn rank_kill_nemesis(&self, id: u32) -> Result<impl Iterator<Item = u32>, TournamentError> {
+        let records = self.get_head_to_head_kills(id)?;
+
+        Ok(self.constrained(
+            id,
+            self.players
+                .keys()
+                .filter(|pid| id != **pid && !self.is_player_archived(**pid))
+                .map(|pid| {
+                    let record = records.get(pid).copied().unwrap_or_default();
+                    (
+                        *pid,
+                        i64::from(record.deaths) - i64::from(record.kills),
+                        record.deaths,
+                    )
+                })
+                .sorted_by(|(id1, s1, d1), (id2, s2, d2)| {
+                    with_tie_breaker(s2.cmp(s1), || with_tie_breaker(d2.cmp(d1), || id1.cmp(id2)))
                 })
-            })
-            .map(|(id, _, _)| id))
+                .map(|(id, _, _)| id),
+        ))
     }
 
-    pub fn rank_loss_with(&self, id: u32) -> Result<impl Iterator<Item = u32>, TournamentError> {
+    /// Ranks opponents by how often neither deck won their shared games, using the head to head
+    /// records of [`Tournament::head_to_head`]
+    pub fn rank_loss_with(
+        &self,
+        id: u32,
+        matrix: &HeadToHeadMatrix,
+    ) -> Result<impl Iterator<Item = u32>, TournamentError> {
         self.ensure_id_registered(id)?;
 
-        let mut counts = self
-            .players
-            .keys()
-            .map(|i| (*i, (0, 0)))
-            .collect::<HashMap<u32, (i32, u32)>>();
-        // Highest score means first pick
-        // Matched scores pick highest games
-
-        for game in &self.games {
-            if game.has_player(id) {
-                for player in game.players() {
-                    let pid = player.id();
-                    let (score, games) = get_mut_from_map(&pid, &mut counts)?;
-                    *games += 1;
-                    if game.winner() == id || game.winner() == pid {
-                        *score -= 1;
-                    } else {
-                        *score += 1;
-                    }
-                }
-            }
-        }
-
-        counts.remove(&id);
-
-        Ok(counts
-            .into_iter()
-            .sorted_by(|(id1, (s1, c1)), (id2, (s2, c2))| {
-                with_tie_breaker(s2.cmp(s1), || with_tie_breaker(c2.cmp(c1), || id1.cmp(id2)))
-            })
-            .map(|(id, _)| id))
+        // Highest score means first pick, the decided games where neither deck won less those
+        // where one did. Matched scores pick highest games.
+        Ok(self.constrained(
+            id,
+            self.players
+                .keys()
+                .filter(|pid| id != **pid && !self.is_player_archived(**pid))
+                .map(|pid| {
+                    let (score, games) = matrix.get(id, *pid).map_or((0, 0), |record| {
+                        let won = i64::from(record.wins + record.opponent_wins);
+                        (i64::from(record.decided) - 2 * won, record.decided)
+                    });
+                    (*pid, (score, games))
+                })
+                .sorted_by(|(id1, (s1, c1)), (id2, (s2, c2))| {
+                    with_tie_breaker(s2.cmp(s1), || with_tie_breaker(c2.cmp(c1), || id1.cmp(id2)))
+                })
+                .map(|(id, _)| id),
+        ))
     }
 
     pub fn rank_elo_neighbors(
@@ -202,59 +206,60 @@ impl Tournament {
         self.ensure_id_registered(id)?;
         let elo = self.get_elo(id);
 
-        Ok(self
-            .players
-            .keys()
-            .filter(|pid| id != **pid)
-            .map(|pid| (*pid, (self.get_elo(*pid) - elo).abs()))
-            .sorted_by(|(i1, d1), (i2, d2)| with_tie_breaker(d1.total_cmp(d2), || i1.cmp(i2)))
-            .map(|(i, _)| i))
+        Ok(self.constrained(
+            id,
+            self.players
+                .keys()
+                .filter(|pid| id != **pid && !self.is_player_archived(**pid))
+                .map(|pid| (*pid, (self.get_elo(*pid) - elo).abs()))
+                .sorted_by(|(i1, d1), (i2, d2)| with_tie_breaker(d1.total_cmp(d2), || i1.cmp(i2)))
+                .map(|(i, _)| i),
+        ))
     }
 
     pub fn rank_wr_neighbors(&self, id: u32) -> Result<impl Iterator<Item = u32>, TournamentError> {
         self.ensure_id_registered(id)?;
         let wr = self.get_wr(id);
 
-        Ok(self
-            .players
-            .keys()
-            .filter(|pid| id != **pid)
-            .map(|pid| (*pid, (self.get_wr(*pid) - wr).abs()))
-            .sorted_by(|(i1, d1), (i2, d2)| with_tie_breaker(d1.total_cmp(d2), || i1.cmp(i2)))
-            .map(|(i, _)| i))
+        Ok(self.constrained(
+            id,
+            self.players
+                .keys()
+                .filter(|pid| id != **pid && !self.is_player_archived(**pid))
+                .map(|pid| (*pid, (self.get_wr(*pid) - wr).abs()))
+                .sorted_by(|(i1, d1), (i2, d2)| with_tie_breaker(d1.total_cmp(d2), || i1.cmp(i2)))
+                .map(|(i, _)| i),
+        ))
     }
 
     pub fn rank_combined(&self, id: u32) -> Result<impl Iterator<Item = u32>, TournamentError> {
-        let scores = chain!(
-            to_weight_rank(
-                self.rank_least_played(id)?,
-                self.config.match_weight_least_played
-            ),
-            to_weight_rank(self.rank_nemesis(id)?, self.config.match_weight_nemesis),
-            to_weight_rank(
-                self.rank_wr_neighbors(id)?,
-                self.config.match_weight_wr_neighbor
-            ),
-            to_weight_rank(
-                self.rank_elo_neighbors(id)?,
-                self.config.match_weight_elo_neighbor
-            ),
-            to_weight_rank(self.rank_loss_with(id)?, self.config.match_weight_lost_with),
-            to_weight_rank(
-                self.rank_expected_neighbors(id)?,
-                self.config.match_weight_expected_neighbor
-            ),
-        );
-
-        Ok(scores
-            .into_grouping_map()
-            .sum()
-            .into_iter()
-            .filter(|(i, _)| *i != id)
-            .sorted_by(|(p1, p1_s), (p2, p2_s)| {
-                with_tie_breaker(p1_s.total_cmp(p2_s), || p1.cmp(p2))
-            })
-            .map(|(pid, _)| pid))
+        Ok(self.constrained(
+            id,
+            self.combined_scores(id, &self.head_to_head())?
+                .into_iter()
+                .sorted_by(|(p1, p1_s), (p2, p2_s)| {
+                    with_tie_breaker(p1_s.total_cmp(p2_s), || p1.cmp(p2))
+                })
+                .map(|(pid, _)| pid),
+        ))
+    }
+
+    /// Weighted sum of each opponent's place in every ranking, lowest being the best pick
+    pub(crate) fn combined_scores(
+        &self,
+        id: u32,
+        head_to_head: &HeadToHeadMatrix,
+    ) -> Result<HashMap<u32, f64>, TournamentError> {
+        let mut scores = HashMap::new();
+        for criterion in CRITERIA {
+            let weight = self.config.match_weight(criterion.id());
+            let ranking = criterion.rank(self, head_to_head, id)?;
+            for (other, score) in to_weight_rank(ranking, weight) {
+                *scores.entry(other).or_insert(0.0) += score;
+            }
+        }
+        scores.remove(&id);
+        Ok(scores)
     }
 }
 
@@ -262,14 +267,15 @@ impl Tournament {
 mod tests {
 
     macro_rules! rank_tests {
-        ($func: ident) => {
+        ($func: ident $(, $extra: ident)?) => {
             mod $func {
                 use crate::Tournament;
                 #[test]
                 fn returns_iterator() {
                     let tournament = Tournament::sample_game();
+                    $(let $extra = tournament.$extra();)?
                     for id in tournament.players.keys() {
-                        let iter = tournament.$func(*id).unwrap();
+                        let iter = tournament.$func(*id $(, &$extra)?).unwrap();
                         assert_eq!(tournament.players.len() - 1, iter.count());
                     }
                 }
@@ -277,8 +283,9 @@ mod tests {
                 #[test]
                 fn does_not_include_self() {
                     let tournament = Tournament::sample_game();
+                    $(let $extra = tournament.$extra();)?
                     for id in tournament.players.keys() {
-                        let mut iter = tournament.$func(*id).unwrap();
+                        let mut iter = tournament.$func(*id $(, &$extra)?).unwrap();
                         assert!(!iter.any(|i| i == *id));
                     }
                 }
@@ -286,11 +293,105 @@ mod tests {
         };
     }
 
+    mod archived {
+        #![allow(clippy::indexing_slicing)]
+
+        use crate::Tournament;
+
+        #[test]
+        fn archived_players_are_not_candidates() {
+            let mut tournament = Tournament::sample_game();
+            let ids = tournament.players.keys().copied().collect::<Vec<_>>();
+            let (archived, id) = (ids[0], ids[1]);
+            tournament.set_player_archived(archived, true).unwrap();
+            let matrix = tournament.head_to_head();
+
+            let rankings: [Vec<u32>; 8] = [
+                tournament.rank_least_played(id).unwrap().collect(),
+                tournament.rank_nemesis(id, &matrix).unwrap().collect(),
+                tournament.rank_kill_nemesis(id).unwrap().collect(),
+                tournament.rank_loss_with(id, &matrix).unwrap().collect(),
+                tournament.rank_elo_neighbors(id).unwrap().collect(),
+                tournament.rank_wr_neighbors(id).unwrap().collect(),
+                tournament.rank_expected_neighbors(id).unwrap().collect(),
+                tournament.rank_combined(id).unwrap().collect(),
+            ];
+            for ranking in rankings {
+                assert_eq!(ids.len() - 2, ranking.len());
+                assert!(!ranking.contains(&archived));
+            }
+        }
+    }
+
     rank_tests!(rank_least_played);
-    rank_tests!(rank_nemesis);
-    rank_tests!(rank_loss_with);
+    rank_tests!(rank_nemesis, head_to_head);
+    rank_tests!(rank_kill_nemesis);
+    rank_tests!(rank_loss_with, head_to_head);
     rank_tests!(rank_elo_neighbors);
     rank_tests!(rank_wr_neighbors);
     rank_tests!(rank_expected_neighbors);
     rank_tests!(rank_combined);
+
+    mod expected_neighbors {
+        #![allow(clippy::indexing_slicing)]
+
+        use itertools::Itertools;
+
+        use crate::{
+            Tournament,
+            game::{entry::GameEntry, outcome::GameOutcome},
+            rating::RatingSystemKind,
+        };
+
+        #[test]
+        fn lopsided_pairing_is_last_for_every_rating_system() {
+            for kind in RatingSystemKind::VALUES {
+                let mut tournament = Tournament::generate_tournament(4, 0).unwrap();
+                let mut config = tournament.config().clone();
+                config.rating_system = kind;
+                tournament.set_config(config).unwrap();
+                let ids = tournament.players.keys().copied().sorted().collect_vec();
+                for _ in 0..6 {
+                    let entry = GameEntry::new(ids.clone(), GameOutcome::Win(ids[0])).unwrap();
+                    tournament.register_entry(entry).unwrap();
+                }
+
+                let ranking = tournament
+                    .rank_expected_neighbors(ids[1])
+                    .unwrap()
+                    .collect_vec();
+                assert_eq!(Some(&ids[0]), ranking.last(), "{kind:?}");
+            }
+        }
+    }
+
+    mod kill_nemesis {
+        #![allow(clippy::indexing_slicing)]
+
+        use itertools::Itertools;
+
+        use crate::{
+            Tournament,
+            game::{
+                elimination::{Elimination, Eliminator},
+                entry::GameEntry,
+                outcome::GameOutcome,
+            },
+        };
+
+        #[test]
+        fn most_kills_against_is_first() {
+            let mut tournament = Tournament::generate_tournament(4, 0).unwrap();
+            let ids = tournament.players.keys().copied().sorted().collect_vec();
+            for (winner, eliminated) in [(ids[3], ids[0]), (ids[3], ids[0]), (ids[2], ids[0])] {
+                let mut entry = GameEntry::new(ids.clone(), GameOutcome::Win(winner)).unwrap();
+                entry.details_mut().eliminations =
+                    vec![Elimination::new(eliminated, Eliminator::Deck(winner))];
+                tournament.register_entry(entry).unwrap();
+            }
+
+            let ranking = tournament.rank_kill_nemesis(ids[0]).unwrap().collect_vec();
+            assert_eq!(vec![ids[3], ids[2], ids[1]], ranking);
+        }
+    }
 }
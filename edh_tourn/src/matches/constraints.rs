@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{Tournament, error::TournamentError, player::info::PlayerInfo};
+
+/// Hard rules for matchmaking, kept with the tournament
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MatchConstraints {
+    /// Pairs of decks that are never put in the same pod, smaller id first
+    #[serde(
+        rename = "x",
+        alias = "exclusions",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    exclusions: Vec<(u32, u32)>,
+    /// Decks that have to be in the next pod made
+    #[serde(
+        rename = "r",
+        alias = "required",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    required: Vec<u32>,
+    /// Whether decks with the same owner are kept out of the same pod
+    #[serde(
+        rename = "o",
+        alias = "one_deck_per_owner",
+        default,
+        skip_serializing_if = "core::ops::Not::not"
+    )]
+    one_deck_per_owner: bool,
+}
+
+impl MatchConstraints {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            exclusions: Vec::new(),
+            required: Vec::new(),
+            one_deck_per_owner: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.exclusions.is_empty() && self.required.is_empty() && !self.one_deck_per_owner
+    }
+
+    #[must_use]
+    pub fn exclusions(&self) -> &[(u32, u32)] {
+        &self.exclusions
+    }
+
+    #[must_use]
+    pub fn required(&self) -> &[u32] {
+        &self.required
+    }
+
+    #[must_use]
+    pub const fn one_deck_per_owner(&self) -> bool {
+        self.one_deck_per_owner
+    }
+
+    pub const fn set_one_deck_per_owner(&mut self, value: bool) {
+        self.one_deck_per_owner = value;
+    }
+
+    /// Keeps the two decks out of the same pod. A deck can't be excluded from itself.
+    pub fn add_exclusion(&mut self, a: u32, b: u32) {
+        let pair = (a.min(b), a.max(b));
+        if a != b && !self.exclusions.contains(&pair) {
+            self.exclusions.push(pair);
+            self.exclusions.sort_unstable();
+        }
+    }
+
+    pub fn remove_exclusion(&mut self, a: u32, b: u32) {
+        let pair = (a.min(b), a.max(b));
+        self.exclusions.retain(|other| *other != pair);
+    }
+
+    #[must_use]
+    pub fn is_excluded(&self, a: u32, b: u32) -> bool {
+        self.exclusions.contains(&(a.min(b), a.max(b)))
+    }
+
+    pub fn set_required(&mut self, id: u32, required: bool) {
+        self.required.retain(|other| *other != id);
+        if required {
+            self.required.push(id);
+            self.required.sort_unstable();
+        }
+    }
+
+    #[must_use]
+    pub fn is_required(&self, id: u32) -> bool {
+        self.required.contains(&id)
+    }
+
+    /// Every deck the constraints mention
+    pub fn ids(&self) -> impl Iterator<Item = u32> {
+        self.exclusions
+            .iter()
+            .flat_map(|(a, b)| [*a, *b])
+            .chain(self.required.iter().copied())
+            .unique()
+    }
+
+    /// Drops every constraint on the deck, such as after it was unregistered
+    pub fn remove_deck(&mut self, id: u32) {
+        self.exclusions.retain(|(a, b)| *a != id && *b != id);
+        self.required.retain(|other| *other != id);
+    }
+
+    /// Copy of the constraints with the deck ids replaced using a map of old to new ids
+    pub(crate) fn map_ids(&self, ids: &HashMap<u32, u32>) -> Result<Self, TournamentError> {
+        let map = |id: u32| {
+            ids.get(&id)
+                .copied()
+                .ok_or(TournamentError::InvalidPlayerId(id))
+        };
+        let mut constraints = Self {
+            one_deck_per_owner: self.one_deck_per_owner,
+            ..Self::new()
+        };
+        for (a, b) in &self.exclusions {
+            constraints.add_exclusion(map(*a)?, map(*b)?);
+        }
+        for id in &self.required {
+            constraints.set_required(map(*id)?, true);
+        }
+        Ok(constraints)
+    }
+}
+
+impl Tournament {
+    #[must_use]
+    pub const fn constraints(&self) -> &MatchConstraints {
+        &self.constraints
+    }
+
+    /// Replaces the matchmaking constraints. Every deck they mention has to be registered.
+    pub fn set_constraints(
+        &mut self,
+        constraints: MatchConstraints,
+    ) -> Result<(), TournamentError> {
+        if let Some(id) = constraints.ids().find(|id| !self.is_id_registered(id)) {
+            return Err(TournamentError::InvalidPlayerId(id));
+        }
+        self.constraints = constraints;
+        self.snapshot += 1;
+        Ok(())
+    }
+
+    /// Whether the constraints allow the two decks in the same pod
+    #[must_use]
+    pub fn can_share_pod(&self, a: u32, b: u32) -> bool {
+        if a == b || self.constraints.is_excluded(a, b) {
+            return false;
+        }
+        if !self.constraints.one_deck_per_owner {
+            return true;
+        }
+        let owner = |id: u32| self.players.get(&id).and_then(PlayerInfo::owner);
+        owner(a).is_none() || owner(a) != owner(b)
+    }
+
+    /// Drops the opponents the constraints keep away from the deck, and moves the required
+    /// decks to the front
+    pub(crate) fn constrained(
+        &self,
+        id: u32,
+        ranking: impl Iterator<Item = u32>,
+    ) -> impl Iterator<Item = u32> {
+        let (required, rest): (Vec<_>, Vec<_>) = ranking
+            .filter(|other| self.can_share_pod(id, *other))
+            .partition(|other| self.constraints.is_required(*other));
+        required.into_iter().chain(rest)
+    }
+
+    /// The deck and the first opponents of the ranking that the constraints allow together,
+    /// until the pod has `size` decks
+    pub fn fill_pod<I: IntoIterator<Item = u32>>(
+        &self,
+        id: u32,
+        ranking: I,
+        size: usize,
+    ) -> Vec<u32> {
+        let mut pod = vec![id];
+        for other in ranking {
+            if pod.len() >= size {
+                break;
+            }
+            if pod.iter().all(|deck| self.can_share_pod(*deck, other)) {
+                pod.push(other);
+            }
+        }
+        pod
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{Tournament, error::TournamentError, matches::constraints::MatchConstraints};
+
+    #[test]
+    fn exclusions_are_unordered() {
+        let mut constraints = MatchConstraints::new();
+        assert!(constraints.is_empty());
+        constraints.add_exclusion(3, 1);
+        constraints.add_exclusion(1, 3);
+        constraints.add_exclusion(2, 2);
+        assert_eq!(&[(1, 3)], constraints.exclusions());
+        assert!(constraints.is_excluded(3, 1));
+
+        constraints.set_required(5, true);
+        constraints.set_required(5, true);
+        assert_eq!(vec![1, 3, 5], constraints.ids().collect_vec());
+
+        constraints.remove_deck(1);
+        constraints.set_required(5, false);
+        assert!(constraints.is_empty());
+    }
+
+    #[test]
+    fn rankings_follow_constraints() {
+        let mut tourn = Tournament::generate_tournament(8, 20).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let alice = tourn.register_pilot("Alice".to_owned()).unwrap();
+        for id in [ids[0], ids[1]] {
+            let mut info = tourn.get_player_info(&id).unwrap().clone();
+            info.set_owner(Some(alice));
+            tourn.set_player_info(id, info).unwrap();
+        }
+
+        let mut constraints = MatchConstraints::new();
+        constraints.add_exclusion(ids[0], ids[2]);
+        constraints.set_required(ids[7], true);
+        constraints.set_one_deck_per_owner(true);
+        tourn.set_constraints(constraints).unwrap();
+
+        let matrix = tourn.head_to_head();
+        let rankings: [Vec<u32>; 8] = [
+            tourn.rank_least_played(ids[0]).unwrap().collect(),
+            tourn.rank_nemesis(ids[0], &matrix).unwrap().collect(),
+            tourn.rank_kill_nemesis(ids[0]).unwrap().collect(),
+            tourn.rank_loss_with(ids[0], &matrix).unwrap().collect(),
+            tourn.rank_elo_neighbors(ids[0]).unwrap().collect(),
+            tourn.rank_wr_neighbors(ids[0]).unwrap().collect(),
+            tourn.rank_expected_neighbors(ids[0]).unwrap().collect(),
+            tourn.rank_combined(ids[0]).unwrap().collect(),
+        ];
+        for ranking in rankings {
+            assert_eq!(5, ranking.len());
+            assert_eq!(ids[7], ranking[0]);
+            assert!(!ranking.contains(&ids[1]) && !ranking.contains(&ids[2]));
+        }
+        // Only decks sharing an owner are kept apart
+        assert_eq!(7, tourn.rank_combined(ids[3]).unwrap().count());
+
+        let pod = tourn.fill_pod(ids[3], [ids[0], ids[1], ids[2], ids[4]], 4);
+        assert_eq!(vec![ids[3], ids[0], ids[4]], pod);
+    }
+
+    #[test]
+    fn constraints_are_saved() {
+        let mut tourn = Tournament::generate_tournament(4, 4).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let mut constraints = MatchConstraints::new();
+        constraints.add_exclusion(ids[0], ids[1]);
+        constraints.set_required(ids[2], true);
+        constraints.set_one_deck_per_owner(true);
+        tourn.set_constraints(constraints).unwrap();
+
+        let ron: Tournament = ron::from_str(&ron::to_string(&tourn).unwrap()).unwrap();
+        assert_eq!(tourn.constraints(), ron.constraints());
+        let fresh = tourn.into_fresh().unwrap();
+        assert_eq!(1, fresh.constraints().exclusions().len());
+
+        tourn.unregister_player(ids[2]).unwrap();
+        assert!(tourn.constraints().required().is_empty());
+    }
+
+    #[test]
+    fn constraints_need_registered_decks() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let mut constraints = MatchConstraints::new();
+        constraints.add_exclusion(0, 99);
+        assert!(matches!(
+            tourn.set_constraints(constraints),
+            Err(TournamentError::InvalidPlayerId(99))
+        ));
+        assert!(tourn.constraints().is_empty());
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{Tournament, error::TournamentError, head_to_head::HeadToHeadMatrix};
+
+/// A way of ranking the opponents of a deck, which the combined ranking weighs against the
+/// others by the weight the config gives its id
+pub trait MatchCriterion: Sync {
+    /// Key of the criterion in the config weights. Changing it loses the saved weight.
+    fn id(&self) -> &'static str;
+
+    /// Name shown in the matchmaker
+    fn name(&self) -> &'static str;
+
+    /// Weight used when the config doesn't set one
+    fn default_weight(&self) -> f64 {
+        0.0
+    }
+
+    /// Opponents of the deck that the constraints allow, best pick first. The head to head
+    /// records are built once by the caller, as they take a walk over the whole history.
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        head_to_head: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError>;
+}
+
+/// Every criterion, in the order the matchmaker lists them. New criteria only need to be added
+/// here to be weighed and picked.
+pub const CRITERIA: [&dyn MatchCriterion; 7] = [
+    &LeastPlayed,
+    &Nemesis,
+    &KillNemesis,
+    &ExpectedNeighbors,
+    &EloNeighbors,
+    &WrNeighbors,
+    &LossWith,
+];
+
+/// The registered criterion with the id
+#[must_use]
+pub fn criterion(id: &str) -> Option<&'static dyn MatchCriterion> {
+    CRITERIA.into_iter().find(|criterion| criterion.id() == id)
+}
+
+/// See [`Tournament::rank_least_played`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LeastPlayed;
+
+impl MatchCriterion for LeastPlayed {
+    fn id(&self) -> &'static str {
+        "least_played"
+    }
+
+    fn name(&self) -> &'static str {
+        "Least Played"
+    }
+
+    fn default_weight(&self) -> f64 {
+        6.0
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        _: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_least_played(id)?.collect())
+    }
+}
+
+/// See [`Tournament::rank_nemesis`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Nemesis;
+
+impl MatchCriterion for Nemesis {
+    fn id(&self) -> &'static str {
+        "nemesis"
+    }
+
+    fn name(&self) -> &'static str {
+        "Nemesis"
+    }
+
+    fn default_weight(&self) -> f64 {
+        4.0
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        head_to_head: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_nemesis(id, head_to_head)?.collect())
+    }
+}
+
+/// See [`Tournament::rank_kill_nemesis`]. Not weighed unless the config asks for it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KillNemesis;
+
+impl MatchCriterion for KillNemesis {
+    fn id(&self) -> &'static str {
+        "kill_nemesis"
+    }
+
+    fn name(&self) -> &'static str {
+        "Nemesis (Kills)"
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        _: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_kill_nemesis(id)?.collect())
+    }
+}
+
+/// See [`Tournament::rank_expected_neighbors`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExpectedNeighbors;
+
+impl MatchCriterion for ExpectedNeighbors {
+    fn id(&self) -> &'static str {
+        "expected_neighbor"
+    }
+
+    fn name(&self) -> &'static str {
+        "Expected Neighbors"
+    }
+
+    fn default_weight(&self) -> f64 {
+        4.0
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        _: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_expected_neighbors(id)?.collect())
+    }
+}
+
+/// See [`Tournament::rank_elo_neighbors`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EloNeighbors;
+
+impl MatchCriterion for EloNeighbors {
+    fn id(&self) -> &'static str {
+        "elo_neighbor"
+    }
+
+    fn name(&self) -> &'static str {
+        "Elo Neighbors"
+    }
+
+    fn default_weight(&self) -> f64 {
+        5.0
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        _: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_elo_neighbors(id)?.collect())
+    }
+}
+
+/// See [`Tournament::rank_wr_neighbors`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WrNeighbors;
+
+impl MatchCriterion for WrNeighbors {
+    fn id(&self) -> &'static str {
+        "wr_neighbor"
+    }
+
+    fn name(&self) -> &'static str {
+        "WR Neighbors"
+    }
+
+    fn default_weight(&self) -> f64 {
+        3.0
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        _: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_wr_neighbors(id)?.collect())
+    }
+}
+
+/// See [`Tournament::rank_loss_with`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LossWith;
+
+impl MatchCriterion for LossWith {
+    fn id(&self) -> &'static str {
+        "lost_with"
+    }
+
+    fn name(&self) -> &'static str {
+        "Loss With"
+    }
+
+    fn default_weight(&self) -> f64 {
+        3.0
+    }
+
+    fn rank(
+        &self,
+        tournament: &Tournament,
+        head_to_head: &HeadToHeadMatrix,
+        id: u32,
+    ) -> Result<Vec<u32>, TournamentError> {
+        Ok(tournament.rank_loss_with(id, head_to_head)?.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        matches::criteria::{CRITERIA, criterion},
+    };
+
+    #[test]
+    fn ids_are_unique_and_found() {
+        assert!(CRITERIA.iter().map(|c| c.id()).all_unique());
+        for registered in CRITERIA {
+            let found = criterion(registered.id()).unwrap();
+            assert_eq!(registered.name(), found.name());
+        }
+        assert!(criterion("missing").is_none());
+    }
+
+    #[test]
+    fn criteria_rank_every_opponent() {
+        let tourn = Tournament::sample_game();
+        let id = *tourn.players().keys().next().unwrap();
+        let head_to_head = tourn.head_to_head();
+        for criterion in CRITERIA {
+            let ranking = criterion.rank(&tourn, &head_to_head, id).unwrap();
+            assert_eq!(tourn.players().len() - 1, ranking.len());
+            assert!(!ranking.contains(&id));
+        }
+    }
+}
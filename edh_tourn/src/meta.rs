@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use crate::{
+    Tournament,
+    player::color::{ColorIdentity, MtgColor},
+};
+
+/// Combined results of every deck in a group
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GroupRecord {
+    /// Registered decks in the group
+    pub decks: u32,
+    /// Games played by decks of the group, a game counting once for each deck in it
+    pub games: u32,
+    pub wins: u32,
+    /// Sum of the current rating of the decks
+    pub rating: f64,
+}
+
+impl GroupRecord {
+    #[must_use]
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.games > 0).then(|| f64::from(self.wins) / f64::from(self.games))
+    }
+
+    #[must_use]
+    pub fn average_rating(&self) -> Option<f64> {
+        (self.decks > 0).then(|| self.rating / f64::from(self.decks))
+    }
+}
+
+/// How decks of one color did in the games they shared with decks of another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColorMatchup {
+    /// Pairs of decks that shared a game, one with each color
+    pub games: u32,
+    /// Shared games won by the deck of the first color
+    pub wins: u32,
+    /// Shared games won by the deck of the second color
+    pub losses: u32,
+}
+
+impl ColorMatchup {
+    /// Share of the games won by either deck that the first color won
+    #[must_use]
+    pub fn win_rate(&self) -> Option<f64> {
+        let decided = self.wins + self.losses;
+        (decided > 0).then(|| f64::from(self.wins) / f64::from(decided))
+    }
+}
+
+/// How the colors of the decks did across the history
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MetaReport {
+    /// Every deck playing the color, whatever else it plays
+    pub by_color: BTreeMap<MtgColor, GroupRecord>,
+    /// Decks grouped by how many colors they play, from colorless to five colors
+    pub by_count: BTreeMap<usize, GroupRecord>,
+    /// Decks grouped by their exact identity, named by its `Display`
+    pub by_identity: BTreeMap<ColorIdentity, GroupRecord>,
+    /// Results between decks of the first color and decks of the second
+    pub matchups: BTreeMap<(MtgColor, MtgColor), ColorMatchup>,
+}
+
+impl MetaReport {
+    #[must_use]
+    pub fn matchup(&self, color: MtgColor, opponent: MtgColor) -> Option<&ColorMatchup> {
+        self.matchups.get(&(color, opponent))
+    }
+}
+
+impl Tournament {
+    /// Records of the decks grouped by color, using the current identity of each deck
+    #[must_use]
+    pub fn meta(&self) -> MetaReport {
+        let mut report = MetaReport::default();
+        let identity = |id: u32| {
+            self.players
+                .get(&id)
+                .map(|info| *info.color_identity())
+                .unwrap_or_default()
+        };
+        let groups =
+            |report: &mut MetaReport, identity: ColorIdentity, apply: &dyn Fn(&mut GroupRecord)| {
+                for color in identity.to_colors() {
+                    apply(report.by_color.entry(color).or_default());
+                }
+                apply(
+                    report
+                        .by_count
+                        .entry(identity.to_colors().count())
+                        .or_default(),
+                );
+                apply(report.by_identity.entry(identity).or_default());
+            };
+
+        for player in self.get_registered_players() {
+            let elo = player.stats().elo();
+            groups(&mut report, *player.info().color_identity(), &|record| {
+                record.decks += 1;
+                record.rating += elo;
+            });
+        }
+
+        for game in &self.games {
+            // Like the players' own records, games without a result aren't counted
+            if game.seat_results().is_none() {
+                continue;
+            }
+            let winner = game.winner();
+            let pod = game.ids().into_iter().unique().collect_vec();
+            for id in &pod {
+                let won = winner == Some(*id);
+                groups(&mut report, identity(*id), &|record| {
+                    record.games += 1;
+                    record.wins += u32::from(won);
+                });
+            }
+
+            for (deck, opponent) in pod.iter().cartesian_product(&pod) {
+                if deck == opponent {
+                    continue;
+                }
+                for (color, opponent_color) in identity(*deck)
+                    .to_colors()
+                    .cartesian_product(identity(*opponent).to_colors().collect_vec())
+                {
+                    let matchup = report.matchups.entry((color, opponent_color)).or_default();
+                    matchup.games += 1;
+                    matchup.wins += u32::from(winner == Some(*deck));
+                    matchup.losses += u32::from(winner == Some(*opponent));
+                }
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        game::{entry::GameEntry, outcome::GameOutcome},
+        player::color::{ColorIdentity, MtgColor},
+    };
+
+    fn colored_tournament() -> (Tournament, Vec<u32>) {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let identities = [
+            vec![MtgColor::White],
+            vec![MtgColor::White, MtgColor::Blue],
+            vec![MtgColor::Red, MtgColor::Green],
+            vec![],
+        ];
+        for (id, colors) in ids.iter().zip(identities) {
+            tourn
+                .players
+                .get_mut(id)
+                .unwrap()
+                .set_color_identity(colors.into_iter().collect());
+        }
+        (tourn, ids)
+    }
+
+    #[test]
+    fn groups_by_color_count_and_identity() {
+        let (mut tourn, ids) = colored_tournament();
+        for winner in [ids[0], ids[1], ids[2]] {
+            tourn
+                .register_entry(GameEntry::new(ids.clone(), GameOutcome::Win(winner)).unwrap())
+                .unwrap();
+        }
+
+        let meta = tourn.meta();
+        let white = meta.by_color[&MtgColor::White];
+        assert_eq!(2, white.decks);
+        assert_eq!(6, white.games);
+        assert_eq!(2, white.wins);
+        assert_relative_eq!(1.0 / 3.0, white.win_rate().unwrap());
+        assert!(!meta.by_color.contains_key(&MtgColor::Black));
+
+        assert_eq!(3, meta.by_count[&0].games);
+        assert_eq!(0, meta.by_count[&0].wins);
+        assert_eq!(2, meta.by_count[&2].decks);
+        assert_eq!(2, meta.by_count[&2].wins);
+
+        let gruul: ColorIdentity = [MtgColor::Red, MtgColor::Green].into_iter().collect();
+        assert_eq!("Gruul", gruul.to_string());
+        assert_eq!(1, meta.by_identity[&gruul].wins);
+        let total = meta.by_identity.values().map(|r| r.games).sum::<u32>();
+        assert_eq!(12, total);
+        let rating = meta.by_identity.values().map(|r| r.rating).sum::<f64>();
+        let elos = tourn.get_registered_players().map(|p| p.stats().elo());
+        assert_relative_eq!(elos.sum::<f64>(), rating, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn games_without_a_result_are_skipped() {
+        let (mut tourn, ids) = colored_tournament();
+        tourn
+            .register_entry(GameEntry::new(ids.clone(), GameOutcome::Win(ids[0])).unwrap())
+            .unwrap();
+        tourn
+            .register_entry(GameEntry::new(ids.clone(), GameOutcome::NoResult).unwrap())
+            .unwrap();
+
+        let meta = tourn.meta();
+        let mono_white = *tourn.players()[&ids[0]].color_identity();
+        let record = meta.by_identity[&mono_white];
+        assert_eq!(1, record.games);
+        assert_relative_eq!(
+            tourn.get_player_or_default_stats(ids[0]).wr().unwrap(),
+            record.win_rate().unwrap()
+        );
+        let white_red = meta.matchup(MtgColor::White, MtgColor::Red).unwrap();
+        assert_eq!(2, white_red.games);
+    }
+
+    #[test]
+    fn matchups_mirror_each_other() {
+        let (mut tourn, ids) = colored_tournament();
+        for winner in [ids[0], ids[2], ids[2]] {
+            tourn
+                .register_entry(GameEntry::new(ids.clone(), GameOutcome::Win(winner)).unwrap())
+                .unwrap();
+        }
+
+        let meta = tourn.meta();
+        let white_red = meta.matchup(MtgColor::White, MtgColor::Red).unwrap();
+        assert_eq!(6, white_red.games);
+        assert_eq!(1, white_red.wins);
+        assert_eq!(4, white_red.losses);
+        assert_relative_eq!(0.2, white_red.win_rate().unwrap());
+        let red_white = meta.matchup(MtgColor::Red, MtgColor::White).unwrap();
+        assert_eq!(white_red.wins, red_white.losses);
+        assert_eq!(white_red.losses, red_white.wins);
+
+        // Azorius against mono white pairs white with itself
+        let white_white = meta.matchup(MtgColor::White, MtgColor::White).unwrap();
+        assert_eq!(6, white_white.games);
+        assert_eq!(white_white.wins, white_white.losses);
+        assert!(meta.matchup(MtgColor::Black, MtgColor::White).is_none());
+    }
+}
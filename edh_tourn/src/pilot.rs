@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use crate::{
+    Tournament,
+    error::TournamentError,
+    game::{outcome::SeatResult, record::GameRecord},
+};
+
+/// A person that owns or pilots decks
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq, Eq)]
+pub struct PilotInfo {
+    #[serde(rename = "n", alias = "name")]
+    name: String,
+}
+
+impl PilotInfo {
+    #[must_use]
+    pub const fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Results of the games a pilot sat down for. The rating starts at the starting elo of the
+/// tournament and moves by the elo their decks gained or lost in those games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PilotStats {
+    games: u32,
+    wins: u32,
+    draws: u32,
+    rating: f64,
+}
+
+impl PilotStats {
+    const fn new(rating: f64) -> Self {
+        Self {
+            games: 0,
+            wins: 0,
+            draws: 0,
+            rating,
+        }
+    }
+
+    #[must_use]
+    pub const fn games(&self) -> u32 {
+        self.games
+    }
+
+    #[must_use]
+    pub const fn wins(&self) -> u32 {
+        self.wins
+    }
+
+    #[must_use]
+    pub const fn draws(&self) -> u32 {
+        self.draws
+    }
+
+    #[must_use]
+    pub fn wr(&self) -> Option<f64> {
+        (self.games > 0).then(|| f64::from(self.wins) / f64::from(self.games))
+    }
+
+    #[must_use]
+    pub const fn rating(&self) -> f64 {
+        self.rating
+    }
+}
+
+impl Tournament {
+    #[must_use]
+    pub const fn pilots(&self) -> &HashMap<u32, PilotInfo> {
+        &self.pilots
+    }
+
+    #[must_use]
+    pub fn get_pilot_info(&self, id: u32) -> Option<&PilotInfo> {
+        self.pilots.get(&id)
+    }
+
+    #[must_use]
+    pub fn get_pilot_id(&self, name: &str) -> Option<u32> {
+        self.pilots
+            .iter()
+            .find(|(_, info)| info.name() == name)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn register_pilot(&mut self, name: String) -> Result<u32, TournamentError> {
+        if name.is_empty() {
+            return Err(TournamentError::InvalidPilotName(name));
+        }
+
+        if let Some(id) = self.get_pilot_id(&name) {
+            return Err(TournamentError::PilotAlreadyRegistered(name, id));
+        }
+
+        let id = self.next_pilot_id();
+        self.pilots.insert(id, PilotInfo::new(name));
+        self.snapshot += 1;
+
+        Ok(id)
+    }
+
+    /// Id the next registered pilot is given
+    #[must_use]
+    pub fn next_pilot_id(&self) -> u32 {
+        self.pilots.keys().max().map_or(0, |i| i + 1)
+    }
+
+    pub fn get_or_register_pilot(&mut self, name: String) -> Result<u32, TournamentError> {
+        match self.register_pilot(name) {
+            Ok(id) | Err(TournamentError::PilotAlreadyRegistered(_, id)) => Ok(id),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Removes a pilot, along with any deck ownership and game overrides that point to them
+    pub fn unregister_pilot(&mut self, id: u32) -> Result<(), TournamentError> {
+        self.pilots
+            .remove(&id)
+            .ok_or(TournamentError::InvalidPilotId(id))?;
+
+        for info in self.players.values_mut() {
+            if info.owner() == Some(id) {
+                info.set_owner(None);
+            }
+        }
+        for game in &mut self.games {
+            game.details_mut().pilots.retain(|_, pilot| *pilot != id);
+        }
+        self.snapshot += 1;
+
+        Ok(())
+    }
+
+    /// Registers a pilot under a known id as the owner of `decks`, and as the pilot of each deck in
+    /// `games` given by game index. Nothing changes if any of them can't be found.
+    pub(crate) fn restore_pilot(
+        &mut self,
+        id: u32,
+        info: PilotInfo,
+        decks: &[u32],
+        games: &[(usize, u32)],
+    ) -> Result<(), TournamentError> {
+        if self.pilots.contains_key(&id) {
+            return Err(TournamentError::InvalidPilotId(id));
+        }
+        if let Some(old_id) = self.get_pilot_id(info.name()) {
+            return Err(TournamentError::PilotAlreadyRegistered(
+                info.name().to_owned(),
+                old_id,
+            ));
+        }
+        if let Some(deck) = decks.iter().find(|deck| !self.players.contains_key(deck)) {
+            return Err(TournamentError::InvalidPlayerId(*deck));
+        }
+        for (gid, deck) in games {
+            let game = self
+                .games
+                .get(*gid)
+                .ok_or(TournamentError::GameNotFound(*gid))?;
+            if !game.has_player(*deck) {
+                return Err(TournamentError::PlayerNotInMatch(*deck));
+            }
+        }
+
+        self.pilots.insert(id, info);
+        for deck in decks {
+            if let Some(info) = self.players.get_mut(deck) {
+                info.set_owner(Some(id));
+            }
+        }
+        for (gid, deck) in games {
+            if let Some(game) = self.games.get_mut(*gid) {
+                game.details_mut().pilots.insert(*deck, id);
+            }
+        }
+        self.snapshot += 1;
+        Ok(())
+    }
+
+    pub(crate) fn ensure_pilot(&self, id: u32) -> Result<(), TournamentError> {
+        if self.pilots.contains_key(&id) {
+            Ok(())
+        } else {
+            Err(TournamentError::InvalidPilotId(id))
+        }
+    }
+
+    /// Pilot of each seat of a game: the pilot the deck was lent to in that game, or else the
+    /// deck's owner
+    #[must_use]
+    pub fn seat_pilots(&self, game: &GameRecord) -> Vec<Option<u32>> {
+        game.players()
+            .iter()
+            .map(|player| {
+                game.details()
+                    .pilots
+                    .get(&player.id())
+                    .copied()
+                    .or_else(|| self.get_player_info(&player.id())?.owner())
+            })
+            .collect()
+    }
+
+    pub fn get_pilot_stats(&self, id: u32) -> Result<PilotStats, TournamentError> {
+        self.get_all_pilot_stats()
+            .remove(&id)
+            .ok_or(TournamentError::InvalidPilotId(id))
+    }
+
+    /// Stats of every registered pilot
+    #[must_use]
+    pub fn get_all_pilot_stats(&self) -> HashMap<u32, PilotStats> {
+        let mut all_stats = self
+            .pilots
+            .keys()
+            .map(|id| (*id, PilotStats::new(self.config.starting_elo)))
+            .collect::<HashMap<_, _>>();
+
+        for game in &self.games {
+            let (Some(results), Some(scores)) = (game.seat_results(), game.seat_scores()) else {
+                continue;
+            };
+
+            let seat_data = game
+                .players()
+                .iter()
+                .zip(self.seat_pilots(game))
+                .zip(results.into_iter().zip(scores));
+            for ((player, pilot), (result, score)) in seat_data {
+                let Some(pilot) = pilot else {
+                    continue;
+                };
+
+                let Some(stats) = all_stats.get_mut(&pilot) else {
+                    continue;
+                };
+                stats.games += 1;
+                match result {
+                    SeatResult::Win => stats.wins += 1,
+                    SeatResult::Draw(_) => stats.draws += 1,
+                    SeatResult::Loss => {}
+                }
+                stats.rating += player.elo_change(score);
+            }
+        }
+
+        all_stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament, error::TournamentError, game::entry::GameEntry, game::outcome::GameOutcome,
+    };
+
+    #[test]
+    fn register_duplicate_pilot() {
+        let mut t = Tournament::new();
+        let id = t.register_pilot("Alice".to_owned()).unwrap();
+        assert!(matches!(
+            t.register_pilot("Alice".to_owned()),
+            Err(TournamentError::PilotAlreadyRegistered(_, i)) if i == id
+        ));
+        assert_eq!(id, t.get_or_register_pilot("Alice".to_owned()).unwrap());
+        t.register_pilot(String::new()).unwrap_err();
+    }
+
+    #[test]
+    fn pilot_stats_follow_owner_and_overrides() {
+        let mut t = Tournament::generate_tournament(4, 0).unwrap();
+        let decks = t.players().keys().copied().sorted().collect_vec();
+        let alice = t.register_pilot("Alice".to_owned()).unwrap();
+        let bob = t.register_pilot("Bob".to_owned()).unwrap();
+        t.set_deck_owner(decks[0], Some(alice)).unwrap();
+        t.set_deck_owner(decks[1], Some(alice)).unwrap();
+
+        t.register_entry(GameEntry::new(decks.clone(), GameOutcome::Win(decks[0])).unwrap())
+            .unwrap();
+        let mut entry = GameEntry::new(decks.clone(), GameOutcome::Win(decks[1])).unwrap();
+        entry.details_mut().pilots.insert(decks[1], bob);
+        t.register_entry(entry).unwrap();
+
+        let alice_stats = t.get_pilot_stats(alice).unwrap();
+        assert_eq!((3, 1), (alice_stats.games(), alice_stats.wins()));
+        let bob_stats = t.get_pilot_stats(bob).unwrap();
+        assert_eq!((1, 1), (bob_stats.games(), bob_stats.wins()));
+        assert!(bob_stats.rating() > t.default_stats().elo());
+    }
+
+    #[test]
+    fn unregister_pilot_clears_links() {
+        let mut t = Tournament::generate_tournament(4, 0).unwrap();
+        let decks = t.players().keys().copied().sorted().collect_vec();
+        let alice = t.register_pilot("Alice".to_owned()).unwrap();
+        t.set_deck_owner(decks[0], Some(alice)).unwrap();
+        let mut entry = GameEntry::new(decks.clone(), GameOutcome::Win(decks[1])).unwrap();
+        entry.details_mut().pilots.insert(decks[1], alice);
+        t.register_entry(entry).unwrap();
+
+        t.unregister_pilot(alice).unwrap();
+        assert_eq!(None, t.get_player_info(&decks[0]).unwrap().owner());
+        assert!(t.games()[0].details().pilots.is_empty());
+        t.set_deck_owner(decks[0], Some(alice)).unwrap_err();
+    }
+
+    #[test]
+    fn game_pilot_must_be_registered() {
+        let mut t = Tournament::generate_tournament(4, 0).unwrap();
+        let decks = t.players().keys().copied().sorted().collect_vec();
+        let mut entry = GameEntry::new(decks.clone(), GameOutcome::Win(decks[1])).unwrap();
+        entry.details_mut().pilots.insert(decks[1], 7);
+        t.register_entry(entry).unwrap_err();
+    }
+}
@@ -0,0 +1,319 @@
+use core::{cmp::Reverse, iter::repeat_n};
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{
+    Tournament,
+    error::TournamentError,
+    game::{DEFAULT_POD_SIZE, MAX_POD_SIZE, MIN_POD_SIZE},
+};
+
+/// Most swaps tried before settling on the plan
+const MAX_SWAPS: usize = 1000;
+/// Cost of putting two decks the constraints keep apart in the same pod, far above any score
+const CONFLICT_COST: f64 = 1e6;
+
+/// One pod of the plan, with how poorly its decks match each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPod {
+    pub ids: Vec<u32>,
+    /// Sum of the combined match scores between every two decks in the pod, lower is better
+    pub cost: f64,
+}
+
+/// Every attending deck split into pods, as close to the usual pod size as the count allows
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PodPlan {
+    pub pods: Vec<PlannedPod>,
+}
+
+impl PodPlan {
+    #[must_use]
+    pub fn cost(&self) -> f64 {
+        self.pods.iter().map(|pod| pod.cost).sum()
+    }
+}
+
+/// How poorly two decks match, each deck's combined score for the other added together
+struct PairCosts {
+    costs: HashMap<(u32, u32), f64>,
+}
+
+impl PairCosts {
+    fn get(&self, a: u32, b: u32) -> f64 {
+        self.costs
+            .get(&(a.min(b), a.max(b)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn pod(&self, pod: &[u32]) -> f64 {
+        pod.iter()
+            .tuple_combinations()
+            .map(|(a, b)| self.get(*a, *b))
+            .sum()
+    }
+
+    /// Cost added by putting `id` in `pod`
+    fn joining(&self, pod: &[u32], id: u32) -> f64 {
+        pod.iter().map(|other| self.get(*other, id)).sum()
+    }
+}
+
+/// Sizes of the pods `count` decks split into, largest first. Of the ways to split the decks
+/// evenly into pods of allowed sizes, picks the one with the fewest seats away from the usual
+/// pod size, and then the one with more, smaller pods. Returns `None` when there are too few
+/// decks for a pod.
+fn pod_sizes(count: usize) -> Option<Vec<usize>> {
+    (1..=count / MIN_POD_SIZE)
+        .filter(|pods| count <= pods * MAX_POD_SIZE)
+        .map(|pods| {
+            let (size, larger) = (count / pods, count % pods);
+            repeat_n(size + 1, larger)
+                .chain(repeat_n(size, pods - larger))
+                .collect_vec()
+        })
+        .min_by_key(|sizes| {
+            let off = sizes
+                .iter()
+                .map(|size| size.abs_diff(DEFAULT_POD_SIZE))
+                .sum::<usize>();
+            (off, Reverse(sizes.len()))
+        })
+}
+
+fn swapped(pod: &[u32], from: u32, to: u32) -> Vec<u32> {
+    pod.iter()
+        .map(|id| if *id == from { to } else { *id })
+        .collect()
+}
+
+impl Tournament {
+    /// Splits the attending decks into pods using the matchmaking weights of the config, so the
+    /// decks in each pod are good picks for each other. Decks are first placed greedily, then
+    /// swapped between pods while a swap lowers the total cost. Only attending decks are
+    /// planned, required or not, and the plan fails if the constraints can't be met.
+    pub fn plan_pods(&self, attending: &[u32]) -> Result<PodPlan, TournamentError> {
+        if let Some(id) = attending.iter().duplicates().next() {
+            return Err(TournamentError::DuplicatePlayer(*id));
+        }
+        if attending.len() < 3 {
+            return Err(TournamentError::NotEnoughPlayers);
+        }
+        let sizes = pod_sizes(attending.len())
+            .ok_or(TournamentError::CannotSplitIntoPods(attending.len()))?;
+
+        let mut costs = PairCosts {
+            costs: HashMap::new(),
+        };
+        let head_to_head = self.head_to_head();
+        for id in attending {
+            let scores = self.combined_scores(*id, &head_to_head)?;
+            // Retired decks aren't ranked, so they count as the worst pick
+            let worst = scores.values().copied().fold(0.0, f64::max);
+            for other in attending.iter().filter(|other| *other != id) {
+                let conflict = if self.can_share_pod(*id, *other) {
+                    0.0
+                } else {
+                    CONFLICT_COST / 2.0
+                };
+                *costs
+                    .costs
+                    .entry((*id.min(other), *id.max(other)))
+                    .or_default() += scores.get(other).copied().unwrap_or(worst) + conflict;
+            }
+        }
+
+        let mut unplaced = attending.iter().copied().sorted().collect_vec();
+        let mut pods: Vec<Vec<u32>> = Vec::with_capacity(sizes.len());
+        for size in sizes {
+            let mut pod = Vec::with_capacity(size);
+            while pod.len() < size {
+                let Some((index, _)) = unplaced.iter().enumerate().min_by(|(_, a), (_, b)| {
+                    costs
+                        .joining(&pod, **a)
+                        .total_cmp(&costs.joining(&pod, **b))
+                }) else {
+                    break;
+                };
+                pod.push(unplaced.remove(index));
+            }
+            pods.push(pod);
+        }
+
+        for _ in 0..MAX_SWAPS {
+            let best = pods
+                .iter()
+                .enumerate()
+                .tuple_combinations()
+                .flat_map(|((a, pod_a), (b, pod_b))| {
+                    let before = costs.pod(pod_a) + costs.pod(pod_b);
+                    let costs = &costs;
+                    pod_a.iter().cartesian_product(pod_b).map(move |(x, y)| {
+                        let after =
+                            costs.pod(&swapped(pod_a, *x, *y)) + costs.pod(&swapped(pod_b, *y, *x));
+                        (a, b, *x, *y, before - after)
+                    })
+                })
+                .max_by(|(.., gain_a), (.., gain_b)| gain_a.total_cmp(gain_b));
+            let Some((a, b, x, y, _)) = best.filter(|(.., gain)| *gain > 1e-9) else {
+                break;
+            };
+            for (pod, from, to) in [(a, x, y), (b, y, x)] {
+                if let Some(pod) = pods.get_mut(pod) {
+                    *pod = swapped(pod, from, to);
+                }
+            }
+        }
+
+        let conflict = pods.iter().any(|pod| {
+            pod.iter()
+                .tuple_combinations()
+                .any(|(a, b)| !self.can_share_pod(*a, *b))
+        });
+        if conflict {
+            return Err(TournamentError::UnsatisfiableConstraints);
+        }
+
+        Ok(PodPlan {
+            pods: pods
+                .into_iter()
+                .map(|ids| PlannedPod {
+                    cost: costs.pod(&ids),
+                    ids,
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        error::TournamentError,
+        game::{MAX_POD_SIZE, MIN_POD_SIZE},
+        matches::constraints::MatchConstraints,
+        planner::pod_sizes,
+    };
+
+    #[test]
+    fn sizes_stay_close_to_four() {
+        assert_eq!(Some(vec![3]), pod_sizes(3));
+        assert_eq!(Some(vec![4]), pod_sizes(4));
+        assert_eq!(Some(vec![5]), pod_sizes(5));
+        assert_eq!(Some(vec![3, 3]), pod_sizes(6));
+        assert_eq!(Some(vec![4, 3]), pod_sizes(7));
+        assert_eq!(Some(vec![5, 4]), pod_sizes(9));
+        assert_eq!(Some(vec![5, 4, 4]), pod_sizes(13));
+        assert_eq!(Some(vec![4, 4, 3, 3]), pod_sizes(14));
+        assert_eq!(Some(vec![4, 4, 4, 4]), pod_sizes(16));
+        assert_eq!(None, pod_sizes(2));
+        for count in MIN_POD_SIZE..100 {
+            let sizes = pod_sizes(count).unwrap();
+            assert_eq!(count, sizes.iter().sum::<usize>());
+            assert!(
+                sizes
+                    .iter()
+                    .all(|size| (MIN_POD_SIZE..=MAX_POD_SIZE).contains(size))
+            );
+        }
+    }
+
+    #[test]
+    fn plan_places_every_deck_once() {
+        let tourn = Tournament::generate_tournament(16, 60).unwrap();
+        let attending = tourn
+            .players()
+            .keys()
+            .copied()
+            .sorted()
+            .take(14)
+            .collect_vec();
+        let plan = tourn.plan_pods(&attending).unwrap();
+
+        assert_eq!(
+            vec![4, 4, 3, 3],
+            plan.pods.iter().map(|pod| pod.ids.len()).collect_vec()
+        );
+        let placed = plan
+            .pods
+            .iter()
+            .flat_map(|pod| pod.ids.iter().copied())
+            .sorted()
+            .collect_vec();
+        assert_eq!(attending, placed);
+
+        // The plan should do at least as well as seating the decks in order
+        let mut in_order = attending;
+        in_order.reverse();
+        let naive = [4, 4, 3, 3]
+            .map(|size| in_order.split_off(in_order.len() - size))
+            .iter()
+            .map(|pod| tourn.plan_pods(pod).unwrap().cost())
+            .sum::<f64>();
+        assert!(plan.cost() <= naive + 1e-9);
+    }
+
+    #[test]
+    fn plan_follows_constraints() {
+        let mut tourn = Tournament::generate_tournament(8, 30).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let mut constraints = MatchConstraints::new();
+        for other in &ids[1..4] {
+            constraints.add_exclusion(ids[0], *other);
+        }
+        constraints.set_required(ids[7], true);
+        tourn.set_constraints(constraints).unwrap();
+
+        // The required deck isn't attending, so it is left out
+        let plan = tourn.plan_pods(&ids[..7]).unwrap();
+        let with_first = plan
+            .pods
+            .iter()
+            .find(|pod| pod.ids.contains(&ids[0]))
+            .unwrap();
+        assert_eq!(4, with_first.ids.len());
+        assert!(with_first.ids.iter().all(|id| !ids[1..4].contains(id)));
+        assert_eq!(
+            ids[..7],
+            plan.pods
+                .iter()
+                .flat_map(|pod| pod.ids.clone())
+                .sorted()
+                .collect_vec()
+        );
+
+        assert!(matches!(
+            tourn.plan_pods(&ids[..3]),
+            Err(TournamentError::UnsatisfiableConstraints)
+        ));
+    }
+
+    #[test]
+    fn invalid_attendance_is_rejected() {
+        let tourn = Tournament::generate_tournament(8, 10).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+
+        let plan = tourn.plan_pods(&ids[..5]).unwrap();
+        assert_eq!(1, plan.pods.len());
+        assert_eq!(5, plan.pods[0].ids.len());
+        assert!(matches!(
+            tourn.plan_pods(&ids[..2]),
+            Err(TournamentError::NotEnoughPlayers)
+        ));
+        assert!(matches!(
+            tourn.plan_pods(&[ids[0], ids[1], ids[2], ids[0]]),
+            Err(TournamentError::DuplicatePlayer(id)) if id == ids[0]
+        ));
+        assert!(matches!(
+            tourn.plan_pods(&[ids[0], ids[1], 99]),
+            Err(TournamentError::InvalidPlayerId(99))
+        ));
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
 };
 
 pub mod color;
+pub mod history;
 pub mod info;
 pub mod stats;
 
@@ -49,6 +50,12 @@ impl Tournament {
         Ok(RegisteredPlayer { id, info, stats })
     }
 
+    /// Registered players that haven't been retired
+    pub fn get_active_players(&self) -> impl Iterator<Item = RegisteredPlayer<'_>> {
+        self.get_registered_players()
+            .filter(|player| !player.info.is_archived())
+    }
+
     pub fn get_registered_players(&self) -> impl Iterator<Item = RegisteredPlayer<'_>> {
         self.players.iter().map(|(id, info)| RegisteredPlayer {
             id: *id,
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{Tournament, error::TournamentError, game::outcome::GameOutcome};
+
+/// A deck's rating around one of its games
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RatingPoint {
+    /// Index of the game in the history
+    pub game: usize,
+    /// When the game was played, in seconds since the Unix epoch, if it was recorded
+    pub played_at: Option<u64>,
+    pub before: f64,
+    pub after: f64,
+    /// Place on the leaderboard before the game, starting at 1
+    pub rank_before: usize,
+    /// Place on the leaderboard after the game, starting at 1
+    pub rank_after: usize,
+}
+
+impl RatingPoint {
+    #[must_use]
+    pub fn change(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+impl Tournament {
+    /// Rating of the deck around each game it played, oldest first
+    pub fn rating_history(&self, id: u32) -> Result<Vec<RatingPoint>, TournamentError> {
+        if !self.is_id_registered(&id) {
+            return Err(TournamentError::InvalidPlayerId(id));
+        }
+        Ok(self.rating_histories().remove(&id).unwrap_or_default())
+    }
+
+    /// Rating of every deck around each game it played, oldest first. Like the stats, games
+    /// without a result are left out. Ranks are on the leaderboard as it stood then, see
+    /// [`Tournament::leaderboard`], with decks that haven't played yet at the starting rating.
+    #[must_use]
+    pub fn rating_histories(&self) -> HashMap<u32, Vec<RatingPoint>> {
+        let starting_elo = self.default_stats().elo();
+        let mut ratings = self
+            .players
+            .keys()
+            .map(|id| (*id, starting_elo))
+            .collect::<HashMap<_, _>>();
+        let mut histories: HashMap<u32, Vec<RatingPoint>> = HashMap::new();
+
+        for (index, game) in self.games.iter().enumerate() {
+            if matches!(game.outcome(), GameOutcome::NoResult) {
+                continue;
+            }
+            let pod = game.ids().into_iter().unique().collect_vec();
+            let mut points = Vec::with_capacity(pod.len());
+            for id in &pod {
+                let before = game
+                    .get_player(*id)
+                    .map_or(starting_elo, |player| player.stats().elo());
+                ratings.insert(*id, before);
+                points.push((*id, before));
+            }
+            let ranks_before = points
+                .iter()
+                .map(|(id, before)| self.leaderboard_rank(&ratings, *id, *before))
+                .collect_vec();
+
+            for (id, before) in &points {
+                let after = before + game.get_player_elo_change(*id).unwrap_or_default();
+                ratings.insert(*id, after);
+            }
+
+            for ((id, before), rank_before) in points.into_iter().zip(ranks_before) {
+                let after = ratings.get(&id).copied().unwrap_or(before);
+                histories.entry(id).or_default().push(RatingPoint {
+                    game: index,
+                    played_at: game.details().played_at(),
+                    before,
+                    after,
+                    rank_before,
+                    rank_after: self.leaderboard_rank(&ratings, id, after),
+                });
+            }
+        }
+        histories
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{Tournament, error::TournamentError, game::outcome::GameOutcome};
+
+    #[test]
+    fn history_ends_at_current_stats() {
+        let tourn = Tournament::generate_tournament(7, 40).unwrap();
+        let leaderboard = tourn.leaderboard();
+        for (id, history) in tourn.rating_histories() {
+            let stats = tourn.get_player_stats(id).unwrap();
+            assert_eq!(stats.games() as usize, history.len());
+            let last = history.last().unwrap();
+            assert_relative_eq!(stats.elo(), last.after, epsilon = 1e-9);
+            if last.game == tourn.games().len() - 1 {
+                let rank = leaderboard.iter().position(|deck| *deck == id).unwrap() + 1;
+                assert_eq!(rank, last.rank_after);
+            }
+
+            for (previous, next) in history.iter().tuple_windows() {
+                assert!(previous.game < next.game);
+                assert_relative_eq!(previous.after, next.before, epsilon = 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn no_result_adds_no_point() {
+        let mut tourn = Tournament::generate_tournament(4, 6).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let record = tourn
+            .create_match(&ids)
+            .unwrap()
+            .record_outcome(GameOutcome::NoResult)
+            .unwrap();
+        tourn.register_record(record).unwrap();
+
+        for id in ids {
+            let history = tourn.rating_history(id).unwrap();
+            let stats = tourn.get_player_stats(id).unwrap();
+            assert_eq!(stats.games() as usize, history.len());
+            assert!(history.iter().all(|point| point.game < 6));
+        }
+    }
+
+    #[test]
+    fn ranks_follow_the_leaderboard() {
+        let mut tourn = Tournament::generate_tournament(6, 30).unwrap();
+        let leaderboard = tourn.leaderboard();
+        let (retired, kept) = (leaderboard[0], leaderboard[1]);
+        let mut info = tourn.get_player_info(&retired).unwrap().clone();
+        info.set_archived(true);
+        tourn.set_player_info(retired, info).unwrap();
+
+        // The retired deck no longer counts for the others, but still gets a place itself
+        let last = |id: u32| *tourn.rating_history(id).unwrap().last().unwrap();
+        let final_game = tourn.games().len() - 1;
+        if last(kept).game == final_game {
+            assert_eq!(1, last(kept).rank_after);
+        }
+        if last(retired).game == final_game {
+            assert_eq!(1, last(retired).rank_after);
+        }
+
+        // Decks that never played tie at the starting rating and are ordered by name
+        let mut tourn = Tournament::new();
+        for name in ["C", "A", "B", "D"] {
+            tourn.register_player(name.to_owned()).unwrap();
+        }
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let record = tourn.create_match(&ids).unwrap().record(ids[2]).unwrap();
+        tourn.register_record(record).unwrap();
+        let place = |id: u32| tourn.rating_history(id).unwrap()[0];
+        // Before the game: A (ids[1]), B (ids[2]), C (ids[0]), D (ids[3])
+        assert_eq!(
+            vec![3, 1, 2, 4],
+            ids.iter().map(|id| place(*id).rank_before).collect_vec()
+        );
+        assert_eq!(1, place(ids[2]).rank_after);
+        let mut after = ids.iter().map(|id| place(*id).rank_after).collect_vec();
+        after.sort_unstable();
+        assert_eq!(vec![1, 2, 3, 4], after);
+    }
+
+    #[test]
+    fn first_win_takes_the_lead() {
+        let tourn = Tournament::generate_tournament(4, 1).unwrap();
+        let winner = tourn.games()[0].winner().unwrap();
+        let history = tourn.rating_history(winner).unwrap();
+        assert_eq!(1, history.len());
+        assert_eq!(1, history[0].rank_before);
+        assert_eq!(1, history[0].rank_after);
+        assert!(history[0].change() > 0.0);
+
+        let loser = tourn.players().keys().find(|id| **id != winner).unwrap();
+        let place = tourn
+            .leaderboard()
+            .iter()
+            .position(|id| id == loser)
+            .unwrap()
+            + 1;
+        assert!(place > 1);
+        assert_eq!(place, tourn.rating_history(*loser).unwrap()[0].rank_after);
+        assert!(matches!(
+            tourn.rating_history(99),
+            Err(TournamentError::InvalidPlayerId(99))
+        ));
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use crate::{
     Tournament,
     error::TournamentError,
@@ -29,6 +31,22 @@ pub struct PlayerInfo {
         alias = "moxfield_id"
     )]
     moxfield_id: Option<String>,
+    /// Pilot that owns the deck
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        rename = "o",
+        alias = "owner"
+    )]
+    owner: Option<u32>,
+    /// Retired decks keep their games but are left out of leaderboards and matchmaking
+    #[serde(
+        skip_serializing_if = "core::ops::Not::not",
+        default,
+        rename = "a",
+        alias = "archived"
+    )]
+    archived: bool,
 }
 
 impl PlayerInfo {
@@ -38,6 +56,8 @@ impl PlayerInfo {
             description: String::new(),
             identity: ColorIdentity(0),
             moxfield_id: None,
+            owner: None,
+            archived: false,
         }
     }
 
@@ -92,6 +112,39 @@ impl PlayerInfo {
         }
     }
 
+    #[must_use]
+    pub const fn is_archived(&self) -> bool {
+        self.archived
+    }
+
+    pub const fn set_archived(&mut self, archived: bool) {
+        self.archived = archived;
+    }
+
+    #[must_use]
+    pub const fn owner(&self) -> Option<u32> {
+        self.owner
+    }
+
+    pub const fn set_owner(&mut self, owner: Option<u32>) {
+        self.owner = owner;
+    }
+
+    /// Copy of the info with the owner replaced using a map of old to new pilot ids
+    pub(crate) fn map_owner(&self, pilots: &HashMap<u32, u32>) -> Result<Self, TournamentError> {
+        let mut info = self.clone();
+        info.owner = self
+            .owner
+            .map(|id| {
+                pilots
+                    .get(&id)
+                    .copied()
+                    .ok_or(TournamentError::InvalidPilotId(id))
+            })
+            .transpose()?;
+        Ok(info)
+    }
+
     #[must_use]
     pub const fn color_identity(&self) -> &ColorIdentity {
         &self.identity
@@ -139,6 +192,10 @@ impl Tournament {
             return Err(TournamentError::PlayerAlreadyRegistered(info.name, *id));
         }
 
+        if let Some(owner) = info.owner {
+            self.ensure_pilot(owner)?;
+        }
+
         let id = self.players.keys().max().map_or(0, |i| i + 1);
 
         self.player_names.insert(info.name.clone(), id);
@@ -152,6 +209,10 @@ impl Tournament {
         player: u32,
         info: PlayerInfo,
     ) -> Result<(), TournamentError> {
+        if let Some(owner) = info.owner {
+            self.ensure_pilot(owner)?;
+        }
+
         let saved_info = self
             .players
             .get_mut(&player)
@@ -178,6 +239,34 @@ impl Tournament {
         Ok(())
     }
 
+    pub fn set_deck_owner(&mut self, deck: u32, owner: Option<u32>) -> Result<(), TournamentError> {
+        if let Some(owner) = owner {
+            self.ensure_pilot(owner)?;
+        }
+        self.players
+            .get_mut(&deck)
+            .ok_or(TournamentError::InvalidPlayerId(deck))?
+            .set_owner(owner);
+        self.snapshot += 1;
+        Ok(())
+    }
+
+    /// Retires or restores a deck. Its games and their effect on ratings are kept either way.
+    pub fn set_player_archived(&mut self, id: u32, archived: bool) -> Result<(), TournamentError> {
+        self.players
+            .get_mut(&id)
+            .ok_or(TournamentError::InvalidPlayerId(id))?
+            .set_archived(archived);
+        self.snapshot += 1;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn is_player_archived(&self, id: u32) -> bool {
+        self.get_player_info(&id)
+            .is_some_and(PlayerInfo::is_archived)
+    }
+
     #[must_use]
     pub fn get_player_info(&self, id: &u32) -> Option<&PlayerInfo> {
         self.players().get(id)
@@ -222,6 +311,27 @@ mod tests {
         assert!(matches!(res, Err(TournamentError::InvalidPlayerName(_))));
     }
 
+    #[test]
+    fn archive_keeps_games_and_stats() {
+        let mut t = Tournament::sample_game();
+        let id = *t.players().keys().next().unwrap();
+        let stats = t.get_player_stats(id).cloned();
+        let games = t.games().len();
+
+        t.set_player_archived(id, true).unwrap();
+        assert!(t.is_player_archived(id));
+        assert_eq!(games, t.games().len());
+        assert_eq!(stats.as_ref(), t.get_player_stats(id));
+        assert!(t.get_active_players().all(|p| p.id() != id));
+
+        let t: Tournament = ron::from_str(&ron::to_string(&t).unwrap()).unwrap();
+        assert!(t.is_player_archived(id));
+
+        let mut t = t;
+        t.set_player_archived(id, false).unwrap();
+        assert!(t.get_active_players().any(|p| p.id() == id));
+    }
+
     #[test]
     fn register_invalid_name() {
         let mut t = Tournament::new();
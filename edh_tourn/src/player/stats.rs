@@ -1,11 +1,22 @@
 use crate::Tournament;
 
+/// Deviations below the rating that [`PlayerStats::conservative_rating`] reports
+pub const CONSERVATIVE_DEVIATIONS: f64 = 2.0;
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
 pub struct PlayerStats {
     elo: f64,
     games: u32,
     wins: u32,
+    #[serde(default)]
+    draws: u32,
     elo_peak: f64,
+    /// Uncertainty of the rating, for rating systems that keep track of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deviation: Option<f64>,
+    /// How erratic the deck's results are, for rating systems that keep track of it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    volatility: Option<f64>,
 }
 
 impl Default for PlayerStats {
@@ -14,7 +25,10 @@ impl Default for PlayerStats {
             elo: 0.0,
             games: 0,
             wins: 0,
+            draws: 0,
             elo_peak: 0.0,
+            deviation: None,
+            volatility: None,
         }
     }
 }
@@ -26,10 +40,25 @@ impl PlayerStats {
             elo,
             games: 0,
             wins: 0,
+            draws: 0,
             elo_peak: elo,
+            deviation: None,
+            volatility: None,
         }
     }
 
+    #[must_use]
+    pub const fn with_deviation(mut self, deviation: f64) -> Self {
+        self.deviation = Some(deviation);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_volatility(mut self, volatility: f64) -> Self {
+        self.volatility = Some(volatility);
+        self
+    }
+
     #[must_use]
     pub const fn elo(&self) -> f64 {
         self.elo
@@ -45,6 +74,11 @@ impl PlayerStats {
         self.wins
     }
 
+    #[must_use]
+    pub const fn draws(&self) -> u32 {
+        self.draws
+    }
+
     #[must_use]
     pub fn wr(&self) -> Option<f64> {
         (self.games > 0).then(|| f64::from(self.wins) / f64::from(self.games))
@@ -55,20 +89,56 @@ impl PlayerStats {
         self.elo_peak
     }
 
+    #[must_use]
+    pub const fn deviation(&self) -> Option<f64> {
+        self.deviation
+    }
+
+    #[must_use]
+    pub const fn volatility(&self) -> Option<f64> {
+        self.volatility
+    }
+
+    /// A rating the deck is fairly sure to be above, `elo - k * deviation`. New decks start low
+    /// and climb as their rating becomes more certain. Same as the elo for rating systems without
+    /// a deviation.
+    #[must_use]
+    pub fn conservative_rating(&self) -> f64 {
+        CONSERVATIVE_DEVIATIONS.mul_add(-self.deviation.unwrap_or(0.0), self.elo)
+    }
+
+    pub const fn set_deviation(&mut self, deviation: f64) {
+        self.deviation = Some(deviation);
+    }
+
+    pub const fn set_volatility(&mut self, volatility: f64) {
+        self.volatility = Some(volatility);
+    }
+
     pub fn add_win(&mut self, elo_change: f64) {
         self.games += 1;
         self.wins += 1;
-        self.elo += elo_change;
-        if self.elo > self.elo_peak {
-            self.elo_peak = self.elo;
-        }
+        self.change_elo(elo_change);
     }
 
+    /// Records a loss. Losses usually cost elo, but a deck that survived long enough in a game
+    /// with placements may still gain some.
     pub fn add_loss(&mut self, elo_change: f64) {
         self.games += 1;
-        self.elo -= elo_change;
-        if self.elo < 1.0f64 {
-            self.elo = 1.0f64;
+        self.change_elo(-elo_change);
+    }
+
+    /// Records a shared win. Unlike wins and losses, the elo change may go either way.
+    pub fn add_draw(&mut self, elo_change: f64) {
+        self.games += 1;
+        self.draws += 1;
+        self.change_elo(elo_change);
+    }
+
+    fn change_elo(&mut self, elo_change: f64) {
+        self.elo = (self.elo + elo_change).max(1.0f64);
+        if self.elo > self.elo_peak {
+            self.elo_peak = self.elo;
         }
     }
 }
@@ -92,7 +162,18 @@ impl Tournament {
 
 #[cfg(test)]
 mod tests {
-    use crate::Tournament;
+    use super::CONSERVATIVE_DEVIATIONS;
+    use crate::{Tournament, player::stats::PlayerStats};
+
+    #[test]
+    fn conservative_rating_subtracts_deviations() {
+        let stats = PlayerStats::new(1500.0);
+        assert!(stats.conservative_rating().total_cmp(&1500.0).is_eq());
+
+        let stats = stats.with_deviation(100.0);
+        let expected = CONSERVATIVE_DEVIATIONS.mul_add(-100.0, 1500.0);
+        assert!(stats.conservative_rating().total_cmp(&expected).is_eq());
+    }
 
     #[test]
     fn default_stats_use_starting_elo() {
@@ -130,7 +211,7 @@ mod tests {
         tourn
             .register_record(
                 tourn
-                    .create_match([player_1, player_2, player_3, player_4])
+                    .create_match(&[player_1, player_2, player_3, player_4])
                     .unwrap()
                     .record(player_1)
                     .unwrap(),
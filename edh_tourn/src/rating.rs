@@ -0,0 +1,194 @@
+pub mod batch;
+pub mod glicko;
+
+use core::fmt::Display;
+
+use crate::{
+    config::TournamentConfig, game::match_player::MatchPlayer, player::stats::PlayerStats,
+    rating::glicko::Glicko2,
+};
+
+/// Rating change of a deck at either end of a game
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RatingDelta {
+    /// Rating gained for an outright win
+    pub win: f64,
+    /// Rating lost for a loss with nothing to show for it
+    pub loss: f64,
+}
+
+/// A model of how likely each deck in a pod is to win, and of how ratings move after a game
+pub trait RatingSystem {
+    /// Stats of a deck that hasn't played a game yet
+    fn initial_stats(&self, config: &TournamentConfig) -> PlayerStats {
+        PlayerStats::new(config.starting_elo)
+    }
+
+    /// Chance of each deck in the pod winning, in seat order. The chances sum to 1.
+    fn expected(&self, pod: &[&PlayerStats], config: &TournamentConfig) -> Vec<f64>;
+
+    /// Rating changes of each deck in the pod, in seat order, given their chances of winning
+    /// after any adjustment for turn order
+    fn deltas(
+        &self,
+        pod: &[&PlayerStats],
+        expected: &[f64],
+        config: &TournamentConfig,
+    ) -> Vec<RatingDelta>;
+
+    /// Updates whatever the system keeps besides the rating, such as a deviation, after a deck
+    /// played a game in `pod` for `score`. Does nothing by default.
+    fn update_state(
+        &self,
+        _stats: &mut PlayerStats,
+        _player: &MatchPlayer,
+        _pod: &[MatchPlayer],
+        _score: f64,
+        _config: &TournamentConfig,
+    ) {
+    }
+}
+
+/// Elo blended with win rate, the original rating system.
+///
+/// Each deck's chance of winning is its share of the pod's elo and of the pod's win rate, each
+/// raised to a power and weighted by the config. A win pays out `game_points` scaled by how
+/// unlikely it was.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EloBlend;
+
+impl RatingSystem for EloBlend {
+    #[allow(clippy::cast_precision_loss)]
+    fn expected(&self, pod: &[&PlayerStats], config: &TournamentConfig) -> Vec<f64> {
+        let default_wr = 1.0 / pod.len() as f64;
+        let scaled = pod
+            .iter()
+            .map(|stats| {
+                (
+                    stats.elo().powf(config.game_elo_pow_scale),
+                    stats
+                        .wr()
+                        .unwrap_or(default_wr)
+                        .powf(config.game_wr_pow_scale),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let sum_elo = scaled.iter().map(|(elo, _)| elo).sum::<f64>();
+        let sum_wr = scaled.iter().map(|(_, wr)| wr).sum::<f64>();
+
+        let weight_total = config.game_wr_weight + config.game_elo_weight;
+        let coef_wr = config.game_wr_weight / weight_total;
+        let coef_elo = config.game_elo_weight / weight_total / sum_elo;
+
+        scaled
+            .into_iter()
+            .map(|(elo, wr)| {
+                // A pod that has never won between them, such as two winless decks, splits the
+                // win rate part evenly
+                let wr_share = if sum_wr > 0.0 {
+                    wr / sum_wr
+                } else {
+                    default_wr
+                };
+                coef_wr.mul_add(wr_share, coef_elo * elo)
+            })
+            .collect()
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn deltas(
+        &self,
+        pod: &[&PlayerStats],
+        expected: &[f64],
+        config: &TournamentConfig,
+    ) -> Vec<RatingDelta> {
+        // Share of the pod that loses a game, so that one win pays for every loss
+        let pod_size = pod.len() as f64;
+        let loss_share = (pod_size - 1.0) / pod_size;
+        expected
+            .iter()
+            .map(|expected| RatingDelta {
+                win: config.game_points * (1.0 - expected) / loss_share,
+                loss: config.game_points * expected / loss_share,
+            })
+            .collect()
+    }
+}
+
+/// Which rating system a tournament uses
+#[derive(
+    Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash, Default,
+)]
+pub enum RatingSystemKind {
+    /// See [`EloBlend`]
+    #[default]
+    #[serde(rename = "eb", alias = "EloBlend")]
+    EloBlend,
+    /// See [`Glicko2`]
+    #[serde(rename = "g2", alias = "Glicko2")]
+    Glicko2,
+}
+
+impl RatingSystemKind {
+    pub const VALUES: [Self; 2] = [Self::EloBlend, Self::Glicko2];
+
+    #[must_use]
+    pub const fn system(self) -> &'static dyn RatingSystem {
+        match self {
+            Self::EloBlend => &EloBlend,
+            Self::Glicko2 => &Glicko2,
+        }
+    }
+}
+
+impl Display for RatingSystemKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::EloBlend => "Elo + Win Rate",
+            Self::Glicko2 => "Glicko-2",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Tournament,
+        config::TournamentConfig,
+        rating::{EloBlend, RatingSystem, RatingSystemKind},
+    };
+
+    #[test]
+    fn rating_system_is_serialized() {
+        let config = TournamentConfig::default();
+        let text = ron::to_string(&config).unwrap();
+        assert!(text.contains("rs:eb"));
+
+        let config: TournamentConfig = ron::from_str("(se:1000.0)").unwrap();
+        assert_eq!(RatingSystemKind::EloBlend, config.rating_system);
+        let config: TournamentConfig = ron::from_str("(rating_system:EloBlend)").unwrap();
+        assert_eq!(RatingSystemKind::EloBlend, config.rating_system);
+    }
+
+    #[test]
+    fn expected_sums_to_one() {
+        let tourn = Tournament::generate_tournament(8, 24).unwrap();
+        let pod = tourn.stats.values().take(5).collect::<Vec<_>>();
+        for kind in RatingSystemKind::VALUES {
+            let expected = kind.system().expected(&pod, tourn.config());
+            assert_relative_eq!(1.0, expected.iter().sum::<f64>(), epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn elo_blend_win_pays_for_losses() {
+        let tourn = Tournament::generate_tournament(8, 24).unwrap();
+        let pod = tourn.stats.values().take(5).collect::<Vec<_>>();
+        let expected = EloBlend.expected(&pod, tourn.config());
+        let deltas = EloBlend.deltas(&pod, &expected, tourn.config());
+        let paid = deltas.iter().map(|delta| delta.win).sum::<f64>();
+        let lost = deltas.iter().map(|delta| delta.loss).sum::<f64>();
+        assert_relative_eq!(paid / 4.0, lost, epsilon = 1e-9);
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use crate::Tournament;
+
+/// Settings for [`Tournament::batch_ratings_with`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchSettings {
+    /// Most passes over the history before giving up on converging
+    pub max_iterations: usize,
+    /// Largest change of any rating between two passes, in elo, for the fit to count as converged
+    pub tolerance: f64,
+    /// Games each deck is assumed to have split evenly against a deck at the starting elo. Keeps
+    /// decks that never won, or never lost, at a finite rating.
+    pub prior_games: f64,
+}
+
+impl Default for BatchSettings {
+    fn default() -> Self {
+        Self {
+            max_iterations: 1000,
+            tolerance: 0.01,
+            prior_games: 2.0,
+        }
+    }
+}
+
+/// Ratings fitted to the whole history at once, see [`Tournament::batch_ratings`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRatings {
+    ratings: HashMap<u32, f64>,
+    iterations: usize,
+    converged: bool,
+}
+
+impl BatchRatings {
+    #[must_use]
+    pub fn rating(&self, id: u32) -> Option<f64> {
+        self.ratings.get(&id).copied()
+    }
+
+    #[must_use]
+    pub const fn ratings(&self) -> &HashMap<u32, f64> {
+        &self.ratings
+    }
+
+    #[must_use]
+    pub const fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Whether the ratings settled within the tolerance before running out of iterations
+    #[must_use]
+    pub const fn converged(&self) -> bool {
+        self.converged
+    }
+}
+
+/// One pick of a Plackett–Luce ranking: the decks in `chosen` finished ahead of the rest of
+/// `seats`. Tied decks split the pick.
+struct Stage {
+    seats: Vec<u32>,
+    chosen: Vec<u32>,
+}
+
+impl Tournament {
+    /// Ratings fitted to every game at once with the default [`BatchSettings`]. Unlike the elo,
+    /// these don't depend on the order the games were entered in.
+    #[must_use]
+    pub fn batch_ratings(&self) -> BatchRatings {
+        self.batch_ratings_with(&BatchSettings::default())
+    }
+
+    /// Fits a Plackett–Luce model to every game by maximum likelihood, using the MM algorithm.
+    ///
+    /// Each game is read as a series of picks by finishing order: the winners out of the whole
+    /// pod, then each placed deck out of the decks left. Decks without a recorded place tie for
+    /// last, so they add nothing beyond having lost. Strengths are reported on the elo scale, with
+    /// the starting elo for a deck as strong as the prior.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn batch_ratings_with(&self, settings: &BatchSettings) -> BatchRatings {
+        let stages = self.batch_stages();
+
+        let mut wins = self
+            .players
+            .keys()
+            .map(|id| (*id, settings.prior_games / 2.0))
+            .collect::<HashMap<_, _>>();
+        for stage in &stages {
+            let share = 1.0 / stage.chosen.len() as f64;
+            for id in &stage.chosen {
+                *wins.entry(*id).or_default() += share;
+            }
+        }
+
+        let mut strengths = wins.keys().map(|id| (*id, 1.0)).collect::<HashMap<_, _>>();
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < settings.max_iterations && !converged {
+            iterations += 1;
+
+            let mut exposure = strengths
+                .iter()
+                .map(|(id, strength)| (*id, settings.prior_games / (strength + 1.0)))
+                .collect::<HashMap<_, _>>();
+            for stage in &stages {
+                let total = stage
+                    .seats
+                    .iter()
+                    .map(|id| strengths.get(id).copied().unwrap_or(1.0))
+                    .sum::<f64>();
+                for id in &stage.seats {
+                    *exposure.entry(*id).or_default() += 1.0 / total;
+                }
+            }
+
+            let mut largest_change = 0.0_f64;
+            for (id, strength) in &mut strengths {
+                let (Some(wins), Some(exposure)) = (wins.get(id), exposure.get(id)) else {
+                    continue;
+                };
+                if *exposure <= 0.0 {
+                    continue;
+                }
+                let updated = (wins / exposure).max(f64::MIN_POSITIVE);
+                largest_change = largest_change.max((400.0 * (updated / *strength).log10()).abs());
+                *strength = updated;
+            }
+            converged = largest_change < settings.tolerance;
+        }
+
+        BatchRatings {
+            ratings: strengths
+                .into_iter()
+                .map(|(id, strength)| {
+                    (
+                        id,
+                        400.0f64.mul_add(strength.log10(), self.config.starting_elo),
+                    )
+                })
+                .collect(),
+            iterations,
+            converged,
+        }
+    }
+
+    /// Every pick made in the history, see [`Stage`]
+    fn batch_stages(&self) -> Vec<Stage> {
+        let mut stages = Vec::new();
+        for game in &self.games {
+            let Some(order) = game.finish_order() else {
+                continue;
+            };
+            let ids = game.ids();
+            let id_of = |seat: &usize| ids.get(*seat).copied();
+
+            let mut remaining = ids.clone();
+            // The last group has no one left to finish ahead of
+            for group in order.iter().take(order.len().saturating_sub(1)) {
+                let chosen = group.iter().filter_map(id_of).collect::<Vec<_>>();
+                stages.push(Stage {
+                    seats: remaining.clone(),
+                    chosen: chosen.clone(),
+                });
+                for id in chosen {
+                    if let Some(index) = remaining.iter().position(|seat| *seat == id) {
+                        remaining.swap_remove(index);
+                    }
+                }
+            }
+        }
+        stages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        game::{entry::GameEntry, outcome::GameOutcome},
+        rating::batch::BatchSettings,
+    };
+
+    #[test]
+    fn batch_ratings_ignore_game_order() {
+        let tourn = Tournament::generate_tournament(8, 64).unwrap();
+        let mut reversed = tourn.clone();
+        let mut entries = reversed.split_games_off(0);
+        entries.reverse();
+        reversed.replay_games(entries).unwrap();
+
+        let ratings = tourn.batch_ratings();
+        let reversed_ratings = reversed.batch_ratings();
+        assert!(ratings.converged());
+        for (id, rating) in ratings.ratings() {
+            assert!(rating.is_finite());
+            assert_relative_eq!(
+                *rating,
+                reversed_ratings.rating(*id).unwrap(),
+                epsilon = 0.1
+            );
+        }
+        assert_ne!(tourn.stats, reversed.stats);
+    }
+
+    #[test]
+    fn winning_deck_rates_highest() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for _ in 0..10 {
+            tourn
+                .register_entry(GameEntry::new(ids.clone(), GameOutcome::Win(ids[0])).unwrap())
+                .unwrap();
+        }
+        tourn
+            .register_entry(GameEntry::new(ids.clone(), GameOutcome::Win(ids[1])).unwrap())
+            .unwrap();
+
+        let ratings = tourn.batch_ratings();
+        assert!(ratings.converged());
+        let best = ids
+            .iter()
+            .max_by(|a, b| {
+                let rating = |id: &u32| ratings.rating(*id).unwrap();
+                rating(a).total_cmp(&rating(b))
+            })
+            .unwrap();
+        assert_eq!(ids[0], *best);
+        assert!(ratings.rating(ids[1]) > ratings.rating(ids[2]));
+        assert_relative_eq!(
+            ratings.rating(ids[2]).unwrap(),
+            ratings.rating(ids[3]).unwrap(),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn placements_separate_losers() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        for _ in 0..4 {
+            let entry = GameEntry::new(ids.clone(), GameOutcome::Win(ids[0]))
+                .unwrap()
+                .with_placements(vec![ids[0], ids[1], ids[2]])
+                .unwrap();
+            tourn.register_entry(entry).unwrap();
+        }
+        let ratings = tourn.batch_ratings();
+        assert!(ratings.rating(ids[1]) > ratings.rating(ids[2]));
+        assert!(ratings.rating(ids[2]) > ratings.rating(ids[3]));
+    }
+
+    #[test]
+    fn no_games_keeps_starting_elo() {
+        let tourn = Tournament::generate_tournament(5, 0).unwrap();
+        let ratings = tourn.batch_ratings();
+        assert_eq!(5, ratings.ratings().len());
+        for rating in ratings.ratings().values() {
+            assert_relative_eq!(tourn.config().starting_elo, *rating, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn stops_at_max_iterations() {
+        let tourn = Tournament::generate_tournament(8, 64).unwrap();
+        let settings = BatchSettings {
+            max_iterations: 1,
+            ..BatchSettings::default()
+        };
+        let ratings = tourn.batch_ratings_with(&settings);
+        assert_eq!(1, ratings.iterations());
+        assert!(!ratings.converged());
+    }
+}
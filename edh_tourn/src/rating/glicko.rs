@@ -0,0 +1,260 @@
+use core::f64::consts::PI;
+
+use crate::{
+    config::TournamentConfig,
+    game::match_player::MatchPlayer,
+    player::stats::PlayerStats,
+    rating::{RatingDelta, RatingSystem},
+};
+
+/// Converts between ratings and the Glicko-2 scale
+const SCALE: f64 = 173.7178;
+/// Precision of the volatility search
+const CONVERGENCE: f64 = 1e-6;
+/// Most steps of the volatility search, in case it doesn't converge
+const MAX_ITERATIONS: usize = 100;
+
+/// Multiplayer Glicko-2.
+///
+/// Each deck has a rating and a deviation saying how sure the rating is. A pod is treated as one
+/// game where the winner is picked from a Plackett–Luce model: each deck's chance of winning is
+/// its share of `exp(g * rating)`, where `g` shrinks the differences between decks the less
+/// certain the pod's ratings are. Decks with a large deviation move a lot, and settle down as
+/// they play. Each game counts as a rating period, so the deviation grows back a little by the
+/// volatility before every game.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Glicko2;
+
+impl Glicko2 {
+    /// Deviation on the Glicko-2 scale, grown by the volatility ahead of a game
+    fn pre_game_phi(stats: &PlayerStats, config: &TournamentConfig) -> f64 {
+        let phi = stats.deviation().unwrap_or(config.starting_deviation) / SCALE;
+        phi.hypot(stats.volatility().unwrap_or(config.starting_volatility))
+    }
+
+    /// Weight of rating differences in a pod. A pod of two works out to Glicko's `g` of both
+    /// deviations.
+    #[allow(clippy::cast_precision_loss)]
+    fn g(pod: &[&PlayerStats], config: &TournamentConfig) -> f64 {
+        let phi_sq = pod
+            .iter()
+            .map(|stats| Self::pre_game_phi(stats, config).powi(2))
+            .sum::<f64>()
+            * 2.0
+            / pod.len() as f64;
+        1.0 / (3.0 * phi_sq / PI.powi(2) + 1.0).sqrt()
+    }
+
+    /// Deviation after a game on the Glicko-2 scale. It doesn't depend on the result.
+    fn post_game_phi(phi: f64, g: f64, expected: f64) -> f64 {
+        1.0 / (g * g * expected)
+            .mul_add(1.0 - expected, phi.powi(-2))
+            .sqrt()
+    }
+}
+
+impl RatingSystem for Glicko2 {
+    fn initial_stats(&self, config: &TournamentConfig) -> PlayerStats {
+        PlayerStats::new(config.starting_elo)
+            .with_deviation(config.starting_deviation)
+            .with_volatility(config.starting_volatility)
+    }
+
+    fn expected(&self, pod: &[&PlayerStats], config: &TournamentConfig) -> Vec<f64> {
+        let g = Self::g(pod, config);
+        let top = pod
+            .iter()
+            .map(|stats| stats.elo())
+            .fold(f64::NEG_INFINITY, f64::max);
+        let strengths = pod
+            .iter()
+            .map(|stats| (g * (stats.elo() - top) / SCALE).exp())
+            .collect::<Vec<_>>();
+        let total = strengths.iter().sum::<f64>();
+        strengths
+            .into_iter()
+            .map(|strength| strength / total)
+            .collect()
+    }
+
+    fn deltas(
+        &self,
+        pod: &[&PlayerStats],
+        expected: &[f64],
+        config: &TournamentConfig,
+    ) -> Vec<RatingDelta> {
+        let g = Self::g(pod, config);
+        pod.iter()
+            .zip(expected)
+            .map(|(stats, expected)| {
+                let phi = Self::post_game_phi(Self::pre_game_phi(stats, config), g, *expected);
+                let step = SCALE * phi * phi * g;
+                RatingDelta {
+                    win: step * (1.0 - expected),
+                    loss: step * expected,
+                }
+            })
+            .collect()
+    }
+
+    /// Shrinks the deviation and updates the volatility from how surprising the result was. The
+    /// new volatility applies from the deck's next game.
+    fn update_state(
+        &self,
+        stats: &mut PlayerStats,
+        player: &MatchPlayer,
+        pod: &[MatchPlayer],
+        score: f64,
+        config: &TournamentConfig,
+    ) {
+        let pod = pod.iter().map(MatchPlayer::stats).collect::<Vec<_>>();
+        let g = Self::g(&pod, config);
+        let expected = *player.expected();
+        let old = player.stats();
+
+        let phi = Self::post_game_phi(Self::pre_game_phi(old, config), g, expected);
+        stats.set_deviation(phi * SCALE);
+
+        let information = g * g * expected * (1.0 - expected);
+        if information <= 0.0 {
+            return;
+        }
+        let variance = 1.0 / information;
+        let change = variance * g * (score - expected);
+        stats.set_volatility(new_volatility(
+            old.deviation().unwrap_or(config.starting_deviation) / SCALE,
+            variance,
+            change,
+            old.volatility().unwrap_or(config.starting_volatility),
+            config.volatility_tau,
+        ));
+    }
+}
+
+/// Finds the new volatility with the Illinois algorithm, from step 5 of the Glicko-2 paper
+fn new_volatility(phi: f64, variance: f64, change: f64, sigma: f64, tau: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let tau_sq = tau * tau;
+    let spread = phi.mul_add(phi, variance);
+    let excess = change.mul_add(change, -spread);
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (excess - ex) / (2.0 * (spread + ex).powi(2)) - (x - a) / tau_sq
+    };
+
+    let mut lower = a;
+    let mut upper = if excess > 0.0 {
+        excess.ln()
+    } else {
+        let mut k = 1.0_f64;
+        while f(k.mul_add(-tau, a)) < 0.0 && k < 100.0 {
+            k += 1.0;
+        }
+        k.mul_add(-tau, a)
+    };
+
+    let mut f_lower = f(lower);
+    let mut f_upper = f(upper);
+    for _ in 0..MAX_ITERATIONS {
+        if (upper - lower).abs() <= CONVERGENCE || (f_upper - f_lower).abs() <= f64::EPSILON {
+            break;
+        }
+        let next = (lower - upper) * f_lower / (f_upper - f_lower) + lower;
+        let f_next = f(next);
+        if f_next * f_upper <= 0.0 {
+            lower = upper;
+            f_lower = f_upper;
+        } else {
+            f_lower /= 2.0;
+        }
+        upper = next;
+        f_upper = f_next;
+    }
+    (lower / 2.0).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        config::TournamentConfig,
+        game::{entry::GameEntry, outcome::GameOutcome},
+        rating::{RatingSystem, RatingSystemKind, glicko::Glicko2},
+    };
+
+    fn glicko_tournament(players: usize, games: usize) -> Tournament {
+        let mut tourn = Tournament::generate_tournament(players, games).unwrap();
+        let config = TournamentConfig {
+            rating_system: RatingSystemKind::Glicko2,
+            ..tourn.config().clone()
+        };
+        tourn.set_config(config).unwrap();
+        tourn
+    }
+
+    #[test]
+    fn volatility_matches_paper_example() {
+        // The worked example from the Glicko-2 paper
+        let sigma = super::new_volatility(1.1513, 1.7785, -0.4834, 0.06, 0.5);
+        assert_relative_eq!(0.05999, sigma, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn deviation_shrinks_with_games() {
+        let tourn = glicko_tournament(6, 40);
+        let start = tourn.default_stats().deviation().unwrap();
+        for stats in tourn.stats.values() {
+            assert!(stats.deviation().unwrap() < start);
+            assert!(stats.volatility().is_some());
+            assert!(stats.conservative_rating() < stats.elo());
+        }
+    }
+
+    #[test]
+    fn new_decks_move_more() {
+        let mut tourn = glicko_tournament(5, 40);
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let new = tourn.register_player("New".to_owned()).unwrap();
+        let pod = [new, ids[0], ids[1], ids[2]];
+        let matchup = tourn.create_match(&pod).unwrap();
+        let players = matchup.players();
+        assert!(players[0].elo_win() > players[1].elo_win());
+        assert!(players[0].elo_loss() > players[1].elo_loss());
+
+        tourn
+            .register_entry(GameEntry::new(pod.to_vec(), GameOutcome::Win(new)).unwrap())
+            .unwrap();
+        let stats = tourn.get_player_stats(new).unwrap();
+        assert!(stats.deviation() < tourn.default_stats().deviation());
+        assert_relative_eq!(
+            tourn.default_stats().elo() + players[0].elo_win(),
+            stats.elo(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn uncertainty_evens_out_expected() {
+        let config = TournamentConfig {
+            rating_system: RatingSystemKind::Glicko2,
+            ..TournamentConfig::default()
+        };
+        let strong = Glicko2.initial_stats(&config);
+        let mut strong = strong.with_deviation(60.0);
+        strong.add_win(300.0);
+        let weak = Glicko2.initial_stats(&config).with_deviation(60.0);
+        let certain = Glicko2.expected(&[&strong, &weak, &weak, &weak], &config);
+
+        let strong = strong.with_deviation(300.0);
+        let weak = weak.with_deviation(300.0);
+        let uncertain = Glicko2.expected(&[&strong, &weak, &weak, &weak], &config);
+
+        assert_relative_eq!(1.0, uncertain.iter().sum::<f64>(), epsilon = 1e-9);
+        assert!(certain[0] > uncertain[0]);
+        assert!(uncertain[0] > uncertain[1]);
+    }
+}
@@ -1,15 +1,26 @@
-use core::hash::BuildHasher;
+use core::{fmt, hash::BuildHasher};
 use std::collections::{BTreeMap, HashMap};
 
-use serde::{Serialize, Serializer};
+use serde::{
+    Deserializer, Serialize, Serializer,
+    de::{SeqAccess, Visitor},
+};
 
 use crate::{
     Tournament,
     config::TournamentConfig,
     error::TournamentError,
-    game::{entry::GameEntry, record::GameRecord},
+    game::{
+        details::{GameDetails, WinCondition},
+        elimination::Elimination,
+        entry::GameEntry,
+        outcome::GameOutcome,
+        record::GameRecord,
+    },
+    matches::constraints::MatchConstraints,
+    pilot::PilotInfo,
     player::info::PlayerInfo,
-    player::stats::PlayerStats,
+    rating::RatingSystemKind,
 };
 
 /// For use with serde's ``serialize_with`` attribute
@@ -28,21 +39,364 @@ pub fn convert_games<S>(items: &[GameRecord], serializer: S) -> Result<S::Ok, S:
 where
     S: Serializer,
 {
-    let values = items
-        .iter()
-        .flat_map(|record| GameEntry::new(record.ids(), record.winner()))
-        .collect::<Vec<_>>();
+    let values = items.iter().map(GameRecord::to_entry).collect::<Vec<_>>();
     values.serialize(serializer)
 }
 
+struct PodIdsVisitor;
+
+impl<'de> Visitor<'de> for PodIdsVisitor {
+    type Value = Vec<u32>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence of player ids")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut ids = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(id) = seq.next_element()? {
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+}
+
+/// For use with serde's ``deserialize_with`` attribute. Reads the player ids of a game as either
+/// a list or a tuple, as older files stored games as fixed size four player tuples.
+pub fn pod_ids<'de, D>(deserializer: D) -> Result<Vec<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(PodIdsVisitor)
+}
+
+/// For use with serde's ``with`` attribute on optional fields that are skipped when empty. Writes
+/// the value without a ``Some`` wrapper, so it reads the same as a required field.
+pub mod bare_option {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[allow(clippy::ref_option)]
+    pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        match value {
+            Some(value) => value.serialize(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        T::deserialize(deserializer).map(Some)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SerdeGameEntry {
+    #[serde(rename = "p", alias = "players", deserialize_with = "pod_ids")]
+    players: Vec<u32>,
+    #[serde(
+        rename = "w",
+        alias = "winner",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    winner: Option<u32>,
+    #[serde(
+        rename = "d",
+        alias = "draw",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    draw: Vec<u32>,
+    #[serde(
+        rename = "f",
+        alias = "first",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    first: Option<usize>,
+    #[serde(
+        rename = "r",
+        alias = "placements",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    placements: Vec<u32>,
+    #[serde(
+        rename = "t",
+        alias = "played_at",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    played_at: Option<u64>,
+    #[serde(
+        rename = "s",
+        alias = "session",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    session: Option<String>,
+    #[serde(
+        rename = "o",
+        alias = "pilots",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pilots: BTreeMap<u32, u32>,
+    #[serde(
+        rename = "tu",
+        alias = "turns",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    turns: Option<u32>,
+    #[serde(
+        rename = "du",
+        alias = "duration",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    duration: Option<u32>,
+    #[serde(
+        rename = "wc",
+        alias = "win_condition",
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "bare_option"
+    )]
+    win_condition: Option<WinCondition>,
+    #[serde(
+        rename = "no",
+        alias = "notes",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    notes: String,
+    #[serde(
+        rename = "el",
+        alias = "eliminations",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    eliminations: Vec<Elimination>,
+}
+
+impl TryFrom<SerdeGameEntry> for GameEntry {
+    type Error = TournamentError;
+
+    fn try_from(value: SerdeGameEntry) -> Result<Self, Self::Error> {
+        let outcome = match (value.winner, value.draw.is_empty()) {
+            (Some(winner), true) => GameOutcome::Win(winner),
+            (None, false) => GameOutcome::Draw(value.draw),
+            (None, true) => GameOutcome::NoResult,
+            (Some(_), false) => return Err(TournamentError::InvalidDraw),
+        };
+        let details = GameDetails {
+            played_at: value.played_at,
+            session: value.session,
+            pilots: value.pilots,
+            turns: value.turns,
+            duration: value.duration,
+            win_condition: value.win_condition,
+            notes: value.notes,
+            eliminations: value.eliminations,
+        };
+        let entry = Self::new(value.players, outcome)?;
+        let entry = match value.first {
+            Some(first) => entry.with_first(first)?,
+            None => entry,
+        };
+        Ok(entry
+            .with_placements(value.placements)?
+            .with_details(details))
+    }
+}
+
+impl From<GameEntry> for SerdeGameEntry {
+    fn from(value: GameEntry) -> Self {
+        let (winner, draw) = match value.outcome() {
+            GameOutcome::Win(winner) => (Some(*winner), Vec::new()),
+            GameOutcome::Draw(draw) => (None, draw.clone()),
+            GameOutcome::NoResult => (None, Vec::new()),
+        };
+        Self {
+            players: value.players().to_vec(),
+            winner,
+            draw,
+            first: value.first(),
+            placements: value.placements().to_vec(),
+            played_at: value.details().played_at(),
+            session: value.details().session().map(ToOwned::to_owned),
+            pilots: value.details().pilots.clone(),
+            turns: value.details().turns(),
+            duration: value.details().duration(),
+            win_condition: value.details().win_condition(),
+            notes: value.details().notes().to_owned(),
+            eliminations: value.details().eliminations().to_vec(),
+        }
+    }
+}
+
+/// Reads a [`TournamentConfig`], including files from before the match weights were keyed by
+/// criterion id
+#[derive(serde::Deserialize)]
+#[serde(default)]
+pub struct SerdeConfig {
+    #[serde(rename = "se", alias = "starting_elo")]
+    starting_elo: f64,
+    #[serde(rename = "rs", alias = "rating_system")]
+    rating_system: RatingSystemKind,
+    #[serde(rename = "sd", alias = "starting_deviation")]
+    starting_deviation: f64,
+    #[serde(rename = "sv", alias = "starting_volatility")]
+    starting_volatility: f64,
+    #[serde(rename = "vt", alias = "volatility_tau")]
+    volatility_tau: f64,
+    #[serde(rename = "gp", alias = "game_points")]
+    game_points: f64,
+    #[serde(rename = "geps", alias = "game_elo_pow_scale")]
+    game_elo_pow_scale: f64,
+    #[serde(rename = "gwps", alias = "game_wr_pow_scale")]
+    game_wr_pow_scale: f64,
+    #[serde(rename = "gew", alias = "game_elo_weight")]
+    game_elo_weight: f64,
+    #[serde(rename = "gww", alias = "game_wr_weight")]
+    game_wr_weight: f64,
+    #[serde(rename = "gpw", alias = "game_placement_weight")]
+    game_placement_weight: f64,
+    #[serde(rename = "sa", alias = "seat_advantage")]
+    seat_advantage: bool,
+    #[serde(rename = "sag", alias = "seat_advantage_min_games")]
+    seat_advantage_min_games: u32,
+    #[serde(rename = "mw", alias = "match_weights")]
+    match_weights: BTreeMap<String, f64>,
+    #[serde(
+        rename = "mwlp",
+        alias = "match_weight_least_played",
+        deserialize_with = "bare_option::deserialize"
+    )]
+    least_played: Option<f64>,
+    #[serde(
+        rename = "mwn",
+        alias = "match_weight_nemesis",
+        deserialize_with = "bare_option::deserialize"
+    )]
+    nemesis: Option<f64>,
+    #[serde(
+        rename = "mwlw",
+        alias = "match_weight_lost_with",
+        deserialize_with = "bare_option::deserialize"
+    )]
+    lost_with: Option<f64>,
+    #[serde(
+        rename = "mwln",
+        alias = "match_weight_neighbor",
+        alias = "mwne",
+        alias = "match_weight_elo_neighbor",
+        deserialize_with = "bare_option::deserialize"
+    )]
+    elo_neighbor: Option<f64>,
+    #[serde(
+        rename = "mwwn",
+        alias = "match_weight_wr_neighbor",
+        deserialize_with = "bare_option::deserialize"
+    )]
+    wr_neighbor: Option<f64>,
+    #[serde(
+        rename = "mwen",
+        alias = "match_weight_expected_neighbor",
+        deserialize_with = "bare_option::deserialize"
+    )]
+    expected_neighbor: Option<f64>,
+}
+
+impl Default for SerdeConfig {
+    fn default() -> Self {
+        let config = TournamentConfig::default();
+        Self {
+            starting_elo: config.starting_elo,
+            rating_system: config.rating_system,
+            starting_deviation: config.starting_deviation,
+            starting_volatility: config.starting_volatility,
+            volatility_tau: config.volatility_tau,
+            game_points: config.game_points,
+            game_elo_pow_scale: config.game_elo_pow_scale,
+            game_wr_pow_scale: config.game_wr_pow_scale,
+            game_elo_weight: config.game_elo_weight,
+            game_wr_weight: config.game_wr_weight,
+            game_placement_weight: config.game_placement_weight,
+            seat_advantage: config.seat_advantage,
+            seat_advantage_min_games: config.seat_advantage_min_games,
+            match_weights: config.match_weights,
+            least_played: None,
+            nemesis: None,
+            lost_with: None,
+            elo_neighbor: None,
+            wr_neighbor: None,
+            expected_neighbor: None,
+        }
+    }
+}
+
+impl From<SerdeConfig> for TournamentConfig {
+    fn from(value: SerdeConfig) -> Self {
+        let mut config = Self {
+            starting_elo: value.starting_elo,
+            rating_system: value.rating_system,
+            starting_deviation: value.starting_deviation,
+            starting_volatility: value.starting_volatility,
+            volatility_tau: value.volatility_tau,
+            game_points: value.game_points,
+            game_elo_pow_scale: value.game_elo_pow_scale,
+            game_wr_pow_scale: value.game_wr_pow_scale,
+            game_elo_weight: value.game_elo_weight,
+            game_wr_weight: value.game_wr_weight,
+            game_placement_weight: value.game_placement_weight,
+            seat_advantage: value.seat_advantage,
+            seat_advantage_min_games: value.seat_advantage_min_games,
+            match_weights: value.match_weights,
+        };
+        let legacy_weights = [
+            ("least_played", value.least_played),
+            ("nemesis", value.nemesis),
+            ("lost_with", value.lost_with),
+            ("elo_neighbor", value.elo_neighbor),
+            ("wr_neighbor", value.wr_neighbor),
+            ("expected_neighbor", value.expected_neighbor),
+        ];
+        for (id, weight) in legacy_weights {
+            if let Some(weight) = weight {
+                config.set_match_weight(id, weight);
+            }
+        }
+        config
+    }
+}
+
 #[derive(serde::Deserialize)]
 pub struct SerdeTournament {
     #[serde(alias = "c")]
     config: TournamentConfig,
     #[serde(alias = "p")]
     players: HashMap<u32, PlayerInfo>,
+    #[serde(alias = "pl", default)]
+    pilots: HashMap<u32, PilotInfo>,
     #[serde(alias = "g")]
     games: Vec<GameEntry>,
+    #[serde(alias = "mc", default)]
+    constraints: MatchConstraints,
 }
 
 impl TryFrom<SerdeTournament> for Tournament {
@@ -55,18 +409,18 @@ impl TryFrom<SerdeTournament> for Tournament {
             .collect();
 
         let mut tournament = Self {
-            default_stats: PlayerStats::new(value.config.starting_elo),
+            default_stats: value.config.initial_stats(),
             config: value.config,
-            stats: HashMap::new(),
             players: value.players,
             player_names,
-            games: Vec::new(),
-            snapshot: 0,
+            pilots: value.pilots,
+            ..Self::default()
         };
 
         for game in value.games {
             tournament.register_entry(game)?;
         }
+        tournament.set_constraints(value.constraints)?;
 
         tournament.snapshot = 0;
 
@@ -76,7 +430,18 @@ impl TryFrom<SerdeTournament> for Tournament {
 
 #[cfg(test)]
 mod tests {
-    use crate::Tournament;
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        game::{
+            elimination::{Elimination, Eliminator},
+            entry::GameEntry,
+            outcome::GameOutcome,
+        },
+    };
 
     #[test]
     fn ron_serialize_loop() {
@@ -108,6 +473,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn deserialize_legacy_four_player_games() {
+        let legacy = ron::to_string(&Tournament::sample_game())
+            .unwrap()
+            .replace("p:[", "p:(")
+            .replace("],w:", "),w:");
+        assert!(legacy.contains("p:("));
+        let tourn: Tournament = ron::from_str(&legacy).unwrap();
+        assert_eq!(Tournament::sample_game(), tourn);
+    }
+
+    #[test]
+    fn serialize_loop_mixed_pod_sizes() {
+        let mut tourn = Tournament::generate_tournament(6, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let outcomes = [
+            GameOutcome::Win(ids[0]),
+            GameOutcome::Draw(vec![ids[1], ids[2]]),
+            GameOutcome::NoResult,
+            GameOutcome::Win(ids[5]),
+        ];
+        for (size, outcome) in [3, 4, 5, 6].into_iter().zip(outcomes) {
+            let matchup = tourn.create_match(&ids[..size]).unwrap();
+            let record = matchup.record_outcome(outcome).unwrap();
+            tourn.register_record(record).unwrap();
+        }
+
+        let ron: Tournament = ron::from_str(&ron::to_string(&tourn).unwrap()).unwrap();
+        let json: Tournament =
+            serde_json::from_str(&serde_json::to_string(&tourn).unwrap()).unwrap();
+        let toml: Tournament = toml::from_str(&toml::to_string(&tourn).unwrap()).unwrap();
+        for de_tourn in [ron, json, toml] {
+            assert_eq!(tourn.games().len(), de_tourn.games().len());
+            for (a, b) in tourn.games().iter().zip(de_tourn.games()) {
+                assert_eq!(a.ids(), b.ids());
+                assert_eq!(a.outcome(), b.outcome());
+            }
+        }
+    }
+
+    #[test]
+    fn serialize_loop_pilots() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let ids = tourn.players().keys().copied().sorted().collect_vec();
+        let alice = tourn.register_pilot("Alice".to_owned()).unwrap();
+        let bob = tourn.register_pilot("Bob".to_owned()).unwrap();
+        tourn.set_deck_owner(ids[0], Some(alice)).unwrap();
+        let mut entry = GameEntry::new(ids.clone(), GameOutcome::Win(ids[0])).unwrap();
+        entry.details_mut().pilots.insert(ids[0], bob);
+        entry.details_mut().eliminations = vec![
+            Elimination::new(ids[1], Eliminator::Deck(ids[0])).with_turn(5),
+            Elimination::new(ids[2], Eliminator::Table),
+        ];
+        tourn.register_entry(entry).unwrap();
+
+        let ron: Tournament = ron::from_str(&ron::to_string(&tourn).unwrap()).unwrap();
+        let json: Tournament =
+            serde_json::from_str(&serde_json::to_string(&tourn).unwrap()).unwrap();
+        let toml: Tournament = toml::from_str(&toml::to_string(&tourn).unwrap()).unwrap();
+        for de_tourn in [ron, json, toml] {
+            assert_eq!(tourn.pilots(), de_tourn.pilots());
+            assert_eq!(tourn.players(), de_tourn.players());
+            assert_eq!(tourn.games()[0].details(), de_tourn.games()[0].details());
+        }
+    }
+
     #[test]
     fn deserialize_populates_player_table() {
         let mut tourn = Tournament::sample_game();
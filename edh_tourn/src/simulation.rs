@@ -0,0 +1,351 @@
+use itertools::Itertools;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::{
+    Tournament,
+    error::TournamentError,
+    game::{entry::GameEntry, outcome::GameOutcome},
+    matches::constraints::MatchConstraints,
+};
+
+/// Settings for [`Tournament::simulate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationSettings {
+    /// Games the pod plays in each run
+    pub games: usize,
+    /// Runs to sample
+    pub runs: usize,
+    /// Seed for the random outcomes. The same seed gives the same simulation.
+    pub seed: u64,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            games: 5,
+            runs: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// Summary of a value sampled once per run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Spread {
+    pub mean: f64,
+    pub std_dev: f64,
+    /// 5th percentile
+    pub low: f64,
+    pub median: f64,
+    /// 95th percentile
+    pub high: f64,
+}
+
+impl Spread {
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    fn of(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(f64::total_cmp);
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+        let percentile = |p: f64| {
+            let index = (p * (count - 1.0)).round() as usize;
+            samples.get(index).copied().unwrap_or(mean)
+        };
+        Self {
+            mean,
+            std_dev: variance.sqrt(),
+            low: percentile(0.05),
+            median: percentile(0.5),
+            high: percentile(0.95),
+        }
+    }
+}
+
+/// How one deck of the pod fared across the runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeckSimulation {
+    pub id: u32,
+    /// Change in elo over each run
+    pub rating_change: Spread,
+    /// Average games won per run
+    pub wins: f64,
+    /// Share of the runs the deck won the most games in, split between decks that tied
+    pub series_wins: f64,
+}
+
+/// Result of [`Tournament::simulate`], with the decks in seat order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    pub settings: SimulationSettings,
+    pub decks: Vec<DeckSimulation>,
+}
+
+impl Simulation {
+    /// The deck most likely to win the series
+    #[must_use]
+    pub fn favorite(&self) -> Option<&DeckSimulation> {
+        self.decks
+            .iter()
+            .max_by(|a, b| a.series_wins.total_cmp(&b.series_wins))
+    }
+}
+
+/// A pod ready to be simulated, holding only the decks of the pod and what recording their games
+/// needs. The game history is left out, so it is cheap to make and to send to another thread.
+#[derive(Debug, Clone)]
+pub struct PodSimulator {
+    tournament: Tournament,
+    pod: Vec<u32>,
+    first: Option<usize>,
+}
+
+impl PodSimulator {
+    /// Plays the pod again and again on copies of the pod's decks, picking each game's winner at
+    /// random by its expected results. Each game goes through [`Tournament::register_entry`], so
+    /// the ratings move between the games of a run just like they would for real.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn run(&self, settings: &SimulationSettings) -> Result<Simulation, TournamentError> {
+        let (base, pod, first) = (&self.tournament, &self.pod, self.first);
+        let mut rng = StdRng::seed_from_u64(settings.seed);
+        let mut rating_changes = vec![Vec::with_capacity(settings.runs); pod.len()];
+        let mut wins = vec![0.0; pod.len()];
+        let mut series_wins = vec![0.0; pod.len()];
+
+        for _ in 0..settings.runs {
+            let mut tourn = base.clone();
+            let mut run_wins = vec![0_usize; pod.len()];
+            for _ in 0..settings.games {
+                let matchup = tourn.create_seated_match(pod, first)?;
+                let expected = matchup
+                    .players()
+                    .iter()
+                    .map(|p| *p.expected())
+                    .collect_vec();
+                let seat = sample_seat(&expected, &mut rng);
+                let winner = pod.get(seat).copied().unwrap_or_default();
+                if let Some(count) = run_wins.get_mut(seat) {
+                    *count += 1;
+                }
+
+                let entry = GameEntry::new(pod.clone(), GameOutcome::Win(winner))?;
+                let entry = match first {
+                    Some(first) => entry.with_first(first)?,
+                    None => entry,
+                };
+                tourn.register_entry(entry)?;
+            }
+
+            let most = run_wins.iter().copied().max().unwrap_or_default();
+            let leaders = run_wins.iter().filter(|count| **count == most).count() as f64;
+            for (seat, id) in pod.iter().enumerate() {
+                let count = run_wins.get(seat).copied().unwrap_or_default();
+                let change = tourn.get_player_or_default_stats(*id).elo()
+                    - base.get_player_or_default_stats(*id).elo();
+                if let Some(changes) = rating_changes.get_mut(seat) {
+                    changes.push(change);
+                }
+                if let Some(total) = wins.get_mut(seat) {
+                    *total += count as f64;
+                }
+                if count == most
+                    && let Some(total) = series_wins.get_mut(seat)
+                {
+                    *total += 1.0 / leaders;
+                }
+            }
+        }
+
+        let runs = settings.runs.max(1) as f64;
+        let decks = itertools::izip!(pod, rating_changes, wins, series_wins)
+            .map(|(id, changes, wins, series_wins)| DeckSimulation {
+                id: *id,
+                rating_change: Spread::of(changes),
+                wins: wins / runs,
+                series_wins: series_wins / runs,
+            })
+            .collect();
+
+        Ok(Simulation {
+            settings: *settings,
+            decks,
+        })
+    }
+}
+
+impl Tournament {
+    /// [`Tournament::without_history`] with only the decks of the pod, so the copy is cheap to
+    /// send to another thread to simulate on. Match constraints are left out.
+    #[must_use]
+    pub(crate) fn pod_copy(&self, pod: &[u32]) -> Self {
+        let mut copy = self.without_history();
+        copy.players.retain(|id, _| pod.contains(id));
+        copy.stats.retain(|id, _| pod.contains(id));
+        copy.player_names.retain(|_, id| pod.contains(id));
+        copy.constraints = MatchConstraints::new();
+        copy
+    }
+
+    /// Sets up the pod, seated in order, to be simulated with [`PodSimulator::run`]
+    pub fn pod_simulator(
+        &self,
+        pod: &[u32],
+        first: Option<usize>,
+    ) -> Result<PodSimulator, TournamentError> {
+        if let Some(id) = pod.iter().duplicates().next() {
+            return Err(TournamentError::DuplicatePlayer(*id));
+        }
+        // Fails early on an invalid pod
+        self.create_seated_match(pod, first)?;
+        Ok(PodSimulator {
+            tournament: self.pod_copy(pod),
+            pod: pod.to_vec(),
+            first,
+        })
+    }
+
+    /// Simulates the pod with [`PodSimulator::run`]. The tournament itself isn't changed.
+    pub fn simulate(
+        &self,
+        pod: &[u32],
+        first: Option<usize>,
+        settings: &SimulationSettings,
+    ) -> Result<Simulation, TournamentError> {
+        self.pod_simulator(pod, first)?.run(settings)
+    }
+}
+
+/// Picks a seat with chances in proportion to its expected result
+fn sample_seat<R: Rng + ?Sized>(expected: &[f64], rng: &mut R) -> usize {
+    let total = expected.iter().map(|p| p.max(0.0)).sum::<f64>();
+    if total <= 0.0 {
+        return rng.random_range(0..expected.len().max(1));
+    }
+    let mut roll = rng.random::<f64>() * total;
+    for (seat, p) in expected.iter().enumerate() {
+        roll -= p.max(0.0);
+        if roll < 0.0 {
+            return seat;
+        }
+    }
+    expected.len().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use itertools::Itertools;
+
+    use crate::{
+        Tournament,
+        error::TournamentError,
+        game::{entry::GameEntry, outcome::GameOutcome},
+        simulation::SimulationSettings,
+    };
+
+    fn pod(tourn: &Tournament) -> Vec<u32> {
+        tourn.players().keys().copied().sorted().take(4).collect()
+    }
+
+    #[test]
+    fn same_seed_same_simulation() {
+        let tourn = Tournament::generate_tournament(6, 30).unwrap();
+        let pod = pod(&tourn);
+        let settings = SimulationSettings {
+            runs: 50,
+            ..SimulationSettings::default()
+        };
+        let simulation = tourn.simulate(&pod, Some(0), &settings).unwrap();
+        assert_eq!(
+            simulation,
+            tourn.simulate(&pod, Some(0), &settings).unwrap()
+        );
+
+        let reseeded = SimulationSettings {
+            seed: 1,
+            ..settings
+        };
+        assert_ne!(
+            simulation,
+            tourn.simulate(&pod, Some(0), &reseeded).unwrap()
+        );
+    }
+
+    #[test]
+    fn shares_add_up() {
+        let tourn = Tournament::generate_tournament(6, 30).unwrap();
+        let pod = pod(&tourn);
+        let settings = SimulationSettings {
+            games: 3,
+            runs: 200,
+            seed: 7,
+        };
+        let simulation = tourn.simulate(&pod, None, &settings).unwrap();
+        assert_eq!(pod, simulation.decks.iter().map(|d| d.id).collect_vec());
+        let wins = simulation.decks.iter().map(|d| d.wins).sum::<f64>();
+        let series_wins = simulation.decks.iter().map(|d| d.series_wins).sum::<f64>();
+        assert_relative_eq!(3.0, wins, epsilon = 1e-9);
+        assert_relative_eq!(1.0, series_wins, epsilon = 1e-9);
+        for deck in &simulation.decks {
+            let change = deck.rating_change;
+            assert!(change.low <= change.median && change.median <= change.high);
+        }
+        assert_eq!(30, tourn.games().len());
+    }
+
+    #[test]
+    fn stronger_deck_is_favored() {
+        let mut tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let pod = pod(&tourn);
+        for _ in 0..10 {
+            tourn
+                .register_entry(GameEntry::new(pod.clone(), GameOutcome::Win(pod[2])).unwrap())
+                .unwrap();
+        }
+        let simulation = tourn
+            .simulate(&pod, None, &SimulationSettings::default())
+            .unwrap();
+        assert_eq!(pod[2], simulation.favorite().unwrap().id);
+        assert!(simulation.decks[2].wins > simulation.decks[0].wins);
+    }
+
+    #[test]
+    fn pod_copy_simulates_the_same() {
+        let mut tourn = Tournament::generate_tournament(6, 30).unwrap();
+        let mut config = tourn.config().clone();
+        config.seat_advantage = true;
+        config.seat_advantage_min_games = 0;
+        tourn.set_config(config).unwrap();
+        let pod = pod(&tourn);
+        let copy = tourn.pod_copy(&pod);
+        assert!(copy.games().is_empty());
+        assert_eq!(4, copy.players().len());
+
+        let settings = SimulationSettings {
+            runs: 50,
+            ..SimulationSettings::default()
+        };
+        assert_eq!(
+            tourn.simulate(&pod, Some(1), &settings).unwrap(),
+            copy.simulate(&pod, Some(1), &settings).unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_repeated_decks() {
+        let tourn = Tournament::generate_tournament(4, 0).unwrap();
+        let id = pod(&tourn)[0];
+        assert!(matches!(
+            tourn.simulate(&[id, id, id, id], None, &SimulationSettings::default()),
+            Err(TournamentError::DuplicatePlayer(_))
+        ));
+    }
+}
@@ -1,30 +1,35 @@
 use crate::{
     Tournament,
     error::{TournResult, TournamentError},
-    game::entry::GameEntry,
+    game::{entry::GameEntry, outcome::GameOutcome},
 };
 
 impl Tournament {
+    /// Parses tab separated games, one game per line. Each line lists the decks in the pod followed
+    /// by the winner in the last cell, so pods of any supported size can be mixed. Empty cells
+    /// after the decks are ignored, but a blank deck or winner cell is an error.
     pub fn from_tsv_games(text: &str) -> TournResult<Self> {
         let mut tourn = Self::new();
-        for line in text.lines() {
-            let mut parts = line.split('\t');
-            // Grabs names
-            let a_name = parts.next().ok_or(TournamentError::NotEnoughPlayers)?;
-            let b_name = parts.next().ok_or(TournamentError::NotEnoughPlayers)?;
-            let c_name = parts.next().ok_or(TournamentError::NotEnoughPlayers)?;
-            let d_name = parts.next().ok_or(TournamentError::NotEnoughPlayers)?;
-            let w_name = parts.next().ok_or(TournamentError::NotEnoughPlayers)?;
+        for (line_no, line) in text.lines().enumerate() {
+            let mut names = line.split('\t').map(str::trim).collect::<Vec<_>>();
+
+            let w_name = names.pop().ok_or(TournamentError::NotEnoughPlayers)?;
+            while names.last().is_some_and(|name| name.is_empty()) {
+                names.pop();
+            }
+            if w_name.is_empty() || names.iter().any(|name| name.is_empty()) {
+                return Err(TournamentError::BlankCell(line_no + 1));
+            }
 
             // Get IDS
-            let a_id = tourn.get_or_register_player(a_name.to_owned())?;
-            let b_id = tourn.get_or_register_player(b_name.to_owned())?;
-            let c_id = tourn.get_or_register_player(c_name.to_owned())?;
-            let d_id = tourn.get_or_register_player(d_name.to_owned())?;
+            let ids = names
+                .into_iter()
+                .map(|name| tourn.get_or_register_player(name.to_owned()))
+                .collect::<TournResult<Vec<_>>>()?;
             let w_id = tourn.get_or_register_player(w_name.to_owned())?;
 
             // Create record
-            let record = GameEntry::new([a_id, b_id, c_id, d_id], w_id)?;
+            let record = GameEntry::new(ids, GameOutcome::Win(w_id))?;
 
             // Register record
             tourn.register_entry(record)?;
@@ -36,7 +41,7 @@ impl Tournament {
 
 #[cfg(test)]
 mod tests {
-    use crate::Tournament;
+    use crate::{Tournament, error::TournamentError};
 
     #[test]
     fn parse_tsv_game_count() {
@@ -45,4 +50,27 @@ mod tests {
         let records = Tournament::from_tsv_games(tsv).unwrap();
         assert_eq!(game_count, records.games().len());
     }
+
+    #[test]
+    fn parse_tsv_variable_pod_sizes() {
+        let tsv = "a\tb\tc\ta\nd\te\tf\tg\th\te\na\tb\tc\td\t\t\tb";
+        let tourn = Tournament::from_tsv_games(tsv).unwrap();
+        let sizes = tourn.games().iter().map(|game| game.ids().len());
+        assert!(sizes.eq([3, 5, 4]));
+    }
+
+    #[test]
+    fn parse_tsv_rejects_blank_cells() {
+        let res = Tournament::from_tsv_games("a\tb\tc\ta\na\tb\tc\td\t");
+        assert!(matches!(res, Err(TournamentError::BlankCell(2))));
+
+        let res = Tournament::from_tsv_games("a\t\tc\td\ta");
+        assert!(matches!(res, Err(TournamentError::BlankCell(1))));
+    }
+
+    #[test]
+    fn parse_tsv_rejects_small_pods() {
+        let res = Tournament::from_tsv_games("a\tb\ta");
+        assert!(matches!(res, Err(TournamentError::InvalidPodSize(2))));
+    }
 }
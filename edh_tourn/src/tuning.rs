@@ -0,0 +1,434 @@
+use core::fmt::Display;
+
+use crate::{
+    Tournament, config::TournamentConfig, error::TournamentError, game::record::GameRecord,
+    rating::RatingSystemKind,
+};
+
+/// Smallest chance a prediction is given when scoring it, so a sure prediction that turned out
+/// wrong costs a lot rather than infinitely much
+const MIN_PROBABILITY: f64 = 1e-15;
+
+/// Scores of one seat's expected result against its result, a share of a win from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SeatScore {
+    /// Squared error of the expected result
+    pub brier: f64,
+    /// Minus the log of the chance given to the seat winning, for the share it won
+    pub win_log_loss: f64,
+    /// Minus the log of the chance given to the seat losing, for the share it lost
+    pub loss_log_loss: f64,
+}
+
+impl SeatScore {
+    pub(crate) fn new(expected: f64, actual: f64) -> Self {
+        Self {
+            brier: (expected - actual).powi(2),
+            win_log_loss: -actual * expected.max(MIN_PROBABILITY).ln(),
+            loss_log_loss: -(1.0 - actual) * (1.0 - expected).max(MIN_PROBABILITY).ln(),
+        }
+    }
+}
+
+/// How a prediction is scored against the result. Lower scores are better for both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ScoringRule {
+    /// Minus the log of the chance given to the actual winner
+    #[default]
+    LogLoss,
+    /// Squared error of the chances given to every seat
+    Brier,
+}
+
+impl ScoringRule {
+    pub const VALUES: [Self; 2] = [Self::LogLoss, Self::Brier];
+}
+
+impl Display for ScoringRule {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::LogLoss => write!(f, "Log-loss"),
+            Self::Brier => write!(f, "Brier"),
+        }
+    }
+}
+
+/// How well the expected results of a set of games predicted their outcomes, averaged over the
+/// games with a result
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PredictionScore {
+    pub games: usize,
+    /// Minus the log of the chances given to the winners of each game, so only one side of each
+    /// seat is scored. The calibration report scores seats on both sides instead, see
+    /// `CalibrationBucket::log_loss`.
+    pub log_loss: f64,
+    /// Squared error of the chances given to every seat, summed over each game
+    pub brier: f64,
+}
+
+impl PredictionScore {
+    #[must_use]
+    pub const fn get(&self, rule: ScoringRule) -> f64 {
+        match rule {
+            ScoringRule::LogLoss => self.log_loss,
+            ScoringRule::Brier => self.brier,
+        }
+    }
+
+    /// Scores each game's expected results against its seat results. A draw counts as a share of
+    /// a win for each drawing seat.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn of_games<'a, I: IntoIterator<Item = &'a GameRecord>>(games: I) -> Self {
+        let mut score = Self::default();
+        for game in games {
+            let Some(results) = game.seat_results() else {
+                continue;
+            };
+            score.games += 1;
+            // Only the winning seats count towards the log-loss, as the chances add up to 1
+            for (player, result) in game.players().iter().zip(results) {
+                let seat = SeatScore::new(*player.expected(), result.share());
+                score.brier += seat.brier;
+                score.log_loss += seat.win_log_loss;
+            }
+        }
+
+        if score.games > 0 {
+            score.log_loss /= score.games as f64;
+            score.brier /= score.games as f64;
+        }
+        score
+    }
+}
+
+/// A setting of [`TournamentConfig`] that changes the expected results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigKnob {
+    GamePoints,
+    EloPowScale,
+    WrPowScale,
+    /// Share of the win rate in the blend of Elo and win rate, from 0 to 1. Only the ratio of the
+    /// two weights changes the expected results, so they are tuned as one.
+    WrShare,
+    PlacementWeight,
+    StartingDeviation,
+    StartingVolatility,
+    VolatilityTau,
+}
+
+impl ConfigKnob {
+    pub const VALUES: [Self; 8] = [
+        Self::GamePoints,
+        Self::EloPowScale,
+        Self::WrPowScale,
+        Self::WrShare,
+        Self::PlacementWeight,
+        Self::StartingDeviation,
+        Self::StartingVolatility,
+        Self::VolatilityTau,
+    ];
+
+    /// The settings the rating system reads. The others don't change its results.
+    #[must_use]
+    pub const fn for_system(kind: RatingSystemKind) -> &'static [Self] {
+        match kind {
+            RatingSystemKind::EloBlend => &[
+                Self::GamePoints,
+                Self::EloPowScale,
+                Self::WrPowScale,
+                Self::WrShare,
+                Self::PlacementWeight,
+            ],
+            RatingSystemKind::Glicko2 => &[
+                Self::StartingDeviation,
+                Self::StartingVolatility,
+                Self::VolatilityTau,
+            ],
+        }
+    }
+
+    #[must_use]
+    pub fn get(self, config: &TournamentConfig) -> f64 {
+        match self {
+            Self::GamePoints => config.game_points,
+            Self::EloPowScale => config.game_elo_pow_scale,
+            Self::WrPowScale => config.game_wr_pow_scale,
+            Self::WrShare => {
+                let total = config.game_elo_weight + config.game_wr_weight;
+                if total > 0.0 {
+                    config.game_wr_weight / total
+                } else {
+                    0.0
+                }
+            }
+            Self::PlacementWeight => config.game_placement_weight,
+            Self::StartingDeviation => config.starting_deviation,
+            Self::StartingVolatility => config.starting_volatility,
+            Self::VolatilityTau => config.volatility_tau,
+        }
+    }
+
+    /// Sets the value. The win rate share keeps the sum of the two weights, so the saved weights
+    /// stay on the scale they were entered on.
+    pub fn set(self, config: &mut TournamentConfig, value: f64) {
+        match self {
+            Self::GamePoints => config.game_points = value,
+            Self::EloPowScale => config.game_elo_pow_scale = value,
+            Self::WrPowScale => config.game_wr_pow_scale = value,
+            Self::WrShare => {
+                let total = config.game_elo_weight + config.game_wr_weight;
+                let total = if total > 0.0 { total } else { 1.0 };
+                config.game_elo_weight = total * (1.0 - value);
+                config.game_wr_weight = total * value;
+            }
+            Self::PlacementWeight => config.game_placement_weight = value,
+            Self::StartingDeviation => config.starting_deviation = value,
+            Self::StartingVolatility => config.starting_volatility = value,
+            Self::VolatilityTau => config.volatility_tau = value,
+        }
+    }
+
+    /// Values to try on either side of `value`. Most settings are scaled by `1 + step`, while the
+    /// shares between 0 and 1 move by half a step within that range.
+    fn candidates(self, value: f64, step: f64) -> [f64; 2] {
+        match self {
+            Self::WrShare | Self::PlacementWeight => {
+                [(value + step / 2.0).min(1.0), (value - step / 2.0).max(0.0)]
+            }
+            _ if value <= 0.0 => [step, value],
+            _ => [value * (1.0 + step), value / (1.0 + step)],
+        }
+    }
+}
+
+impl Display for ConfigKnob {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::GamePoints => write!(f, "Game Points"),
+            Self::EloPowScale => write!(f, "Elo Power"),
+            Self::WrPowScale => write!(f, "Win Rate Power"),
+            Self::WrShare => write!(f, "Win Rate Share"),
+            Self::PlacementWeight => write!(f, "Placement Weight"),
+            Self::StartingDeviation => write!(f, "Starting Deviation"),
+            Self::StartingVolatility => write!(f, "Starting Volatility"),
+            Self::VolatilityTau => write!(f, "Volatility Tau"),
+        }
+    }
+}
+
+/// Settings for [`Tournament::suggest_config`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TuningSettings {
+    pub rule: ScoringRule,
+    /// Relative change tried first for each setting
+    pub initial_step: f64,
+    /// The search stops once the step has been halved below this
+    pub min_step: f64,
+    /// Most configs to replay the history under
+    pub max_evaluations: usize,
+}
+
+impl Default for TuningSettings {
+    fn default() -> Self {
+        Self {
+            rule: ScoringRule::default(),
+            initial_step: 0.5,
+            min_step: 0.02,
+            max_evaluations: 200,
+        }
+    }
+}
+
+/// The best config found by [`Tournament::suggest_config`], along with how it compares to the
+/// current one
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSuggestion {
+    pub config: TournamentConfig,
+    pub current: PredictionScore,
+    pub suggested: PredictionScore,
+    /// Configs the history was replayed under
+    pub evaluations: usize,
+}
+
+impl ConfigSuggestion {
+    /// Whether the suggested config scores better than the current one
+    #[must_use]
+    pub fn improves(&self, rule: ScoringRule) -> bool {
+        self.suggested.get(rule) < self.current.get(rule)
+    }
+}
+
+impl Tournament {
+    /// How well the expected results of every game predicted their outcomes
+    #[must_use]
+    pub fn prediction_score(&self) -> PredictionScore {
+        PredictionScore::of_games(&self.games)
+    }
+
+    /// Replays the history under another config and scores its predictions
+    pub fn score_config(
+        &self,
+        config: &TournamentConfig,
+    ) -> Result<PredictionScore, TournamentError> {
+        let mut tourn = self.clone();
+        tourn.set_config(config.clone())?;
+        Ok(tourn.prediction_score())
+    }
+
+    /// Searches for the settings of the current rating system that best predict the history, one
+    /// setting at a time. Each setting is nudged up and down, keeping any change that scores
+    /// better, and the nudges get smaller once none of them help. The tournament itself isn't
+    /// changed.
+    pub fn suggest_config(
+        &self,
+        settings: &TuningSettings,
+    ) -> Result<ConfigSuggestion, TournamentError> {
+        let current = self.prediction_score();
+        let mut best = current;
+        let mut config = self.config.clone();
+        let mut evaluations = 1;
+        let mut step = settings.initial_step;
+
+        'search: loop {
+            if step < settings.min_step {
+                break;
+            }
+            let mut improved = false;
+            for &knob in ConfigKnob::for_system(self.config.rating_system) {
+                let value = knob.get(&config);
+                for candidate in knob.candidates(value, step) {
+                    if evaluations >= settings.max_evaluations {
+                        break 'search;
+                    }
+                    if (candidate - value).abs() <= f64::EPSILON {
+                        continue;
+                    }
+
+                    let mut next = config.clone();
+                    knob.set(&mut next, candidate);
+                    let score = self.score_config(&next)?;
+                    evaluations += 1;
+                    if score.get(settings.rule) < best.get(settings.rule) {
+                        best = score;
+                        config = next;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            if !improved {
+                step /= 2.0;
+            }
+        }
+
+        Ok(ConfigSuggestion {
+            config,
+            current,
+            suggested: best,
+            evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Tournament,
+        config::TournamentConfig,
+        game::outcome::GameOutcome,
+        rating::RatingSystemKind,
+        tuning::{ConfigKnob, ScoringRule, TuningSettings},
+    };
+
+    #[test]
+    fn even_pod_scores() {
+        let mut tourn = Tournament::new();
+        let id = tourn.register_player("A".to_owned()).unwrap();
+        let record = tourn
+            .create_match(&[id, id, id, id])
+            .unwrap()
+            .record(id)
+            .unwrap();
+        tourn.register_record(record).unwrap();
+        let record = tourn
+            .create_match(&[id, id, id, id])
+            .unwrap()
+            .record_outcome(GameOutcome::NoResult)
+            .unwrap();
+        tourn.register_record(record).unwrap();
+
+        let score = tourn.prediction_score();
+        assert_eq!(1, score.games);
+        assert_relative_eq!(4.0f64.ln(), score.log_loss, epsilon = 1e-9);
+        assert_relative_eq!(0.75, score.brier, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn suggestion_never_scores_worse() {
+        let tourn = Tournament::generate_tournament(8, 64).unwrap();
+        for rule in ScoringRule::VALUES {
+            let settings = TuningSettings {
+                rule,
+                max_evaluations: 40,
+                ..TuningSettings::default()
+            };
+            let suggestion = tourn.suggest_config(&settings).unwrap();
+            assert!(suggestion.evaluations <= 40);
+            assert!(suggestion.suggested.get(rule) <= suggestion.current.get(rule));
+            assert_eq!(tourn.prediction_score(), suggestion.current);
+
+            let score = tourn.score_config(&suggestion.config).unwrap();
+            assert_relative_eq!(suggestion.suggested.get(rule), score.get(rule));
+        }
+    }
+
+    #[test]
+    fn glicko_tunes_its_own_settings() {
+        let mut tourn = Tournament::generate_tournament(8, 48).unwrap();
+        let mut config = tourn.config().clone();
+        config.rating_system = RatingSystemKind::Glicko2;
+        tourn.set_config(config.clone()).unwrap();
+
+        let settings = TuningSettings {
+            max_evaluations: 30,
+            ..TuningSettings::default()
+        };
+        let suggestion = tourn.suggest_config(&settings).unwrap();
+        assert!(suggestion.evaluations > 1);
+        for knob in ConfigKnob::for_system(RatingSystemKind::EloBlend) {
+            assert_relative_eq!(knob.get(&config), knob.get(&suggestion.config));
+        }
+        let score = tourn.score_config(&suggestion.config).unwrap();
+        assert_relative_eq!(
+            suggestion.suggested.get(settings.rule),
+            score.get(settings.rule)
+        );
+    }
+
+    #[test]
+    fn shares_stay_in_range() {
+        for knob in [ConfigKnob::PlacementWeight, ConfigKnob::WrShare] {
+            for (value, step) in [(0.75, 0.5), (0.25, 1.0), (0.0, 2.0)] {
+                for candidate in knob.candidates(value, step) {
+                    assert!((0.0..=1.0).contains(&candidate));
+                }
+            }
+        }
+        let [up, down] = ConfigKnob::WrPowScale.candidates(0.0, 1.5);
+        assert_relative_eq!(1.5, up);
+        assert_relative_eq!(0.0, down);
+    }
+
+    #[test]
+    fn wr_share_keeps_total_weight() {
+        let mut config = TournamentConfig::default();
+        let total = config.game_elo_weight + config.game_wr_weight;
+        assert_relative_eq!(0.35, ConfigKnob::WrShare.get(&config));
+
+        ConfigKnob::WrShare.set(&mut config, 0.5);
+        assert_relative_eq!(0.5, ConfigKnob::WrShare.get(&config));
+        assert_relative_eq!(total, config.game_elo_weight + config.game_wr_weight);
+        assert_relative_eq!(config.game_elo_weight, config.game_wr_weight);
+    }
+}